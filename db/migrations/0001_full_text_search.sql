@@ -0,0 +1,81 @@
+-- Indices de texto completo para la busqueda global. Son tablas de contenido
+-- externo: el texto vive en la tabla original y los triggers mantienen el
+-- indice sincronizado.
+
+CREATE VIRTUAL TABLE ClientFts USING fts5(
+    client_name,
+    content = 'Client',
+    content_rowid = 'id_client',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER client_fts_insert AFTER INSERT ON Client BEGIN
+    INSERT INTO ClientFts(rowid, client_name) VALUES (new.id_client, new.client_name);
+END;
+
+CREATE TRIGGER client_fts_delete AFTER DELETE ON Client BEGIN
+    INSERT INTO ClientFts(ClientFts, rowid, client_name)
+        VALUES ('delete', old.id_client, old.client_name);
+END;
+
+CREATE TRIGGER client_fts_update AFTER UPDATE OF client_name ON Client BEGIN
+    INSERT INTO ClientFts(ClientFts, rowid, client_name)
+        VALUES ('delete', old.id_client, old.client_name);
+    INSERT INTO ClientFts(rowid, client_name) VALUES (new.id_client, new.client_name);
+END;
+
+CREATE VIRTUAL TABLE SiteFts USING fts5(
+    site_key,
+    site_name,
+    site_municipality,
+    content = 'Site',
+    content_rowid = 'id_site',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER site_fts_insert AFTER INSERT ON Site BEGIN
+    INSERT INTO SiteFts(rowid, site_key, site_name, site_municipality)
+        VALUES (new.id_site, new.site_key, new.site_name, new.site_municipality);
+END;
+
+CREATE TRIGGER site_fts_delete AFTER DELETE ON Site BEGIN
+    INSERT INTO SiteFts(SiteFts, rowid, site_key, site_name, site_municipality)
+        VALUES ('delete', old.id_site, old.site_key, old.site_name, old.site_municipality);
+END;
+
+CREATE TRIGGER site_fts_update AFTER UPDATE OF site_key, site_name, site_municipality ON Site BEGIN
+    INSERT INTO SiteFts(SiteFts, rowid, site_key, site_name, site_municipality)
+        VALUES ('delete', old.id_site, old.site_key, old.site_name, old.site_municipality);
+    INSERT INTO SiteFts(rowid, site_key, site_name, site_municipality)
+        VALUES (new.id_site, new.site_key, new.site_name, new.site_municipality);
+END;
+
+CREATE VIRTUAL TABLE SampleFts USING fts5(
+    control_number,
+    project,
+    content = 'Sample',
+    content_rowid = 'id_sample',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER sample_fts_insert AFTER INSERT ON Sample BEGIN
+    INSERT INTO SampleFts(rowid, control_number, project)
+        VALUES (new.id_sample, new.control_number, new.project);
+END;
+
+CREATE TRIGGER sample_fts_delete AFTER DELETE ON Sample BEGIN
+    INSERT INTO SampleFts(SampleFts, rowid, control_number, project)
+        VALUES ('delete', old.id_sample, old.control_number, old.project);
+END;
+
+CREATE TRIGGER sample_fts_update AFTER UPDATE OF control_number, project ON Sample BEGIN
+    INSERT INTO SampleFts(SampleFts, rowid, control_number, project)
+        VALUES ('delete', old.id_sample, old.control_number, old.project);
+    INSERT INTO SampleFts(rowid, control_number, project)
+        VALUES (new.id_sample, new.control_number, new.project);
+END;
+
+-- Indexar los registros que ya existian antes de la migracion
+INSERT INTO ClientFts(ClientFts) VALUES ('rebuild');
+INSERT INTO SiteFts(SiteFts) VALUES ('rebuild');
+INSERT INTO SampleFts(SampleFts) VALUES ('rebuild');
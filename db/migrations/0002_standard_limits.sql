@@ -0,0 +1,4 @@
+-- Limites permisibles de cada analisis dentro de una norma; NULL si la norma
+-- no fija ese extremo
+ALTER TABLE StandardDetail ADD COLUMN min_value REAL;
+ALTER TABLE StandardDetail ADD COLUMN max_value REAL;
@@ -0,0 +1,15 @@
+-- Los numeros de control repetidos que ya existan conservan el primero y a
+-- los demas se les agrega su id para poder crear el indice UNIQUE
+UPDATE Sample
+SET control_number = control_number || '-' || id_sample
+WHERE id_sample NOT IN (
+    SELECT MIN(id_sample) FROM Sample GROUP BY control_number
+);
+
+CREATE UNIQUE INDEX SampleControlNumber ON Sample(control_number);
+
+-- Ultimo consecutivo asignado en cada año
+CREATE TABLE ControlNumberSequence(
+    seq_year INTEGER PRIMARY KEY,
+    last_value INTEGER NOT NULL
+);
@@ -0,0 +1,159 @@
+-- Fecha de alta y de ultimo cambio en todas las tablas, en UTC con el mismo
+-- formato que usa rusqlite para DateTime<Utc>. Las filas existentes toman la
+-- fecha de la migracion porque no se sabe cuando se crearon.
+
+ALTER TABLE Logs ADD COLUMN created_at TEXT;
+ALTER TABLE Logs ADD COLUMN updated_at TEXT;
+UPDATE Logs SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER LogsTimestamps AFTER INSERT ON Logs
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Logs SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Standard ADD COLUMN created_at TEXT;
+ALTER TABLE Standard ADD COLUMN updated_at TEXT;
+UPDATE Standard SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER StandardTimestamps AFTER INSERT ON Standard
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Standard SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Signatary ADD COLUMN created_at TEXT;
+ALTER TABLE Signatary ADD COLUMN updated_at TEXT;
+UPDATE Signatary SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SignataryTimestamps AFTER INSERT ON Signatary
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Signatary SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Parameter ADD COLUMN created_at TEXT;
+ALTER TABLE Parameter ADD COLUMN updated_at TEXT;
+UPDATE Parameter SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER ParameterTimestamps AFTER INSERT ON Parameter
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Parameter SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Client ADD COLUMN created_at TEXT;
+ALTER TABLE Client ADD COLUMN updated_at TEXT;
+UPDATE Client SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER ClientTimestamps AFTER INSERT ON Client
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Client SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Site ADD COLUMN created_at TEXT;
+ALTER TABLE Site ADD COLUMN updated_at TEXT;
+UPDATE Site SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SiteTimestamps AFTER INSERT ON Site
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Site SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Analysis ADD COLUMN created_at TEXT;
+ALTER TABLE Analysis ADD COLUMN updated_at TEXT;
+UPDATE Analysis SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER AnalysisTimestamps AFTER INSERT ON Analysis
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Analysis SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE StandardDetail ADD COLUMN created_at TEXT;
+ALTER TABLE StandardDetail ADD COLUMN updated_at TEXT;
+UPDATE StandardDetail SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER StandardDetailTimestamps AFTER INSERT ON StandardDetail
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE StandardDetail SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE SignataryDetail ADD COLUMN created_at TEXT;
+ALTER TABLE SignataryDetail ADD COLUMN updated_at TEXT;
+UPDATE SignataryDetail SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SignataryDetailTimestamps AFTER INSERT ON SignataryDetail
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE SignataryDetail SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Sampler ADD COLUMN created_at TEXT;
+ALTER TABLE Sampler ADD COLUMN updated_at TEXT;
+UPDATE Sampler SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SamplerTimestamps AFTER INSERT ON Sampler
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Sampler SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE Sample ADD COLUMN created_at TEXT;
+ALTER TABLE Sample ADD COLUMN updated_at TEXT;
+UPDATE Sample SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SampleTimestamps AFTER INSERT ON Sample
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE Sample SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
+
+ALTER TABLE SampleResult ADD COLUMN created_at TEXT;
+ALTER TABLE SampleResult ADD COLUMN updated_at TEXT;
+UPDATE SampleResult SET
+    created_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+    updated_at = strftime('%Y-%m-%d %H:%M:%f+00:00', 'now');
+CREATE TRIGGER SampleResultTimestamps AFTER INSERT ON SampleResult
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE SampleResult SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
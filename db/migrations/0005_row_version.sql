@@ -0,0 +1,15 @@
+-- Version de cada fila: empieza en 1 y aumenta con cada cambio. `modify`
+-- solo actualiza si la version no cambio desde que se cargo la fila.
+
+ALTER TABLE Logs ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Standard ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Signatary ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Parameter ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Client ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Site ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Analysis ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE StandardDetail ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE SignataryDetail ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Sampler ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE Sample ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE SampleResult ADD COLUMN row_version INTEGER NOT NULL DEFAULT 1;
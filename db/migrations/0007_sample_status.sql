@@ -0,0 +1,19 @@
+-- Estado de cada muestra en el laboratorio e historial de sus cambios. Las
+-- muestras existentes empiezan como recibidas.
+
+ALTER TABLE Sample ADD COLUMN sample_status TEXT NOT NULL DEFAULT 'received';
+
+CREATE TABLE SampleTransition(
+    id_transition INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_sample INTEGER NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    transitioned_at TEXT NOT NULL,
+    FOREIGN KEY (id_sample)
+        REFERENCES Sample(id_sample)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX SampleTransitionSample ON SampleTransition(id_sample);
+CREATE INDEX SampleStatus ON Sample(sample_status);
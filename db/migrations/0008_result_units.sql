@@ -0,0 +1,3 @@
+-- Unidad en que se guarda cada resultado. NULL en los resultados anteriores:
+-- se toman en la unidad de su norma.
+ALTER TABLE SampleResult ADD COLUMN result_unit TEXT;
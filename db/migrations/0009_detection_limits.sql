@@ -0,0 +1,3 @@
+-- Limite de deteccion de cada analisis; los resultados "ND" lo toman como
+-- limite. NULL si el analisis no lo tiene registrado.
+ALTER TABLE Analysis ADD COLUMN detection_limit REAL;
@@ -0,0 +1,4 @@
+-- Rol de cada usuario: admin, reviewer, analyst, sampler o read_only. Los
+-- usuarios existentes quedan de solo lectura hasta que un administrador les
+-- asigne otro rol; mientras no haya administradores no se pide inicio de sesion.
+ALTER TABLE Signatary ADD COLUMN role TEXT NOT NULL DEFAULT 'read_only';
@@ -0,0 +1,17 @@
+-- Firma electronica de resultados: quien firmo, cuando y el hash SHA-256 de
+-- los datos firmados. Un resultado se firma una sola vez y despues ya no se
+-- puede modificar; la verificacion vuelve a calcular el hash.
+
+CREATE TABLE ResultSignature(
+    id_signature INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_sample_result INTEGER NOT NULL UNIQUE,
+    id_signatary INTEGER NOT NULL,
+    signed_at TEXT NOT NULL,
+    payload_hash TEXT NOT NULL,
+    FOREIGN KEY (id_sample_result)
+        REFERENCES SampleResult(id_sample_result)
+        ON DELETE RESTRICT,
+    FOREIGN KEY (id_signatary)
+        REFERENCES Signatary(id_signatary)
+        ON DELETE RESTRICT
+);
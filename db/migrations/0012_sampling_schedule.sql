@@ -0,0 +1,66 @@
+-- Programa de muestreo: monitoreos periodicos contratados por los clientes en
+-- sus sitios, con los analisis a realizar y las visitas planeadas. Una visita
+-- se completa cuando se registra la muestra tomada en ella.
+
+CREATE TABLE SamplingSchedule(
+    id_schedule INTEGER PRIMARY KEY AUTOINCREMENT,
+    schedule_active INTEGER NOT NULL,
+    id_site INTEGER NOT NULL,
+    frequency TEXT NOT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE,
+    id_sampler INTEGER,
+    project TEXT NOT NULL,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (id_site)
+        REFERENCES Site(id_site)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_sampler)
+        REFERENCES Sampler(id_sampler)
+        ON DELETE SET NULL
+        ON UPDATE CASCADE
+);
+
+CREATE TABLE ScheduleAnalysis(
+    id_schedule_analysis INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_schedule INTEGER NOT NULL,
+    id_analysis INTEGER NOT NULL,
+    UNIQUE (id_schedule, id_analysis),
+    FOREIGN KEY (id_schedule)
+        REFERENCES SamplingSchedule(id_schedule)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+
+CREATE TABLE SamplingVisit(
+    id_visit INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_schedule INTEGER NOT NULL,
+    planned_date DATE NOT NULL,
+    id_sampler INTEGER,
+    id_sample INTEGER UNIQUE,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1,
+    UNIQUE (id_schedule, planned_date),
+    FOREIGN KEY (id_schedule)
+        REFERENCES SamplingSchedule(id_schedule)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_sampler)
+        REFERENCES Sampler(id_sampler)
+        ON DELETE SET NULL
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_sample)
+        REFERENCES Sample(id_sample)
+        ON DELETE SET NULL
+        ON UPDATE CASCADE
+);
+
+CREATE INDEX SamplingVisitDate ON SamplingVisit(planned_date);
@@ -0,0 +1,98 @@
+-- Cotizaciones: precio de cada analisis, paquetes de analisis, descuento por
+-- cliente y cotizaciones que al aceptarse se convierten en muestras. Los
+-- importes se guardan en centavos.
+
+ALTER TABLE Analysis ADD COLUMN price INTEGER;
+ALTER TABLE Client ADD COLUMN discount_percent REAL NOT NULL DEFAULT 0;
+
+CREATE TABLE AnalysisPackage(
+    id_package INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_active INTEGER NOT NULL,
+    package_name TEXT NOT NULL,
+    -- NULL: la suma de los precios de sus analisis
+    package_price INTEGER,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE PackageDetail(
+    id_package_detail INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_package INTEGER NOT NULL,
+    id_analysis INTEGER NOT NULL,
+    UNIQUE (id_package, id_analysis),
+    FOREIGN KEY (id_package)
+        REFERENCES AnalysisPackage(id_package)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+
+-- El descuento y el IVA se copian al cotizar para que la cotizacion no cambie
+-- si despues cambian los del cliente o la configuracion
+CREATE TABLE Quotation(
+    id_quotation INTEGER PRIMARY KEY AUTOINCREMENT,
+    quotation_active INTEGER NOT NULL,
+    id_client INTEGER NOT NULL,
+    id_site INTEGER,
+    project TEXT NOT NULL,
+    issued_on DATE NOT NULL,
+    valid_until DATE NOT NULL,
+    discount_percent REAL NOT NULL,
+    tax_percent INTEGER NOT NULL,
+    converted_on DATE,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (id_client)
+        REFERENCES Client(id_client)
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_site)
+        REFERENCES Site(id_site)
+        ON DELETE SET NULL
+        ON UPDATE CASCADE
+);
+
+-- Cada partida es un paquete o un analisis suelto con su precio al cotizar;
+-- `samples` es el numero de muestras que llevan esos analisis
+CREATE TABLE QuotationItem(
+    id_item INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_quotation INTEGER NOT NULL,
+    id_package INTEGER,
+    id_analysis INTEGER,
+    description TEXT NOT NULL,
+    samples INTEGER NOT NULL,
+    unit_price INTEGER NOT NULL,
+    CHECK ((id_package IS NULL) <> (id_analysis IS NULL)),
+    FOREIGN KEY (id_quotation)
+        REFERENCES Quotation(id_quotation)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_package)
+        REFERENCES AnalysisPackage(id_package)
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON UPDATE CASCADE
+);
+
+-- Analisis que se esperan de cada muestra
+CREATE TABLE SampleAnalysis(
+    id_sample_analysis INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_sample INTEGER NOT NULL,
+    id_analysis INTEGER NOT NULL,
+    UNIQUE (id_sample, id_analysis),
+    FOREIGN KEY (id_sample)
+        REFERENCES Sample(id_sample)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+
+ALTER TABLE Sample ADD COLUMN id_quotation INTEGER REFERENCES Quotation(id_quotation);
@@ -0,0 +1,56 @@
+-- Facturas de las muestras ya reportadas: una partida por muestra y analisis.
+-- El folio se asigna al emitirla, con un consecutivo por año; los importes se
+-- guardan en centavos y se calculan al crear la factura para que no cambien
+-- si despues cambian los precios, el descuento o el IVA.
+
+CREATE TABLE Invoice(
+    id_invoice INTEGER PRIMARY KEY AUTOINCREMENT,
+    invoice_active INTEGER NOT NULL,
+    invoice_number TEXT UNIQUE,
+    id_client INTEGER NOT NULL,
+    invoice_status TEXT NOT NULL DEFAULT 'draft',
+    drafted_on DATE NOT NULL,
+    issued_on DATE,
+    paid_on DATE,
+    cancelled_on DATE,
+    discount_percent REAL NOT NULL,
+    tax_percent INTEGER NOT NULL,
+    subtotal INTEGER NOT NULL,
+    discount INTEGER NOT NULL,
+    tax INTEGER NOT NULL,
+    total INTEGER NOT NULL,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (id_client)
+        REFERENCES Client(id_client)
+        ON UPDATE CASCADE
+);
+
+CREATE TABLE InvoiceLine(
+    id_line INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_invoice INTEGER NOT NULL,
+    id_sample INTEGER NOT NULL,
+    id_analysis INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    unit_price INTEGER NOT NULL,
+    UNIQUE (id_invoice, id_sample, id_analysis),
+    FOREIGN KEY (id_invoice)
+        REFERENCES Invoice(id_invoice)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_sample)
+        REFERENCES Sample(id_sample)
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON UPDATE CASCADE
+);
+
+CREATE INDEX InvoiceLineSample ON InvoiceLine(id_sample);
+
+-- Ultimo folio asignado en cada año
+CREATE TABLE InvoiceSequence(
+    seq_year INTEGER PRIMARY KEY,
+    last_value INTEGER NOT NULL
+);
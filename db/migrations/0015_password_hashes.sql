@@ -0,0 +1,5 @@
+-- Las contraseñas se guardan como hash Argon2 con sal (formato PHC); las que
+-- siguen en texto plano se cifran con la funcion propia password_hash.
+UPDATE Signatary
+SET passwd = password_hash(passwd)
+WHERE passwd <> '' AND passwd NOT LIKE '$argon2%';
@@ -0,0 +1,19 @@
+-- Un resultado firmado no se modifica ni se borra, tampoco por fuera de
+-- ResultManager. La firma se guarda despues de actualizar el resultado en la
+-- misma transaccion, asi que firmar no choca con los triggers. El mensaje lo
+-- reconoce Error para responder E011_LOCKED.
+CREATE TRIGGER SignedResultNoUpdate BEFORE UPDATE ON SampleResult
+WHEN EXISTS (
+    SELECT 1 FROM ResultSignature WHERE id_sample_result = OLD.id_sample_result
+)
+BEGIN
+    SELECT RAISE(ABORT, 'signed_result_locked');
+END;
+
+CREATE TRIGGER SignedResultNoDelete BEFORE DELETE ON SampleResult
+WHEN EXISTS (
+    SELECT 1 FROM ResultSignature WHERE id_sample_result = OLD.id_sample_result
+)
+BEGIN
+    SELECT RAISE(ABORT, 'signed_result_locked');
+END;
@@ -0,0 +1,15 @@
+-- Una muestra solo puede estar en una factura vigente (activa y no
+-- cancelada). InvoiceManager lo revisa dentro de la transaccion del borrador;
+-- el trigger lo garantiza aunque dos borradores se crucen.
+CREATE TRIGGER InvoiceLineSampleOnce BEFORE INSERT ON InvoiceLine
+WHEN EXISTS (
+    SELECT 1 FROM InvoiceLine
+    JOIN Invoice ON Invoice.id_invoice = InvoiceLine.id_invoice
+    WHERE InvoiceLine.id_sample = NEW.id_sample
+      AND InvoiceLine.id_invoice <> NEW.id_invoice
+      AND Invoice.invoice_active = 1
+      AND Invoice.invoice_status <> 'cancelled'
+)
+BEGIN
+    SELECT RAISE(ABORT, 'sample_already_invoiced');
+END;
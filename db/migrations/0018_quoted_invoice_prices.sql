@@ -0,0 +1,85 @@
+-- Las muestras que salen de una cotizacion se facturan al precio congelado de
+-- su partida y con el descuento de la cotizacion. Cada muestra guarda la
+-- partida de la que salio; en las ya convertidas se busca la partida cuyos
+-- analisis coinciden con los que se esperan de la muestra.
+ALTER TABLE Sample ADD COLUMN id_quotation_item INTEGER
+    REFERENCES QuotationItem(id_item);
+
+WITH ItemAnalysis(id_item, id_analysis) AS (
+    SELECT id_item, id_analysis FROM QuotationItem WHERE id_analysis IS NOT NULL
+    UNION
+    SELECT QuotationItem.id_item, PackageDetail.id_analysis FROM QuotationItem
+    JOIN PackageDetail ON PackageDetail.id_package = QuotationItem.id_package
+)
+UPDATE Sample SET id_quotation_item = (
+    SELECT QuotationItem.id_item FROM QuotationItem
+    WHERE QuotationItem.id_quotation = Sample.id_quotation
+      AND NOT EXISTS (
+          SELECT 1 FROM SampleAnalysis
+          WHERE SampleAnalysis.id_sample = Sample.id_sample
+            AND SampleAnalysis.id_analysis NOT IN (
+                SELECT id_analysis FROM ItemAnalysis
+                WHERE ItemAnalysis.id_item = QuotationItem.id_item
+            )
+      )
+      AND NOT EXISTS (
+          SELECT 1 FROM ItemAnalysis
+          WHERE ItemAnalysis.id_item = QuotationItem.id_item
+            AND ItemAnalysis.id_analysis NOT IN (
+                SELECT id_analysis FROM SampleAnalysis
+                WHERE SampleAnalysis.id_sample = Sample.id_sample
+            )
+      )
+    ORDER BY QuotationItem.id_item
+    LIMIT 1
+)
+WHERE id_quotation IS NOT NULL;
+
+-- La partida de una muestra cotizada es su paquete o analisis de la
+-- cotizacion (id_item), asi que id_analysis deja de ser obligatorio
+CREATE TABLE InvoiceLineNew(
+    id_line INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_invoice INTEGER NOT NULL,
+    id_sample INTEGER NOT NULL,
+    id_analysis INTEGER,
+    id_item INTEGER,
+    description TEXT NOT NULL,
+    unit_price INTEGER NOT NULL,
+    UNIQUE (id_invoice, id_sample, id_analysis),
+    UNIQUE (id_invoice, id_sample, id_item),
+    CHECK ((id_analysis IS NULL) <> (id_item IS NULL)),
+    FOREIGN KEY (id_invoice)
+        REFERENCES Invoice(id_invoice)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_sample)
+        REFERENCES Sample(id_sample)
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_analysis)
+        REFERENCES Analysis(id_analysis)
+        ON UPDATE CASCADE,
+    FOREIGN KEY (id_item)
+        REFERENCES QuotationItem(id_item)
+        ON UPDATE CASCADE
+);
+
+INSERT INTO InvoiceLineNew (id_line, id_invoice, id_sample, id_analysis, description, unit_price)
+SELECT id_line, id_invoice, id_sample, id_analysis, description, unit_price FROM InvoiceLine;
+
+DROP TABLE InvoiceLine;
+ALTER TABLE InvoiceLineNew RENAME TO InvoiceLine;
+
+CREATE INDEX InvoiceLineSample ON InvoiceLine(id_sample);
+
+CREATE TRIGGER InvoiceLineSampleOnce BEFORE INSERT ON InvoiceLine
+WHEN EXISTS (
+    SELECT 1 FROM InvoiceLine
+    JOIN Invoice ON Invoice.id_invoice = InvoiceLine.id_invoice
+    WHERE InvoiceLine.id_sample = NEW.id_sample
+      AND InvoiceLine.id_invoice <> NEW.id_invoice
+      AND Invoice.invoice_active = 1
+      AND Invoice.invoice_status <> 'cancelled'
+)
+BEGIN
+    SELECT RAISE(ABORT, 'sample_already_invoiced');
+END;
@@ -0,0 +1,34 @@
+-- Al borrar definitivamente un cliente se borran sus contactos; sin ON DELETE
+-- la llave foranea rechazaba el borrado
+
+CREATE TABLE ClientContactNew(
+    id_contact INTEGER PRIMARY KEY AUTOINCREMENT,
+    contact_active INTEGER NOT NULL,
+    id_client INTEGER NOT NULL,
+    contact_name TEXT NOT NULL,
+    contact_role TEXT,
+    email TEXT,
+    phone TEXT,
+    created_at TEXT,
+    updated_at TEXT,
+    row_version INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (id_client)
+        REFERENCES Client(id_client)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+
+INSERT INTO ClientContactNew SELECT * FROM ClientContact;
+
+DROP TABLE ClientContact;
+ALTER TABLE ClientContactNew RENAME TO ClientContact;
+
+CREATE INDEX ClientContactClient ON ClientContact(id_client);
+
+CREATE TRIGGER ClientContactTimestamps AFTER INSERT ON ClientContact
+WHEN NEW.created_at IS NULL OR NEW.updated_at IS NULL BEGIN
+    UPDATE ClientContact SET
+        created_at = coalesce(NEW.created_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now')),
+        updated_at = coalesce(NEW.updated_at, strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+    WHERE rowid = NEW.rowid;
+END;
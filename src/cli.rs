@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+use crate::config::{Config, Error};
+use crate::logic::labels::LabelFormat;
+
+// Lo que se ejecuta al iniciar: el menu o un subcomando sin sesion interactiva
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Command {
+    #[default]
+    Menu,
+    Labels(LabelsCommand),
+}
+
+// `labels [--format F] --output ARCHIVO [--reception FECHA] [NUMERO...]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelsCommand {
+    pub control_numbers: Vec<String>,
+    pub reception: Option<NaiveDate>,
+    pub format: LabelFormat,
+    pub output: PathBuf,
+}
+
+// Las banderas de configuracion que esperan valor; el subcomando empieza en
+// la primera palabra `labels` que no sea el valor de una de ellas
+fn takes_value(flag: &str) -> bool {
+    flag.starts_with("--") && !flag.contains('=') && flag != "--tui"
+}
+
+// Separa el subcomando de las banderas de configuracion, que van antes
+pub fn parse(args: Vec<String>) -> Result<(Command, Vec<String>), Error> {
+    let position = (0..args.len())
+        .find(|&index| args[index] == "labels" && (index == 0 || !takes_value(&args[index - 1])));
+    let Some(position) = position else {
+        return Ok((Command::Menu, args));
+    };
+
+    let mut config_args = args;
+    let command_args = config_args.split_off(position);
+    let command = parse_labels(command_args.into_iter().skip(1))?;
+    Ok((Command::Labels(command), config_args))
+}
+
+fn parse_labels(args: impl Iterator<Item = String>) -> Result<LabelsCommand, Error> {
+    let mut control_numbers = Vec::new();
+    let mut reception = None;
+    let mut format = None;
+    let mut output = None;
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = || -> Result<String, Error> {
+            match inline_value.clone().or_else(|| args.next()) {
+                Some(value) => Ok(value),
+                None => Err(Error::MissingFlagValue { flag: flag.clone() }),
+            }
+        };
+        let invalid = |key: &str, value: String, reason: &str| Error::InvalidValue {
+            key: key.to_string(),
+            value,
+            reason: reason.to_string(),
+        };
+
+        match flag.as_str() {
+            "--format" => {
+                let value = value()?;
+                format = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid("format", value, "expected svg, pdf or png"))?,
+                );
+            }
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--reception" => {
+                let value = value()?;
+                reception =
+                    Some(value.parse().map_err(|_| {
+                        invalid("reception", value, "expected a date as YYYY-MM-DD")
+                    })?);
+            }
+            _ if flag.starts_with("--") => return Err(Error::UnknownFlag { flag }),
+            _ => control_numbers.extend(
+                arg.split(',')
+                    .map(str::trim)
+                    .filter(|number| !number.is_empty())
+                    .map(str::to_string),
+            ),
+        }
+    }
+
+    let Some(output) = output else {
+        return Err(Error::MissingFlagValue {
+            flag: "--output".to_string(),
+        });
+    };
+    if control_numbers.is_empty() == reception.is_none() {
+        return Err(Error::InvalidValue {
+            key: "labels".to_string(),
+            value: control_numbers.join(","),
+            reason: "expected control numbers or --reception, but not both".to_string(),
+        });
+    }
+    let format = format
+        .or_else(|| LabelFormat::from_path(&output))
+        .unwrap_or_default();
+
+    Ok(LabelsCommand {
+        control_numbers,
+        reception,
+        format,
+        output,
+    })
+}
+
+pub fn usage() -> String {
+    let mut usage = Config::usage();
+    usage.push_str("\n\nCommands:\n");
+    usage.push_str(
+        "  labels [--format svg|pdf|png] --output <FILE> [--reception <DATE>] [NUMBER...]\n",
+    );
+    usage.push_str(
+        "                           Label sheets with Code128 and QR for the given control\n",
+    );
+    usage.push_str(
+        "                           numbers, or for the samples received on DATE (YYYY-MM-DD)",
+    );
+    usage
+}
@@ -0,0 +1,762 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+use toml::de::Error as TomlError;
+
+use crate::data::model::result_value::BelowLimitPolicy;
+use crate::i18n::Locale;
+use crate::logic::control_number::ControlNumberPattern;
+
+pub const DEFAULT_CONFIG_FILE: &str = "./config.toml";
+
+#[derive(Debug)]
+pub enum Error {
+    ReadFile {
+        path: PathBuf,
+        source: IoError,
+    },
+    ParseFile {
+        path: PathBuf,
+        source: TomlError,
+    },
+    UnknownProfile {
+        profile: String,
+    },
+    InvalidValue {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    UnknownFlag {
+        flag: String,
+    },
+    MissingFlagValue {
+        flag: String,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReadFile { path, source } => {
+                write!(f, "ConfigError: cannot read {}: {}", path.display(), source)
+            }
+            Self::ParseFile { path, source } => {
+                write!(
+                    f,
+                    "ConfigError: invalid TOML in {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            Self::UnknownProfile { profile } => {
+                write!(
+                    f,
+                    "ConfigError: unknown profile \"{}\" (expected dev, test or prod)",
+                    profile
+                )
+            }
+            Self::InvalidValue { key, value, reason } => {
+                write!(f, "ConfigError: invalid {} \"{}\": {}", key, value, reason)
+            }
+            Self::UnknownFlag { flag } => {
+                write!(f, "ConfigError: unknown flag {} (try --help)", flag)
+            }
+            Self::MissingFlagValue { flag } => {
+                write!(f, "ConfigError: flag {} expects a value", flag)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ReadFile { source, .. } => Some(source),
+            Self::ParseFile { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    #[default]
+    Dev,
+    Test,
+    Prod,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dev => "dev",
+            Self::Test => "test",
+            Self::Prod => "prod",
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dev" | "development" => Ok(Self::Dev),
+            "test" => Ok(Self::Test),
+            "prod" | "production" => Ok(Self::Prod),
+            _ => Err(Error::UnknownProfile {
+                profile: s.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level <= *self
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(Error::InvalidValue {
+                key: "log_level".to_string(),
+                value: s.to_string(),
+                reason: "expected error, warn, info, debug or trace".to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interface {
+    #[default]
+    Console,
+    Tui,
+}
+
+impl FromStr for Interface {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "console" => Ok(Self::Console),
+            "tui" => Ok(Self::Tui),
+            _ => Err(Error::InvalidValue {
+                key: "interface".to_string(),
+                value: s.to_string(),
+                reason: "expected console or tui".to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub profile: Profile,
+    pub database_url: String,
+    pub database_init_script: String,
+    pub page_size: u64,
+    pub locale: Locale,
+    pub log_level: LogLevel,
+    pub interface: Interface,
+    pub control_number_pattern: ControlNumberPattern,
+    // Dias naturales desde la recepcion para entregar resultados
+    pub sla_days: u32,
+    // Valor de los resultados debajo del limite de deteccion en estadisticas
+    pub below_limit: BelowLimitPolicy,
+    // IVA (%) que se agrega a las cotizaciones
+    pub tax_percent: u32,
+}
+
+impl Config {
+    // Valores por defecto de cada perfil, antes de aplicar archivo, entorno y banderas
+    pub fn defaults(profile: Profile) -> Self {
+        let (database_url, log_level) = match profile {
+            Profile::Dev => ("./db/samples.db", LogLevel::Debug),
+            Profile::Test => ("./db/test.db", LogLevel::Warn),
+            Profile::Prod => ("./db/samples.db", LogLevel::Info),
+        };
+
+        Self {
+            profile,
+            database_url: database_url.to_string(),
+            database_init_script: "./db/script.sql".to_string(),
+            page_size: 50,
+            locale: Locale::EsMx,
+            log_level,
+            interface: Interface::Console,
+            control_number_pattern: ControlNumberPattern::default(),
+            sla_days: 10,
+            below_limit: BelowLimitPolicy::default(),
+            tax_percent: 16,
+        }
+    }
+
+    // Orden de precedencia: valores por defecto < archivo (base) < archivo ([profiles.x])
+    // < variables de entorno < banderas de linea de comandos
+    pub fn load<I>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        dotenv::dotenv().ok();
+        let flags = Flags::parse(args)?;
+        let env = Settings::from_env()?;
+
+        let config_path = flags
+            .config_file
+            .clone()
+            .or_else(|| dotenv::var("APP_CONFIG").ok().map(PathBuf::from));
+        let file = match &config_path {
+            Some(path) => Some(ConfigFile::read(path)?),
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(ConfigFile::read(Path::new(DEFAULT_CONFIG_FILE))?)
+            }
+            None => None,
+        };
+        Self::resolve(&flags, &env, file.as_ref())
+    }
+
+    // Combina las fuentes ya leidas segun la precedencia de `load`
+    fn resolve(flags: &Flags, env: &Settings, file: Option<&ConfigFile>) -> Result<Self, Error> {
+        let profile = match flags.profile.clone().or(env.profile.clone()) {
+            Some(profile) => profile.parse()?,
+            None => match file.and_then(|file| file.profile.clone()) {
+                Some(profile) => profile.parse()?,
+                None => Profile::default(),
+            },
+        };
+
+        let mut config = Self::defaults(profile);
+        if let Some(file) = file {
+            config.apply(&file.base)?;
+            if let Some(section) = file.profiles.get(profile.as_str()) {
+                config.apply(section)?;
+            }
+        }
+        config.apply(env)?;
+        config.apply(&flags.settings)?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn apply(&mut self, settings: &Settings) -> Result<(), Error> {
+        if let Some(database_url) = &settings.database_url {
+            self.database_url = database_url.clone();
+        }
+
+        if let Some(script) = &settings.database_init_script {
+            self.database_init_script = script.clone();
+        }
+
+        if let Some(page_size) = &settings.page_size {
+            self.page_size = page_size.parse().map_err(|_| Error::InvalidValue {
+                key: "page_size".to_string(),
+                value: page_size.clone(),
+                reason: "expected a positive integer".to_string(),
+            })?;
+        }
+
+        if let Some(locale) = &settings.locale {
+            self.locale = locale.parse()?;
+        }
+
+        if let Some(log_level) = &settings.log_level {
+            self.log_level = log_level.parse()?;
+        }
+
+        if let Some(interface) = &settings.interface {
+            self.interface = interface.parse()?;
+        }
+
+        if let Some(pattern) = &settings.control_number_pattern {
+            self.control_number_pattern = pattern.parse()?;
+        }
+
+        if let Some(sla_days) = &settings.sla_days {
+            self.sla_days = sla_days.parse().map_err(|_| Error::InvalidValue {
+                key: "sla_days".to_string(),
+                value: sla_days.clone(),
+                reason: "expected a positive integer".to_string(),
+            })?;
+        }
+
+        if let Some(below_limit) = &settings.below_limit {
+            self.below_limit = below_limit.parse()?;
+        }
+
+        if let Some(tax_percent) = &settings.tax_percent {
+            self.tax_percent = tax_percent.parse().map_err(|_| Error::InvalidValue {
+                key: "tax_percent".to_string(),
+                value: tax_percent.clone(),
+                reason: "expected a whole percentage".to_string(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.database_url.trim().is_empty() {
+            return Err(Error::InvalidValue {
+                key: "database_url".to_string(),
+                value: self.database_url.clone(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        if self.database_init_script.trim().is_empty() {
+            return Err(Error::InvalidValue {
+                key: "database_init_script".to_string(),
+                value: self.database_init_script.clone(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        if self.page_size == 0 {
+            return Err(Error::InvalidValue {
+                key: "page_size".to_string(),
+                value: self.page_size.to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        if self.sla_days == 0 {
+            return Err(Error::InvalidValue {
+                key: "sla_days".to_string(),
+                value: self.sla_days.to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        if self.tax_percent > 100 {
+            return Err(Error::InvalidValue {
+                key: "tax_percent".to_string(),
+                value: self.tax_percent.to_string(),
+                reason: "must be at most 100".to_string(),
+            });
+        }
+
+        if self.interface == Interface::Tui && !cfg!(feature = "tui") {
+            return Err(Error::InvalidValue {
+                key: "interface".to_string(),
+                value: "tui".to_string(),
+                reason: "this build does not include the tui feature".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn usage() -> String {
+        let mut usage = String::new();
+        usage.push_str("Usage: rust-project [OPTIONS] [COMMAND]\n\n");
+        usage.push_str("Options:\n");
+        usage.push_str(
+            "  --config <FILE>          TOML configuration file (default ./config.toml)\n",
+        );
+        usage.push_str("  --profile <PROFILE>      dev, test or prod\n");
+        usage.push_str("  --database-url <PATH>    SQLite database file\n");
+        usage.push_str("  --init-script <PATH>     SQL script used to create the database\n");
+        usage.push_str("  --page-size <N>          Rows per page in searches\n");
+        usage.push_str("  --locale <LOCALE>        es-MX or en\n");
+        usage.push_str("  --log-level <LEVEL>      error, warn, info, debug or trace\n");
+        usage.push_str("  --interface <UI>         console or tui (requires the tui feature)\n");
+        usage.push_str("  --tui                    Same as --interface tui\n");
+        usage.push_str(
+            "  --control-number-pattern <PATTERN>  Sample numbers, e.g. {YYYY}-{client}-{seq:05}\n",
+        );
+        usage.push_str("  --sla-days <N>           Days from reception to deliver results\n");
+        usage.push_str(
+            "  --below-limit <POLICY>   Results below detection limit: exclude, zero, half or limit\n",
+        );
+        usage.push_str("  --tax-percent <N>        VAT percentage added to quotations\n");
+        usage.push_str("  -h, --help               Print this help\n\n");
+        usage.push_str(
+            "Environment: APP_CONFIG, APP_PROFILE, DATABASE_URL, DATABASE_INIT_SCRIPT,\n",
+        );
+        usage.push_str("APP_PAGE_SIZE, APP_LOCALE, APP_LOG_LEVEL,\n");
+        usage.push_str(
+            "APP_INTERFACE, APP_CONTROL_NUMBER_PATTERN, APP_SLA_DAYS, APP_BELOW_LIMIT,\n",
+        );
+        usage.push_str("APP_TAX_PERCENT");
+        usage
+    }
+}
+
+// Valores sin procesar tal como llegan de una fuente (archivo, entorno o banderas)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Settings {
+    #[serde(skip)]
+    profile: Option<String>,
+    database_url: Option<String>,
+    database_init_script: Option<String>,
+    #[serde(default, deserialize_with = "string_or_integer")]
+    page_size: Option<String>,
+    locale: Option<String>,
+    log_level: Option<String>,
+    interface: Option<String>,
+    control_number_pattern: Option<String>,
+    #[serde(default, deserialize_with = "string_or_integer")]
+    sla_days: Option<String>,
+    below_limit: Option<String>,
+    #[serde(default, deserialize_with = "string_or_integer")]
+    tax_percent: Option<String>,
+}
+
+impl Settings {
+    fn from_env() -> Result<Self, Error> {
+        Ok(Self {
+            profile: env_var("APP_PROFILE")?,
+            database_url: env_var("DATABASE_URL")?,
+            database_init_script: env_var("DATABASE_INIT_SCRIPT")?,
+            page_size: env_var("APP_PAGE_SIZE")?,
+            locale: env_var("APP_LOCALE")?,
+            log_level: env_var("APP_LOG_LEVEL")?,
+            interface: env_var("APP_INTERFACE")?,
+            control_number_pattern: env_var("APP_CONTROL_NUMBER_PATTERN")?,
+            sla_days: env_var("APP_SLA_DAYS")?,
+            below_limit: env_var("APP_BELOW_LIMIT")?,
+            tax_percent: env_var("APP_TAX_PERCENT")?,
+        })
+    }
+}
+
+fn env_var(key: &str) -> Result<Option<String>, Error> {
+    match dotenv::var(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(dotenv::Error::EnvVar(std::env::VarError::NotPresent)) => Ok(None),
+        Err(_) => Err(Error::InvalidValue {
+            key: key.to_string(),
+            value: String::new(),
+            reason: "environment variable is not valid unicode".to_string(),
+        }),
+    }
+}
+
+fn string_or_integer<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Int(i64),
+        Str(String),
+    }
+
+    Ok(
+        Option::<Raw>::deserialize(deserializer)?.map(|raw| match raw {
+            Raw::Int(value) => value.to_string(),
+            Raw::Str(value) => value,
+        }),
+    )
+}
+
+struct ConfigFile {
+    profile: Option<String>,
+    base: Settings,
+    profiles: HashMap<String, Settings>,
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path).map_err(|e| Error::ReadFile {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Self::parse(path, &content)
+    }
+
+    fn parse(path: &Path, content: &str) -> Result<Self, Error> {
+        let parse_error = |e| Error::ParseFile {
+            path: path.to_path_buf(),
+            source: e,
+        };
+
+        let mut table: toml::Table = content.parse().map_err(parse_error)?;
+
+        let profile = match table.remove("profile") {
+            Some(toml::Value::String(profile)) => Some(profile),
+            Some(other) => {
+                return Err(Error::InvalidValue {
+                    key: "profile".to_string(),
+                    value: other.to_string(),
+                    reason: "expected a string".to_string(),
+                })
+            }
+            None => None,
+        };
+
+        let profiles: HashMap<String, Settings> = match table.remove("profiles") {
+            Some(profiles) => profiles.try_into().map_err(parse_error)?,
+            None => HashMap::new(),
+        };
+
+        for name in profiles.keys() {
+            name.parse::<Profile>()?;
+        }
+
+        let base: Settings = toml::Value::Table(table).try_into().map_err(parse_error)?;
+
+        Ok(Self {
+            profile,
+            base,
+            profiles,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Flags {
+    config_file: Option<PathBuf>,
+    profile: Option<String>,
+    settings: Settings,
+}
+
+impl Flags {
+    fn parse<I>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut flags = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+
+            let mut value = || -> Result<String, Error> {
+                match inline_value.clone().or_else(|| args.next()) {
+                    Some(value) => Ok(value),
+                    None => Err(Error::MissingFlagValue { flag: flag.clone() }),
+                }
+            };
+
+            match flag.as_str() {
+                "--config" => flags.config_file = Some(PathBuf::from(value()?)),
+                "--profile" => flags.profile = Some(value()?),
+                "--database-url" => flags.settings.database_url = Some(value()?),
+                "--init-script" => flags.settings.database_init_script = Some(value()?),
+                "--page-size" => flags.settings.page_size = Some(value()?),
+                "--locale" => flags.settings.locale = Some(value()?),
+                "--log-level" => flags.settings.log_level = Some(value()?),
+                "--interface" => flags.settings.interface = Some(value()?),
+                "--tui" => flags.settings.interface = Some("tui".to_string()),
+                "--control-number-pattern" => {
+                    flags.settings.control_number_pattern = Some(value()?)
+                }
+                "--sla-days" => flags.settings.sla_days = Some(value()?),
+                "--below-limit" => flags.settings.below_limit = Some(value()?),
+                "--tax-percent" => flags.settings.tax_percent = Some(value()?),
+                _ => return Err(Error::UnknownFlag { flag }),
+            }
+        }
+
+        Ok(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn invalid_key(result: Result<(), Error>) -> String {
+        match result {
+            Err(Error::InvalidValue { key, .. }) => key,
+            other => panic!("expected InvalidValue, got {:?}", other),
+        }
+    }
+
+    const FILE: &str = r#"
+        profile = "prod"
+        locale = "en"
+        page_size = 20
+        sla_days = 5
+        tax_percent = "10"
+
+        [profiles.prod]
+        page_size = 30
+        sla_days = 7
+        tax_percent = 12
+
+        [profiles.test]
+        database_url = "./db/ci.db"
+    "#;
+
+    #[test]
+    fn flags_accept_inline_and_separate_values() {
+        let flags = Flags::parse(args(&[
+            "--page-size=20",
+            "--locale",
+            "en",
+            "--config=otro.toml",
+            "--profile",
+            "prod",
+            "--tui",
+            "--control-number-pattern={YYYY}-{seq:04}",
+        ]))
+        .unwrap();
+
+        assert_eq!(flags.config_file, Some(PathBuf::from("otro.toml")));
+        assert_eq!(flags.profile.as_deref(), Some("prod"));
+        assert_eq!(flags.settings.page_size.as_deref(), Some("20"));
+        assert_eq!(flags.settings.locale.as_deref(), Some("en"));
+        assert_eq!(flags.settings.interface.as_deref(), Some("tui"));
+        assert_eq!(
+            flags.settings.control_number_pattern.as_deref(),
+            Some("{YYYY}-{seq:04}")
+        );
+        assert_eq!(flags.settings.database_url, None);
+    }
+
+    #[test]
+    fn unknown_flags_and_missing_values_are_rejected() {
+        assert!(matches!(
+            Flags::parse(args(&["--colour", "red"])),
+            Err(Error::UnknownFlag { flag }) if flag == "--colour"
+        ));
+        assert!(matches!(
+            Flags::parse(args(&["--color=red"])),
+            Err(Error::UnknownFlag { flag }) if flag == "--color"
+        ));
+        assert!(matches!(
+            Flags::parse(args(&["clients"])),
+            Err(Error::UnknownFlag { .. })
+        ));
+        assert!(matches!(
+            Flags::parse(args(&["--locale", "en", "--page-size"])),
+            Err(Error::MissingFlagValue { flag }) if flag == "--page-size"
+        ));
+    }
+
+    #[test]
+    fn profile_sections_are_read_apart_from_the_base() {
+        let file = ConfigFile::parse(Path::new("config.toml"), FILE).unwrap();
+        assert_eq!(file.profile.as_deref(), Some("prod"));
+        assert_eq!(file.base.page_size.as_deref(), Some("20"));
+        assert_eq!(file.base.tax_percent.as_deref(), Some("10"));
+        assert_eq!(file.profiles.len(), 2);
+        assert_eq!(file.profiles["prod"].page_size.as_deref(), Some("30"));
+        assert_eq!(file.profiles["prod"].locale, None);
+        assert_eq!(
+            file.profiles["test"].database_url.as_deref(),
+            Some("./db/ci.db")
+        );
+
+        assert!(matches!(
+            ConfigFile::parse(Path::new("config.toml"), "[profiles.staging]\npage_size = 1"),
+            Err(Error::UnknownProfile { profile }) if profile == "staging"
+        ));
+        assert!(matches!(
+            ConfigFile::parse(Path::new("config.toml"), "page_sise = 1"),
+            Err(Error::ParseFile { .. })
+        ));
+        assert!(matches!(
+            ConfigFile::parse(Path::new("config.toml"), "profile = 1"),
+            Err(Error::InvalidValue { key, .. }) if key == "profile"
+        ));
+    }
+
+    #[test]
+    fn each_source_overrides_the_previous_one() {
+        let file = ConfigFile::parse(Path::new("config.toml"), FILE).unwrap();
+        let env = Settings {
+            page_size: Some("40".to_string()),
+            tax_percent: Some("8".to_string()),
+            ..Settings::default()
+        };
+        let flags = Flags::parse(args(&["--page-size", "60"])).unwrap();
+
+        let config = Config::resolve(&flags, &env, Some(&file)).unwrap();
+        // El perfil lo elige el archivo y sus valores por defecto se conservan
+        assert_eq!(config.profile, Profile::Prod);
+        assert_eq!(config.log_level, LogLevel::Info);
+        assert_eq!(config.database_init_script, "./db/script.sql");
+        assert_eq!(config.locale, Locale::En);
+        assert_eq!(config.sla_days, 7);
+        assert_eq!(config.tax_percent, 8);
+        assert_eq!(config.page_size, 60);
+
+        // El entorno y las banderas eligen el perfil antes que el archivo
+        let env = Settings {
+            profile: Some("test".to_string()),
+            ..Settings::default()
+        };
+        let config = Config::resolve(&Flags::default(), &env, Some(&file)).unwrap();
+        assert_eq!(config.profile, Profile::Test);
+        assert_eq!(config.database_url, "./db/ci.db");
+        assert_eq!(config.page_size, 20);
+        let flags = Flags::parse(args(&["--profile", "dev"])).unwrap();
+        let config = Config::resolve(&flags, &env, Some(&file)).unwrap();
+        assert_eq!(config.profile, Profile::Dev);
+        assert_eq!(config.log_level, LogLevel::Debug);
+
+        assert_eq!(
+            Config::resolve(&Flags::default(), &Settings::default(), None).unwrap(),
+            Config::defaults(Profile::Dev)
+        );
+        let flags = Flags::parse(args(&["--sla-days", "diez"])).unwrap();
+        assert_eq!(
+            invalid_key(Config::resolve(&flags, &Settings::default(), None).map(|_| ())),
+            "sla_days"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_values() {
+        assert!(Config::defaults(Profile::Prod).validate().is_ok());
+
+        let mut config = Config::defaults(Profile::Dev);
+        config.database_url = " ".to_string();
+        assert_eq!(invalid_key(config.validate()), "database_url");
+
+        let mut config = Config::defaults(Profile::Dev);
+        config.page_size = 0;
+        assert_eq!(invalid_key(config.validate()), "page_size");
+
+        let mut config = Config::defaults(Profile::Dev);
+        config.sla_days = 0;
+        assert_eq!(invalid_key(config.validate()), "sla_days");
+
+        let mut config = Config::defaults(Profile::Dev);
+        config.tax_percent = 101;
+        assert_eq!(invalid_key(config.validate()), "tax_percent");
+
+        let mut config = Config::defaults(Profile::Dev);
+        config.interface = Interface::Tui;
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "tui"));
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Analysis {
+    pub id_analysis: Option<u32>,
+    pub analysis_active: bool,
+    pub analysis_name: String,
+    pub id_parameter: Option<u32>,
+    // Limite de deteccion en la unidad en que se guardan los resultados
+    pub detection_limit: Option<f64>,
+    // Precio de lista por muestra; sin precio no se puede cotizar
+    pub price: Option<Money>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Analysis in "Analysis" {
+        id: id_analysis,
+        active: analysis_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "analysis_name",
+        columns: [
+            analysis_active,
+            analysis_name,
+            id_parameter,
+            detection_limit,
+            price,
+        ],
+    }
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+use crate::table_model;
+
+// Conjunto de analisis que se cotiza junto, p. ej. los de una norma
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AnalysisPackage {
+    pub id_package: Option<u32>,
+    pub package_active: bool,
+    pub package_name: String,
+    // Sin precio propio vale la suma de sus analisis
+    pub package_price: Option<Money>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    AnalysisPackage in "AnalysisPackage" {
+        id: id_package,
+        active: package_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "package_name",
+        columns: [package_active, package_name, package_price],
+    }
+}
@@ -1,10 +1,38 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::table_model;
+
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Client {
     pub id_client: Option<u32>,
     pub client_active: bool,
     pub client_name: String,
+    // RFC (clave fiscal) y domicilio de facturacion
+    pub client_rfc: Option<String>,
+    pub billing_address: Option<String>,
+    // Descuento (%) que se aplica en sus cotizaciones
+    pub discount_percent: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Client in "Client" {
+        id: id_client,
+        active: client_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "client_name",
+        columns: [
+            client_active,
+            client_name,
+            client_rfc,
+            billing_address,
+            discount_percent,
+        ],
+    }
 }
 
 impl std::fmt::Display for Client {
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Persona de contacto de un cliente
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ClientContact {
+    pub id_contact: Option<u32>,
+    pub contact_active: bool,
+    pub id_client: u32,
+    pub contact_name: String,
+    pub contact_role: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    ClientContact in "ClientContact" {
+        id: id_contact,
+        active: contact_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "contact_name",
+        columns: [
+            contact_active,
+            id_client,
+            contact_name,
+            contact_role,
+            email,
+            phone,
+        ],
+    }
+}
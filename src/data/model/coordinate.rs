@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Latitude,
+    Longitude,
+}
+
+impl Axis {
+    fn limit(&self) -> f64 {
+        match self {
+            Self::Latitude => 90.0,
+            Self::Longitude => 180.0,
+        }
+    }
+
+    // Letra del hemisferio y si vuelve negativo el valor; se acepta O (oeste)
+    fn hemisphere(&self, letter: char) -> Option<bool> {
+        match (self, letter) {
+            (Self::Latitude, 'N') => Some(false),
+            (Self::Latitude, 'S') => Some(true),
+            (Self::Longitude, 'E') => Some(false),
+            (Self::Longitude, 'W' | 'O') => Some(true),
+            _ => None,
+        }
+    }
+}
+
+// Grados decimales; latitud positiva al norte y longitud positiva al este
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinate {
+    pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+        let valid = |value: f64, axis: Axis| value.is_finite() && value.abs() <= axis.limit();
+        if valid(latitude, Axis::Latitude) && valid(longitude, Axis::Longitude) {
+            Some(Self {
+                latitude,
+                longitude,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn parse(latitude: &str, longitude: &str) -> Option<Self> {
+        Some(Self {
+            latitude: parse_degrees(latitude, Axis::Latitude)?,
+            longitude: parse_degrees(longitude, Axis::Longitude)?,
+        })
+    }
+
+    // Distancia sobre la superficie (haversine)
+    pub fn distance_km(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+impl std::fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.6}, {:.6}", self.latitude, self.longitude)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    pub fn new(south_west: Coordinate, north_east: Coordinate) -> Self {
+        Self {
+            min_latitude: south_west.latitude,
+            min_longitude: south_west.longitude,
+            max_latitude: north_east.latitude,
+            max_longitude: north_east.longitude,
+        }
+    }
+
+    // Caja que contiene el circulo de `radius_km` alrededor de `center`; se
+    // usa como primer filtro antes de medir la distancia exacta
+    pub fn around(center: Coordinate, radius_km: f64) -> Self {
+        let d_lat = (radius_km / EARTH_RADIUS_KM).to_degrees();
+        let min_latitude = (center.latitude - d_lat).max(-90.0);
+        let max_latitude = (center.latitude + d_lat).min(90.0);
+
+        // Cerca de los polos la caja abarca todas las longitudes
+        let widest = min_latitude.abs().max(max_latitude.abs()).to_radians();
+        let (min_longitude, max_longitude) = if widest.cos() <= f64::EPSILON {
+            (-180.0, 180.0)
+        } else {
+            let d_lon = d_lat / widest.cos();
+            if d_lon >= 180.0 {
+                (-180.0, 180.0)
+            } else {
+                (center.longitude - d_lon, center.longitude + d_lon)
+            }
+        };
+
+        Self {
+            min_latitude,
+            min_longitude,
+            max_latitude,
+            max_longitude,
+        }
+    }
+
+    pub fn contains(&self, point: &Coordinate) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&point.latitude)
+            && self
+                .longitude_ranges()
+                .iter()
+                .any(|(min, max)| (*min..=*max).contains(&point.longitude))
+    }
+
+    // Rangos de longitud normalizados a [-180, 180]; una caja que cruza el
+    // antimeridiano se parte en dos
+    pub fn longitude_ranges(&self) -> Vec<(f64, f64)> {
+        if self.max_longitude - self.min_longitude >= 360.0 {
+            return vec![(-180.0, 180.0)];
+        }
+
+        let wrap = |value: f64| (value + 180.0).rem_euclid(360.0) - 180.0;
+        let (min, max) = (wrap(self.min_longitude), wrap(self.max_longitude));
+        if min <= max {
+            vec![(min, max)]
+        } else {
+            vec![(min, 180.0), (-180.0, max)]
+        }
+    }
+}
+
+// Acepta grados decimales ("-99.1332", "19.4326 N") y grados, minutos y
+// segundos ("19°25'57.4\"N", "99 08 W", "N 19° 25.95'")
+pub fn parse_degrees(text: &str, axis: Axis) -> Option<f64> {
+    let mut text = text.trim().to_uppercase();
+
+    let mut negative = None;
+    if let Some(letter) = text.chars().last().filter(char::is_ascii_alphabetic) {
+        negative = Some(axis.hemisphere(letter)?);
+        text.pop();
+    } else if let Some(letter) = text.chars().next().filter(char::is_ascii_alphabetic) {
+        negative = Some(axis.hemisphere(letter)?);
+        text.remove(0);
+    }
+
+    let mut text = text.trim();
+    let mut sign = 1.0;
+    if let Some(rest) = text.strip_prefix('-') {
+        // Un signo junto con la letra del hemisferio es ambiguo
+        if negative.is_some() {
+            return None;
+        }
+        sign = -1.0;
+        text = rest;
+    } else if let Some(rest) = text.strip_prefix('+') {
+        text = rest;
+    }
+    if negative == Some(true) {
+        sign = -1.0;
+    }
+
+    let is_separator = |c: char| c.is_whitespace() || "°º'’′\"”″".contains(c);
+    let parts: Vec<&str> = text
+        .split(is_separator)
+        .filter(|part| !part.is_empty())
+        .collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let mut numbers = Vec::new();
+    for part in &parts {
+        if !part.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+        numbers.push(part.parse::<f64>().ok()?);
+    }
+
+    // Solo la ultima parte puede tener decimales
+    let last = numbers.len() - 1;
+    if numbers[..last].iter().any(|value| value.fract() != 0.0) {
+        return None;
+    }
+    if numbers[1..].iter().any(|value| *value >= 60.0) {
+        return None;
+    }
+
+    let value = numbers
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(value, divisor)| value / divisor)
+        .sum::<f64>();
+
+    if value > axis.limit() {
+        return None;
+    }
+    Some(sign * value)
+}
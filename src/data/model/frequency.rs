@@ -0,0 +1,84 @@
+use chrono::{Days, Months, NaiveDate};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+// Periodicidad de un monitoreo contratado
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Weekly,
+    Biweekly,
+    #[default]
+    Monthly,
+    Bimonthly,
+    Quarterly,
+    Semiannual,
+    Annual,
+}
+
+impl Frequency {
+    pub const ALL: [Self; 7] = [
+        Self::Weekly,
+        Self::Biweekly,
+        Self::Monthly,
+        Self::Bimonthly,
+        Self::Quarterly,
+        Self::Semiannual,
+        Self::Annual,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Biweekly => "biweekly",
+            Self::Monthly => "monthly",
+            Self::Bimonthly => "bimonthly",
+            Self::Quarterly => "quarterly",
+            Self::Semiannual => "semiannual",
+            Self::Annual => "annual",
+        }
+    }
+
+    // Fecha de la n-esima visita contando desde el inicio (la 0 es el inicio).
+    // Se calcula siempre desde el inicio para que un dia 31 no se vaya
+    // recorriendo a 28 despues de febrero.
+    pub fn nth(&self, starts_on: NaiveDate, n: u32) -> Option<NaiveDate> {
+        let months = match self {
+            Self::Weekly => return starts_on.checked_add_days(Days::new(7 * n as u64)),
+            Self::Biweekly => return starts_on.checked_add_days(Days::new(14 * n as u64)),
+            Self::Monthly => 1,
+            Self::Bimonthly => 2,
+            Self::Quarterly => 3,
+            Self::Semiannual => 6,
+            Self::Annual => 12,
+        };
+        starts_on.checked_add_months(Months::new(months * n))
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|frequency| frequency.as_str() == s)
+            .ok_or_else(|| format!("unknown frequency {}", s))
+    }
+}
+
+impl ToSql for Frequency {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Frequency {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::invoice_status::InvoiceStatus;
+use super::money::Money;
+use crate::table_model;
+
+// Factura a un cliente; el folio se asigna al emitirla
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Invoice {
+    pub id_invoice: Option<u32>,
+    pub invoice_active: bool,
+    pub invoice_number: Option<String>,
+    pub id_client: u32,
+    // Solo cambia con las transiciones de `InvoiceManager`
+    pub invoice_status: InvoiceStatus,
+    pub drafted_on: NaiveDate,
+    pub issued_on: Option<NaiveDate>,
+    pub paid_on: Option<NaiveDate>,
+    pub cancelled_on: Option<NaiveDate>,
+    // Copiados del cliente y de la configuracion al crearla
+    pub discount_percent: f64,
+    pub tax_percent: u32,
+    pub subtotal: Money,
+    pub discount: Money,
+    pub tax: Money,
+    pub total: Money,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Invoice in "Invoice" {
+        id: id_invoice,
+        active: invoice_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "drafted_on DESC, id_invoice DESC",
+        columns: [
+            invoice_active,
+            invoice_number,
+            id_client,
+            invoice_status,
+            drafted_on,
+            issued_on,
+            paid_on,
+            cancelled_on,
+            discount_percent,
+            tax_percent,
+            subtotal,
+            discount,
+            tax,
+            total,
+        ],
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+use crate::table_model;
+
+// Partida de una factura: un analisis de una muestra con su precio de lista
+// al facturar, o la partida de la cotizacion de la que salio la muestra con
+// su precio cotizado
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InvoiceLine {
+    pub id_line: Option<u32>,
+    pub id_invoice: u32,
+    pub id_sample: u32,
+    pub id_analysis: Option<u32>,
+    pub id_item: Option<u32>,
+    pub description: String,
+    pub unit_price: Money,
+}
+
+table_model! {
+    InvoiceLine in "InvoiceLine" {
+        id: id_line,
+        order_by: "id_line",
+        columns: [
+            id_invoice,
+            id_sample,
+            id_analysis,
+            id_item,
+            description,
+            unit_price,
+        ],
+    }
+}
@@ -0,0 +1,64 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+// Etapas de una factura; pagada y cancelada son finales
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceStatus {
+    #[default]
+    Draft,
+    Issued,
+    Paid,
+    Cancelled,
+}
+
+impl InvoiceStatus {
+    pub const ALL: [Self; 4] = [Self::Draft, Self::Issued, Self::Paid, Self::Cancelled];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Issued => "issued",
+            Self::Paid => "paid",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn next(&self) -> Vec<Self> {
+        match self {
+            Self::Draft => vec![Self::Issued, Self::Cancelled],
+            Self::Issued => vec![Self::Paid, Self::Cancelled],
+            Self::Paid | Self::Cancelled => Vec::new(),
+        }
+    }
+
+    pub fn can_change_to(&self, to: Self) -> bool {
+        self.next().contains(&to)
+    }
+}
+
+impl std::str::FromStr for InvoiceStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| format!("unknown invoice status {}", s))
+    }
+}
+
+impl ToSql for InvoiceStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for InvoiceStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Log {
+    pub id_log: Option<u32>,
+    pub log_active: bool,
+    pub msg: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Log in "Logs" {
+        id: id_log,
+        active: log_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "id_log",
+        columns: [
+            log_active,
+            msg,
+        ],
+    }
+}
@@ -1 +1,37 @@
+pub mod analysis;
+pub mod analysis_package;
 pub mod client;
+pub mod client_contact;
+pub mod coordinate;
+pub mod frequency;
+pub mod invoice;
+pub mod invoice_line;
+pub mod invoice_status;
+pub mod log;
+pub mod money;
+pub mod package_detail;
+pub mod parameter;
+pub mod planned_visit;
+pub mod quotation;
+pub mod quotation_item;
+pub mod result_limits;
+pub mod result_point;
+pub mod result_signature;
+pub mod result_value;
+pub mod role;
+pub mod sample;
+pub mod sample_analysis;
+pub mod sample_result;
+pub mod sample_status;
+pub mod sample_transition;
+pub mod sampler;
+pub mod sampling_schedule;
+pub mod sampling_visit;
+pub mod schedule_analysis;
+pub mod search_hit;
+pub mod signatary;
+pub mod signatary_detail;
+pub mod site;
+pub mod standard;
+pub mod standard_detail;
+pub mod turnaround;
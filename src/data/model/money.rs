@@ -0,0 +1,130 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+// Importe en pesos; se guarda en centavos para no acumular errores de redondeo
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Self = Self(0);
+
+    pub fn from_cents(cents: i64) -> Self {
+        Self(cents)
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    // Sin signo de pesos ni separadores de miles, para CSV: "-1234.50"
+    pub fn to_decimal(&self) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+    }
+
+    // Porcentaje del importe redondeado al centavo (mitades hacia arriba)
+    pub fn percent(&self, percent: f64) -> Self {
+        Self((self.0 as f64 * percent / 100.0).round() as i64)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        let digits = (cents / 100).to_string();
+        let mut pesos = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                pesos.push(',');
+            }
+            pesos.push(digit);
+        }
+        let text = format!("{}${}.{:02}", sign, pesos, cents % 100);
+        f.pad(&text)
+    }
+}
+
+// Acepta "1200", "1,200.5" o "$1,200.50"; a lo mas dos decimales
+impl FromStr for Money {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid amount {}", s.trim());
+        let text: String = s
+            .trim()
+            .trim_start_matches('$')
+            .chars()
+            .filter(|c| *c != ',' && !c.is_whitespace())
+            .collect();
+        let (pesos, cents) = text.split_once('.').unwrap_or((&text, ""));
+        if pesos.is_empty() && cents.is_empty()
+            || cents.len() > 2
+            || !pesos
+                .chars()
+                .chain(cents.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let pesos: i64 = if pesos.is_empty() {
+            0
+        } else {
+            pesos.parse().map_err(|_| invalid())?
+        };
+        let cents: i64 = format!("{:0<2}", cents).parse().map_err(|_| invalid())?;
+        pesos
+            .checked_mul(100)
+            .and_then(|total| total.checked_add(cents))
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+impl Add for Money {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul<u32> for Money {
+    type Output = Self;
+
+    fn mul(self, quantity: u32) -> Self {
+        Self(self.0 * i64::from(quantity))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl ToSql for Money {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0))
+    }
+}
+
+impl FromSql for Money {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_i64().map(Self)
+    }
+}
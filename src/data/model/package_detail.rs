@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Analisis que forman un paquete
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PackageDetail {
+    pub id_package_detail: Option<u32>,
+    pub id_package: u32,
+    pub id_analysis: u32,
+}
+
+table_model! {
+    PackageDetail in "PackageDetail" {
+        id: id_package_detail,
+        order_by: "id_package_detail",
+        columns: [id_package, id_analysis],
+    }
+}
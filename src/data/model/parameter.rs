@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Parameter {
+    pub id_parameter: Option<u32>,
+    pub parameter_active: bool,
+    pub parameter_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Parameter in "Parameter" {
+        id: id_parameter,
+        active: parameter_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "parameter_name",
+        columns: [
+            parameter_active,
+            parameter_name,
+        ],
+    }
+}
@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// Visita pendiente con los datos de su sitio, cliente y muestreador
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PlannedVisit {
+    pub id_visit: u32,
+    pub id_schedule: u32,
+    pub planned_date: NaiveDate,
+    pub project: String,
+    pub id_site: u32,
+    pub site_key: String,
+    pub site_name: String,
+    pub client_name: Option<String>,
+    pub id_sampler: Option<u32>,
+    pub sampler_user: Option<String>,
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Cotizacion a un cliente; al aceptarse se convierte en muestras una sola vez
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Quotation {
+    pub id_quotation: Option<u32>,
+    pub quotation_active: bool,
+    pub id_client: u32,
+    pub id_site: Option<u32>,
+    pub project: String,
+    pub issued_on: NaiveDate,
+    pub valid_until: NaiveDate,
+    // Copiados del cliente y de la configuracion al cotizar
+    pub discount_percent: f64,
+    pub tax_percent: u32,
+    pub converted_on: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Quotation in "Quotation" {
+        id: id_quotation,
+        active: quotation_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "issued_on DESC, id_quotation DESC",
+        columns: [
+            quotation_active,
+            id_client,
+            id_site,
+            project,
+            issued_on,
+            valid_until,
+            discount_percent,
+            tax_percent,
+            converted_on,
+        ],
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+use crate::table_model;
+
+// Partida de una cotizacion: un paquete o un analisis suelto para `samples`
+// muestras, con el precio unitario vigente al cotizar
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct QuotationItem {
+    pub id_item: Option<u32>,
+    pub id_quotation: u32,
+    pub id_package: Option<u32>,
+    pub id_analysis: Option<u32>,
+    pub description: String,
+    pub samples: u32,
+    pub unit_price: Money,
+}
+
+table_model! {
+    QuotationItem in "QuotationItem" {
+        id: id_item,
+        order_by: "id_item",
+        columns: [
+            id_quotation,
+            id_package,
+            id_analysis,
+            description,
+            samples,
+            unit_price,
+        ],
+    }
+}
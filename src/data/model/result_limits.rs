@@ -0,0 +1,21 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::result_value::ResultValue;
+
+// Resultado de una muestra junto con los limites que fija su norma
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultLimits {
+    pub id_sample: u32,
+    pub control_number: String,
+    pub sampling_date: NaiveDate,
+    pub id_analysis: Option<u32>,
+    pub result: ResultValue,
+    pub result_unit: Option<String>,
+    // Unidad de la norma en que estan los limites
+    pub std_units: Option<String>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    // Limite de deteccion del analisis
+    pub detection_limit: Option<f64>,
+}
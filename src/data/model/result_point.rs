@@ -0,0 +1,16 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::result_value::ResultValue;
+
+// Un resultado de la serie de tiempo de un analisis en un sitio
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultPoint {
+    pub id_sample_result: u32,
+    pub id_sample: u32,
+    pub control_number: String,
+    pub sampling_date: NaiveDate,
+    pub analysis_date: NaiveDate,
+    pub result: ResultValue,
+    pub detection_limit: Option<f64>,
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Firma de un resultado. Las filas no se modifican, por eso no llevan
+// version ni fecha de cambio.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultSignature {
+    pub id_signature: Option<u32>,
+    pub id_sample_result: u32,
+    pub id_signatary: u32,
+    pub signed_at: DateTime<Utc>,
+    // SHA-256 en hexadecimal de `logic::signature::payload`
+    pub payload_hash: String,
+}
+
+table_model! {
+    ResultSignature in "ResultSignature" {
+        id: id_signature,
+        order_by: "signed_at, id_signature",
+        columns: [id_sample_result, id_signatary, signed_at, payload_hash],
+    }
+}
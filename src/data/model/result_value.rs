@@ -0,0 +1,205 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::config::Error as ConfigError;
+
+// Resultado tal como lo reporta el laboratorio. Se guarda como texto
+// ("0.25", "<0.005", "ND", ">2400", "TNTC", "Ausente", "NA").
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum ResultValue {
+    Numeric(f64),
+    // Por debajo del limite de deteccion; sin limite ("ND") se usa el del analisis
+    BelowLimit(Option<f64>),
+    // Por encima del rango del metodo; sin valor cuando es incontable ("TNTC")
+    AboveRange(Option<f64>),
+    Qualitative(String),
+    // No se analizo o no se pudo determinar
+    #[default]
+    NotDetermined,
+}
+
+const NOT_DETERMINED: &[&str] = &["", "NA", "N/A", "N.A.", "NR", "-", "--"];
+const NOT_DETECTED: &[&str] = &["ND", "N.D.", "NO DETECTADO", "NOT DETECTED", "<LD", "<LOD"];
+const TOO_NUMEROUS: &[&str] = &["TNTC", "INC", "INCONTABLE", "MNPC"];
+
+fn finite(text: &str) -> Option<f64> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+impl ResultValue {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let upper = text.to_uppercase();
+        let compact: String = upper.split_whitespace().collect();
+
+        if NOT_DETERMINED.contains(&compact.as_str()) {
+            return Self::NotDetermined;
+        }
+        if NOT_DETECTED.contains(&upper.as_str()) || NOT_DETECTED.contains(&compact.as_str()) {
+            return Self::BelowLimit(None);
+        }
+        if TOO_NUMEROUS.contains(&compact.as_str()) {
+            return Self::AboveRange(None);
+        }
+        if let Some(value) = finite(text) {
+            return Self::Numeric(value);
+        }
+
+        let bound = |prefixes: &[&str]| {
+            prefixes
+                .iter()
+                .find_map(|prefix| text.strip_prefix(prefix))
+                .and_then(finite)
+        };
+        if let Some(limit) = bound(&["<=", "≤", "<"]) {
+            return Self::BelowLimit(Some(limit));
+        }
+        if let Some(limit) = bound(&[">=", "≥", ">"]) {
+            return Self::AboveRange(Some(limit));
+        }
+        Self::Qualitative(text.to_string())
+    }
+
+    // Solo los resultados cuantificados
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            Self::Numeric(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Numeric(_))
+    }
+
+    // "ND" toma el limite de deteccion del analisis, si lo tiene
+    pub fn with_detection_limit(self, detection_limit: Option<f64>) -> Self {
+        match (self, detection_limit) {
+            (Self::BelowLimit(None), Some(limit)) => Self::BelowLimit(Some(limit)),
+            (value, _) => value,
+        }
+    }
+
+    // Aplica `f` a la parte numerica, p. ej. para convertir de unidad
+    pub fn try_map<E>(self, f: impl Fn(f64) -> Result<f64, E>) -> Result<Self, E> {
+        Ok(match self {
+            Self::Numeric(value) => Self::Numeric(f(value)?),
+            Self::BelowLimit(Some(limit)) => Self::BelowLimit(Some(f(limit)?)),
+            Self::AboveRange(Some(limit)) => Self::AboveRange(Some(f(limit)?)),
+            value => value,
+        })
+    }
+
+    // Valor para estadisticas: el numero o, debajo del limite, lo que indique
+    // la politica configurada
+    pub fn for_statistics(&self, policy: BelowLimitPolicy) -> Option<f64> {
+        match self {
+            Self::Numeric(value) => Some(*value),
+            Self::BelowLimit(Some(limit)) => policy.substitute(*limit),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResultValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numeric(value) => write!(f, "{}", value),
+            Self::BelowLimit(Some(limit)) => write!(f, "<{}", limit),
+            Self::BelowLimit(None) => write!(f, "ND"),
+            Self::AboveRange(Some(limit)) => write!(f, ">{}", limit),
+            Self::AboveRange(None) => write!(f, "TNTC"),
+            Self::Qualitative(text) => write!(f, "{}", text),
+            Self::NotDetermined => write!(f, "NA"),
+        }
+    }
+}
+
+impl FromStr for ResultValue {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl Serialize for ResultValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResultValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+impl ToSql for ResultValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for ResultValue {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ok(Self::parse(value.as_str()?))
+    }
+}
+
+// Que valor toma en las estadisticas un resultado debajo del limite de deteccion
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BelowLimitPolicy {
+    // Se deja fuera
+    #[default]
+    Exclude,
+    Zero,
+    // La mitad del limite
+    Half,
+    Limit,
+}
+
+impl BelowLimitPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exclude => "exclude",
+            Self::Zero => "zero",
+            Self::Half => "half",
+            Self::Limit => "limit",
+        }
+    }
+
+    pub fn substitute(&self, limit: f64) -> Option<f64> {
+        match self {
+            Self::Exclude => None,
+            Self::Zero => Some(0.0),
+            Self::Half => Some(limit / 2.0),
+            Self::Limit => Some(limit),
+        }
+    }
+}
+
+impl FromStr for BelowLimitPolicy {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "exclude" => Ok(Self::Exclude),
+            "zero" => Ok(Self::Zero),
+            "half" => Ok(Self::Half),
+            "limit" => Ok(Self::Limit),
+            _ => Err(ConfigError::InvalidValue {
+                key: "below_limit".to_string(),
+                value: s.to_string(),
+                reason: "expected exclude, zero, half or limit".to_string(),
+            }),
+        }
+    }
+}
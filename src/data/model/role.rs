@@ -0,0 +1,124 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+// Rol de un usuario (firmante); define que puede hacer en el sistema
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Reviewer,
+    Analyst,
+    Sampler,
+    #[default]
+    ReadOnly,
+}
+
+// Acciones que no todos los roles pueden hacer; consultar esta permitido a todos
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    EditClients,
+    EditSites,
+    EditStandards,
+    // Precios, paquetes, descuentos y cotizaciones
+    ManageQuotations,
+    // Crear, emitir, cobrar y cancelar facturas
+    ManageInvoices,
+    RegisterSamples,
+    RecordResults,
+    ReviewResults,
+    // Borrado definitivo de registros
+    DeleteRecords,
+    ManageUsers,
+}
+
+impl Role {
+    pub const ALL: [Self; 5] = [
+        Self::Admin,
+        Self::Reviewer,
+        Self::Analyst,
+        Self::Sampler,
+        Self::ReadOnly,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Reviewer => "reviewer",
+            Self::Analyst => "analyst",
+            Self::Sampler => "sampler",
+            Self::ReadOnly => "read_only",
+        }
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        use Permission::*;
+        match self {
+            Self::Admin => true,
+            Self::Reviewer => matches!(
+                permission,
+                EditClients | EditSites | RegisterSamples | RecordResults | ReviewResults
+            ),
+            Self::Analyst => matches!(permission, RecordResults),
+            Self::Sampler => matches!(permission, EditSites | RegisterSamples),
+            Self::ReadOnly => false,
+        }
+    }
+}
+
+impl Permission {
+    pub const ALL: [Self; 10] = [
+        Self::EditClients,
+        Self::EditSites,
+        Self::EditStandards,
+        Self::ManageQuotations,
+        Self::ManageInvoices,
+        Self::RegisterSamples,
+        Self::RecordResults,
+        Self::ReviewResults,
+        Self::DeleteRecords,
+        Self::ManageUsers,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EditClients => "edit_clients",
+            Self::EditSites => "edit_sites",
+            Self::EditStandards => "edit_standards",
+            Self::ManageQuotations => "manage_quotations",
+            Self::ManageInvoices => "manage_invoices",
+            Self::RegisterSamples => "register_samples",
+            Self::RecordResults => "record_results",
+            Self::ReviewResults => "review_results",
+            Self::DeleteRecords => "delete_records",
+            Self::ManageUsers => "manage_users",
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("unknown role {}", s))
+    }
+}
+
+impl ToSql for Role {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Role {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
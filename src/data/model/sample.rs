@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::sample_status::SampleStatus;
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Sample {
+    pub id_sample: Option<u32>,
+    pub sample_active: bool,
+    pub control_number: String,
+    pub project: String,
+    pub sampling_date: NaiveDate,
+    pub sampling_hour: String,
+    pub reception: NaiveDate,
+    pub id_sampler: Option<u32>,
+    pub id_site: Option<u32>,
+    // Solo cambia con las transiciones de `SampleManager`
+    pub sample_status: SampleStatus,
+    // Cotizacion de la que salio la muestra
+    pub id_quotation: Option<u32>,
+    // Partida de la cotizacion (paquete o analisis) con la que se factura
+    pub id_quotation_item: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Sample in "Sample" {
+        id: id_sample,
+        active: sample_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "reception DESC, control_number",
+        columns: [
+            sample_active,
+            control_number,
+            project,
+            sampling_date,
+            sampling_hour,
+            reception,
+            id_sampler,
+            id_site,
+            sample_status,
+            id_quotation,
+            id_quotation_item,
+        ],
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Analisis que se espera hacer a una muestra
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SampleAnalysis {
+    pub id_sample_analysis: Option<u32>,
+    pub id_sample: u32,
+    pub id_analysis: u32,
+}
+
+table_model! {
+    SampleAnalysis in "SampleAnalysis" {
+        id: id_sample_analysis,
+        order_by: "id_sample_analysis",
+        columns: [id_sample, id_analysis],
+    }
+}
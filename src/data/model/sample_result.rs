@@ -0,0 +1,42 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::result_value::ResultValue;
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SampleResult {
+    pub id_sample_result: Option<u32>,
+    pub result_active: bool,
+    pub result: ResultValue,
+    // Simbolo del catalogo de `logic::units`; None = unidad de la norma
+    pub result_unit: Option<String>,
+    pub analysis_date: NaiveDate,
+    pub id_signatary: Option<u32>,
+    pub id_analysis: Option<u32>,
+    pub id_standard: Option<u32>,
+    pub id_sample: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    SampleResult in "SampleResult" {
+        id: id_sample_result,
+        active: result_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "analysis_date DESC, id_sample_result",
+        columns: [
+            result_active,
+            result,
+            result_unit,
+            analysis_date,
+            id_signatary,
+            id_analysis,
+            id_standard,
+            id_sample,
+        ],
+    }
+}
@@ -0,0 +1,80 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+// Etapas de una muestra en el laboratorio, en orden
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleStatus {
+    #[default]
+    Received,
+    InAnalysis,
+    ResultsComplete,
+    Reviewed,
+    Reported,
+    Archived,
+}
+
+impl SampleStatus {
+    pub const ALL: [Self; 6] = [
+        Self::Received,
+        Self::InAnalysis,
+        Self::ResultsComplete,
+        Self::Reviewed,
+        Self::Reported,
+        Self::Archived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Received => "received",
+            Self::InAnalysis => "in_analysis",
+            Self::ResultsComplete => "results_complete",
+            Self::Reviewed => "reviewed",
+            Self::Reported => "reported",
+            Self::Archived => "archived",
+        }
+    }
+
+    // Estados a los que se puede pasar: el siguiente de la cadena o, si la
+    // revision encuentra un problema, de vuelta a analisis
+    pub fn next(&self) -> Vec<Self> {
+        match self {
+            Self::Received => vec![Self::InAnalysis],
+            Self::InAnalysis => vec![Self::ResultsComplete],
+            Self::ResultsComplete => vec![Self::Reviewed, Self::InAnalysis],
+            Self::Reviewed => vec![Self::Reported, Self::InAnalysis],
+            Self::Reported => vec![Self::Archived],
+            Self::Archived => Vec::new(),
+        }
+    }
+
+    pub fn can_change_to(&self, to: Self) -> bool {
+        self.next().contains(&to)
+    }
+}
+
+impl std::str::FromStr for SampleStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| format!("unknown sample status {}", s))
+    }
+}
+
+impl ToSql for SampleStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for SampleStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
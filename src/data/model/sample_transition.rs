@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::sample_status::SampleStatus;
+use crate::table_model;
+
+// Cambio de estado de una muestra: quien lo hizo y cuando. Las filas no se
+// modifican, por eso no llevan version ni fecha de cambio.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SampleTransition {
+    pub id_transition: Option<u32>,
+    pub id_sample: u32,
+    pub from_status: SampleStatus,
+    pub to_status: SampleStatus,
+    pub actor: String,
+    pub transitioned_at: DateTime<Utc>,
+}
+
+table_model! {
+    SampleTransition in "SampleTransition" {
+        id: id_transition,
+        order_by: "transitioned_at, id_transition",
+        columns: [id_sample, from_status, to_status, actor, transitioned_at],
+    }
+}
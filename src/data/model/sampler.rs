@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Sampler {
+    pub id_sampler: Option<u32>,
+    pub sampler_active: bool,
+    pub id_signatary: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Sampler in "Sampler" {
+        id: id_sampler,
+        active: sampler_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "id_sampler",
+        columns: [
+            sampler_active,
+            id_signatary,
+        ],
+    }
+}
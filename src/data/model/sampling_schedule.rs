@@ -0,0 +1,41 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::frequency::Frequency;
+use crate::table_model;
+
+// Monitoreo periodico de un sitio; sin fecha de fin se repite indefinidamente
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SamplingSchedule {
+    pub id_schedule: Option<u32>,
+    pub schedule_active: bool,
+    pub id_site: u32,
+    pub frequency: Frequency,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>,
+    // Muestreador que se asigna a las visitas al planearlas
+    pub id_sampler: Option<u32>,
+    pub project: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    SamplingSchedule in "SamplingSchedule" {
+        id: id_schedule,
+        active: schedule_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "id_site, starts_on",
+        columns: [
+            schedule_active,
+            id_site,
+            frequency,
+            starts_on,
+            ends_on,
+            id_sampler,
+            project,
+        ],
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Visita planeada de un programa; queda completa al registrar su muestra
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SamplingVisit {
+    pub id_visit: Option<u32>,
+    pub id_schedule: u32,
+    pub planned_date: NaiveDate,
+    pub id_sampler: Option<u32>,
+    pub id_sample: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    SamplingVisit in "SamplingVisit" {
+        id: id_visit,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "planned_date, id_visit",
+        columns: [
+            id_schedule,
+            planned_date,
+            id_sampler,
+            id_sample,
+        ],
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+// Analisis que se hacen a las muestras de un programa de muestreo
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ScheduleAnalysis {
+    pub id_schedule_analysis: Option<u32>,
+    pub id_schedule: u32,
+    pub id_analysis: u32,
+}
+
+table_model! {
+    ScheduleAnalysis in "ScheduleAnalysis" {
+        id: id_schedule_analysis,
+        order_by: "id_schedule_analysis",
+        columns: [id_schedule, id_analysis],
+    }
+}
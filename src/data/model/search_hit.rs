@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SearchEntity {
+    Client,
+    Site,
+    Sample,
+}
+
+impl SearchEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Site => "site",
+            Self::Sample => "sample",
+        }
+    }
+}
+
+impl std::str::FromStr for SearchEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "client" => Ok(Self::Client),
+            "site" => Ok(Self::Site),
+            "sample" => Ok(Self::Sample),
+            _ => Err(format!("unknown search entity {}", s)),
+        }
+    }
+}
+
+// Resultado de la busqueda global; `title` y `snippet` traen las
+// coincidencias marcadas entre corchetes
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub entity: SearchEntity,
+    pub id: u32,
+    pub active: bool,
+    pub title: String,
+    pub snippet: String,
+    pub rank: f64,
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::role::Role;
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Signatary {
+    pub id_signatary: Option<u32>,
+    pub signatary_active: bool,
+    pub first_name: String,
+    pub second_name: String,
+    pub father_last_name: String,
+    pub mother_last_name: String,
+    pub user: String,
+    pub passwd: String,
+    pub role: Role,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Signatary in "Signatary" {
+        id: id_signatary,
+        active: signatary_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "father_last_name, mother_last_name, first_name",
+        columns: [
+            signatary_active,
+            first_name,
+            second_name,
+            father_last_name,
+            mother_last_name,
+            user,
+            passwd,
+            role,
+        ],
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SignataryDetail {
+    pub id_sig_detail: Option<u32>,
+    pub id_signatary: u32,
+    pub id_analysis: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    SignataryDetail in "SignataryDetail" {
+        id: id_sig_detail,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "id_sig_detail",
+        columns: [
+            id_signatary,
+            id_analysis,
+        ],
+    }
+}
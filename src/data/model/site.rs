@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Site {
+    pub id_site: Option<u32>,
+    pub site_active: bool,
+    pub site_key: String,
+    pub site_name: String,
+    pub watershed: Option<String>,
+    pub aquifer_key: Option<String>,
+    pub aquifer: Option<String>,
+    pub watershed_org: Option<String>,
+    pub local_dir: Option<String>,
+    pub site_state: Option<String>,
+    pub site_municipality: Option<String>,
+    pub body_of_water: Option<String>,
+    pub body_of_water_type: Option<String>,
+    pub latitude: String,
+    pub longitude: String,
+    pub site_use: Option<String>,
+    pub sampling_site: Option<String>,
+    pub id_client: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Site in "Site" {
+        id: id_site,
+        active: site_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "site_name",
+        columns: [
+            site_active,
+            site_key,
+            site_name,
+            watershed,
+            aquifer_key,
+            aquifer,
+            watershed_org,
+            local_dir,
+            site_state,
+            site_municipality,
+            body_of_water,
+            body_of_water_type,
+            latitude,
+            longitude,
+            site_use,
+            sampling_site,
+            id_client,
+        ],
+    }
+}
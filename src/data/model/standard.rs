@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Standard {
+    pub id_standard: Option<u32>,
+    pub std_active: bool,
+    pub std_name: String,
+    pub units: String,
+    pub window_type: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    Standard in "Standard" {
+        id: id_standard,
+        active: std_active,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "std_name",
+        columns: [
+            std_active,
+            std_name,
+            units,
+            window_type,
+        ],
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::table_model;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct StandardDetail {
+    pub id_std_detail: Option<u32>,
+    pub id_standard: Option<u32>,
+    pub id_analysis: Option<u32>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub row_version: u32,
+}
+
+table_model! {
+    StandardDetail in "StandardDetail" {
+        id: id_std_detail,
+        timestamps: (created_at, updated_at),
+        version: row_version,
+        order_by: "id_std_detail",
+        columns: [
+            id_standard,
+            id_analysis,
+            min_value,
+            max_value,
+        ],
+    }
+}
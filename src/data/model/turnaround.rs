@@ -0,0 +1,18 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// Resultado de una muestra con la fecha de recepcion y los nombres de su
+// analisis, signatario y cliente
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultTurnaround {
+    pub id_sample: u32,
+    pub control_number: String,
+    pub reception: NaiveDate,
+    pub analysis_date: NaiveDate,
+    pub id_analysis: Option<u32>,
+    pub analysis_name: Option<String>,
+    pub id_signatary: Option<u32>,
+    pub signatary_name: Option<String>,
+    pub id_client: Option<u32>,
+    pub client_name: Option<String>,
+}
@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::analysis_package::AnalysisPackage;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type AnalysisPackageRepo = TableRepo<AnalysisPackage, PackageSearchCriteria>;
+
+search_criteria! {
+    PackageSearchCriteria {
+        id_package: u32 => eq,
+        package_active: bool => eq,
+        package_name: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,17 @@
+use super::table_repo::TableRepo;
+use crate::data::model::analysis::Analysis;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type AnalysisRepo = TableRepo<Analysis, AnalysisSearchCriteria>;
+
+search_criteria! {
+    AnalysisSearchCriteria {
+        id_analysis: u32 => eq,
+        analysis_active: bool => eq,
+        analysis_name: String => like,
+        id_parameter: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
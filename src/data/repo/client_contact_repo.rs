@@ -0,0 +1,18 @@
+use super::table_repo::TableRepo;
+use crate::data::model::client_contact::ClientContact;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type ClientContactRepo = TableRepo<ClientContact, ContactSearchCriteria>;
+
+search_criteria! {
+    ContactSearchCriteria {
+        id_contact: u32 => eq,
+        contact_active: bool => eq,
+        id_client: u32 => eq,
+        contact_name: String => like,
+        email: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,63 @@
+use super::conector::Connector;
+use crate::data::model::result_limits::ResultLimits;
+use crate::error::{Error, Result};
+
+// Resultados activos de la muestra activa mas reciente del sitio que tenga
+// resultados, con los limites de la norma asignada a cada resultado
+const LATEST_SITE_RESULTS: &str = "
+    SELECT Sample.id_sample, Sample.control_number, Sample.sampling_date,
+           SampleResult.id_analysis, SampleResult.result,
+           StandardDetail.min_value, StandardDetail.max_value,
+           SampleResult.result_unit, Standard.units, Analysis.detection_limit
+    FROM Sample
+    JOIN SampleResult ON SampleResult.id_sample = Sample.id_sample
+    LEFT JOIN StandardDetail
+        ON StandardDetail.id_standard = SampleResult.id_standard
+        AND StandardDetail.id_analysis = SampleResult.id_analysis
+    LEFT JOIN Standard ON Standard.id_standard = SampleResult.id_standard
+    LEFT JOIN Analysis ON Analysis.id_analysis = SampleResult.id_analysis
+    WHERE SampleResult.result_active = 1
+      AND Sample.id_sample = (
+        SELECT Sample.id_sample
+        FROM Sample JOIN SampleResult ON SampleResult.id_sample = Sample.id_sample
+        WHERE Sample.id_site = ?1 AND Sample.sample_active = 1
+          AND SampleResult.result_active = 1
+        ORDER BY Sample.sampling_date DESC, Sample.id_sample DESC
+        LIMIT 1)
+    ORDER BY SampleResult.id_sample_result";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceRepo {
+    connector: Connector,
+}
+
+impl ComplianceRepo {
+    pub fn new(connector: Connector) -> Self {
+        Self { connector }
+    }
+
+    pub fn latest_site_results(&self, id_site: u32) -> Result<Vec<ResultLimits>> {
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn
+            .prepare(LATEST_SITE_RESULTS)
+            .map_err(|e| Error::query(e, LATEST_SITE_RESULTS))?;
+        let mut rows = stmt.query([id_site])?;
+
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            results.push(ResultLimits {
+                id_sample: row.get(0).map_err(Error::from_row("id_sample"))?,
+                control_number: row.get(1).map_err(Error::from_row("control_number"))?,
+                sampling_date: row.get(2).map_err(Error::from_row("sampling_date"))?,
+                id_analysis: row.get(3).map_err(Error::from_row("id_analysis"))?,
+                result: row.get(4).map_err(Error::from_row("result"))?,
+                min_value: row.get(5).map_err(Error::from_row("min_value"))?,
+                max_value: row.get(6).map_err(Error::from_row("max_value"))?,
+                result_unit: row.get(7).map_err(Error::from_row("result_unit"))?,
+                std_units: row.get(8).map_err(Error::from_row("std_units"))?,
+                detection_limit: row.get(9).map_err(Error::from_row("detection_limit"))?,
+            });
+        }
+        Ok(results)
+    }
+}
@@ -1,172 +1,111 @@
-use dotenv::dotenv;
-use dotenv::Error as DotenvError;
+use rusqlite::functions::FunctionFlags;
 use rusqlite::Connection;
-use rusqlite::Error as RusqliteError;
-use std::env::VarError;
 use std::fs;
-use std::io::Error as IoError;
 use std::path::Path;
 
-#[derive(Debug)]
-pub enum Error<'a> {
-    RusqliteError {
-        source: RusqliteError,
-        file: &'a str,
-        line: u32,
-    },
-    DotenvError {
-        source: DotenvError,
-        file: &'a str,
-        line: u32,
-    },
-    DotenvVarError {
-        source: VarError,
-        file: &'a str,
-        line: u32,
-    },
-    IoError {
-        source: IoError,
-        file: &'a str,
-        line: u32,
-    },
-    MissingEnvVarError {
-        key: String,
-        file: &'a str,
-        line: u32,
-    },
-    SqlExecutionError {
-        source: RusqliteError,
-        file: &'a str,
-        line: u32,
-    },
+use super::migrations;
+use crate::config::{Config, LogLevel};
+use crate::data::model::coordinate::{parse_degrees, Axis};
+use crate::error::{Error, Result};
+use crate::i18n::{t, tf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connector {
+    database_url: String,
+    init_script: String,
+    log_level: LogLevel,
 }
 
-impl<'a> std::fmt::Display for Error<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::RusqliteError { source, file, line } => {
-                write!(f, "RusqliteError: {} (on {}: {})", source, file, line)
-            }
-            Self::DotenvError { source, file, line } => {
-                write!(f, "DotenvError: {} (on {}: {})", source, file, line)
-            }
-            Self::IoError { source, file, line } => {
-                write!(f, "IoError: {} (on {}: {})", source, file, line)
-            }
-            Self::MissingEnvVarError { key, file, line } => {
-                write!(
-                    f,
-                    "MissingEnvVarError: Missing var {} (on {}: {})",
-                    key, file, line
-                )
-            }
-            Self::DotenvVarError { source, file, line } => {
-                write!(f, "DotenvVarError: {} (on {}: {})", source, file, line)
-            }
-            Self::SqlExecutionError { source, file, line } => {
-                write!(f, "SqlExecutionError: {} (on {}: {})", source, file, line)
-            }
+#[allow(unused)]
+impl Connector {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            database_url: config.database_url.clone(),
+            init_script: config.database_init_script.clone(),
+            log_level: config.log_level,
         }
     }
-}
 
-impl<'a> std::error::Error for Error<'a> {}
-
-fn get_env_var<'a>(key: &'a str, file: &'a str, line: u32) -> Result<String, Error<'a>> {
-    match dotenv::var(key) {
-        Ok(key) => Ok(key),
-        Err(e) => match e {
-            DotenvError::EnvVar(var_err) => match var_err {
-                VarError::NotPresent => {
-                    let key = key.to_string();
-                    let e = Error::MissingEnvVarError { key, file, line };
-                    return Err(e);
-                }
-                _ => {
-                    let e = Error::DotenvVarError {
-                        source: var_err,
-                        line,
-                        file,
-                    };
-                    return Err(e);
-                }
-            },
-            _ => {
-                let e = Error::DotenvError {
-                    source: e,
-                    file: file!(),
-                    line: line!(),
-                };
-                return Err(e);
-            }
-        },
+    pub fn database_url(&self) -> &str {
+        &self.database_url
     }
-}
 
-pub struct Connector;
-
-#[allow(unused)]
-impl Connector {
-    pub fn get_connection<'a>() -> Result<Connection, Error<'a>> {
-        dotenv().ok(); // Load environment variables from the .env file
-        let database_url = get_env_var("DATABASE_URL", file!(), line!())?;
-        let conn = Connection::open(database_url).map_err(|e| Error::RusqliteError {
-            source: e,
-            file: file!(),
-            line: line!(),
-        })?;
+    pub fn get_connection(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.database_url)?;
+        // SQLite no revisa las llaves foraneas si no se pide en cada conexion
+        conn.pragma_update(None, "foreign_keys", true)?;
+        register_functions(&conn)?;
         Ok(conn)
     }
 
-    pub fn db_exists<'a>() -> Result<bool, Error<'a>> {
-        dotenv().ok(); // Load environment variables from the .env file
-        let database_url = get_env_var("DATABASE_URL", file!(), line!())?;
-        let exists = Path::new(&database_url).exists();
-        Ok(exists)
+    pub fn db_exists(&self) -> bool {
+        Path::new(&self.database_url).exists()
     }
 
-    pub fn db_init<'a>() -> Result<(), Error<'a>> {
+    pub fn db_init(&self) -> Result<()> {
         // Check if the database exists
-        let db_exists = Self::db_exists()?;
-        if !db_exists {
-            let database_url = get_env_var("DATABASE_URL", file!(), line!())?;
-            println!("The database does not exist, proceeding to create it...");
+        if !self.db_exists() {
+            self.info(&t("db.creating"));
 
-            // Create the database
-            let script_path = get_env_var("DATABASE_INIT_SCRIPT", file!(), line!())?;
-            let conn = Self::get_connection()?;
-            println!("Initialization script located at: {}", script_path);
+            self.info(&tf("db.script_location", &[("path", &self.init_script)]));
+
+            // Read the SQL script before touching the database file
+            let sql = fs::read_to_string(&self.init_script)?;
 
-            // Read the SQL script
-            let sql = fs::read_to_string(&script_path).map_err(|e| Error::IoError {
-                source: e,
-                file: file!(),
-                line: line!(),
-            })?;
+            // Create the database
+            let conn = self.get_connection()?;
 
             // Temporarily create the database file
             let result = conn.execute_batch(&sql);
             // Delete the database file if an error occurs
             if let Err(e) = result {
-                if Path::new(&database_url).exists() {
-                    fs::remove_file(&database_url).map_err(|e| Error::IoError {
-                        source: e,
-                        file: file!(),
-                        line: line!(),
-                    })?;
+                if self.db_exists() {
+                    fs::remove_file(&self.database_url)?;
                 }
-                return Err(Error::SqlExecutionError {
-                    source: e,
-                    file: file!(),
-                    line: line!(),
-                });
+                return Err(Error::query(
+                    e,
+                    &format!("init script {}", self.init_script),
+                ));
             }
 
             // If the result was Ok, the database file was created successfully
-            println!("The database was created successfully.");
+            self.info(&t("db.created"));
         } else {
-            println!("The database already exists.");
+            self.info(&t("db.exists"));
+        }
+
+        self.migrate()
+    }
+
+    // Apply the schema changes added after the initialization script
+    pub fn migrate(&self) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        for name in migrations::migrate(&mut conn)? {
+            self.info(&tf("db.migrated", &[("name", &name)]));
         }
         Ok(())
     }
+
+    fn info(&self, msg: &str) {
+        if self.log_level.enabled(LogLevel::Info) {
+            println!("{}", msg);
+        }
+    }
+}
+
+// Funciones SQL propias: `latitude_degrees(texto)` y `longitude_degrees(texto)`
+// convierten las coordenadas guardadas (decimal o GMS) a grados decimales, o
+// NULL si no son validas
+fn register_functions(conn: &Connection) -> Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    for (name, axis) in [
+        ("latitude_degrees", Axis::Latitude),
+        ("longitude_degrees", Axis::Longitude),
+    ] {
+        conn.create_scalar_function(name, 1, flags, move |ctx| {
+            let text: Option<String> = ctx.get(0)?;
+            Ok(text.and_then(|text| parse_degrees(&text, axis)))
+        })?;
+    }
+    Ok(())
 }
@@ -0,0 +1,83 @@
+use rusqlite::{Connection, TransactionBehavior};
+
+use super::conector::Connector;
+use super::sample_repo::SampleRepo;
+use crate::data::model::sample::Sample;
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+// Numeros ya ocupados (capturados a mano) que se saltan antes de rendirse
+const MAX_ATTEMPTS: u32 = 100;
+
+const NEXT_VALUE: &str = "
+    INSERT INTO ControlNumberSequence (seq_year, last_value) VALUES (?1, 1)
+    ON CONFLICT (seq_year) DO UPDATE SET last_value = last_value + 1
+    RETURNING last_value";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlNumberRepo {
+    connector: Connector,
+}
+
+impl ControlNumberRepo {
+    pub fn new(connector: Connector) -> Self {
+        Self { connector }
+    }
+
+    // Toma el siguiente consecutivo del año e inserta la muestra con el numero
+    // que arma `format`; devuelve el id asignado. La transaccion IMMEDIATE bloquea a los demas
+    // escritores hasta el commit, asi dos altas simultaneas no pueden tomar el
+    // mismo consecutivo; si el numero ya existe se avanza al siguiente.
+    pub fn insert_numbered<F>(&self, sample: &Sample, year: i32, format: F) -> Result<u32>
+    where
+        F: Fn(u64) -> String,
+    {
+        let mut conn = self.connector.get_connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let id = Self::insert_numbered_in(&tx, sample, year, format)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    // Igual que `insert_numbered` dentro de una transaccion ya abierta
+    pub fn insert_numbered_in<F>(
+        conn: &Connection,
+        sample: &Sample,
+        year: i32,
+        format: F,
+    ) -> Result<u32>
+    where
+        F: Fn(u64) -> String,
+    {
+        let mut sample = sample.clone();
+        for _ in 0..MAX_ATTEMPTS {
+            let seq: u64 = conn
+                .query_row(NEXT_VALUE, [year], |row| row.get(0))
+                .map_err(|e| Error::query(e, NEXT_VALUE))?;
+            sample.control_number = format(seq);
+
+            match SampleRepo::insert_in(conn, &sample) {
+                Ok(id) => return Ok(id),
+                Err(e) if e.is_unique_violation() => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::InvalidField {
+            entity: "Sample",
+            errors: vec![Message::new("sample.error.control_number_exhausted")
+                .arg("number", &sample.control_number)],
+        })
+    }
+
+    pub fn last_value(&self, year: i32) -> Result<u64> {
+        let query = "SELECT last_value FROM ControlNumberSequence WHERE seq_year = ?";
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn.prepare(query).map_err(|e| Error::query(e, query))?;
+        let mut rows = stmt.query([year])?;
+        match rows.next()? {
+            Some(row) => Ok(row.get(0).map_err(Error::from_row("last_value"))?),
+            None => Ok(0),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use rusqlite::{Connection, OptionalExtension};
+
+use super::table_repo::TableRepo;
+use crate::data::model::invoice_line::InvoiceLine;
+use crate::error::{Error, Result};
+use crate::search_criteria;
+
+pub type InvoiceLineRepo = TableRepo<InvoiceLine, InvoiceLineSearchCriteria>;
+
+search_criteria! {
+    InvoiceLineSearchCriteria {
+        id_line: u32 => eq,
+        id_invoice: u32 => eq,
+        id_sample: u32 => eq,
+        id_analysis: u32 => eq,
+        id_item: u32 => eq,
+    }
+}
+
+const INVOICE_OF_SAMPLE: &str = "
+    SELECT Invoice.id_invoice FROM InvoiceLine
+    JOIN Invoice ON Invoice.id_invoice = InvoiceLine.id_invoice
+    WHERE InvoiceLine.id_sample = ?1
+      AND Invoice.invoice_active = 1
+      AND Invoice.invoice_status <> 'cancelled'
+    LIMIT 1";
+
+// Factura vigente (activa y no cancelada) en la que ya esta una muestra
+pub struct InvoicedSampleRepo;
+
+impl InvoicedSampleRepo {
+    // Dentro de la transaccion IMMEDIATE del borrador, para que dos borradores
+    // simultaneos no facturen la misma muestra
+    pub fn invoice_of_in(conn: &Connection, id_sample: u32) -> Result<Option<u32>> {
+        conn.query_row(INVOICE_OF_SAMPLE, [id_sample], |row| row.get(0))
+            .optional()
+            .map_err(|e| Error::query(e, INVOICE_OF_SAMPLE))
+    }
+}
@@ -0,0 +1,20 @@
+use rusqlite::Connection;
+
+use crate::error::{Error, Result};
+
+const NEXT_VALUE: &str = "
+    INSERT INTO InvoiceSequence (seq_year, last_value) VALUES (?1, 1)
+    ON CONFLICT (seq_year) DO UPDATE SET last_value = last_value + 1
+    RETURNING last_value";
+
+// Consecutivo de los folios de factura, uno por año
+pub struct InvoiceNumberRepo;
+
+impl InvoiceNumberRepo {
+    // Debe llamarse dentro de una transaccion IMMEDIATE para que dos emisiones
+    // simultaneas no tomen el mismo folio
+    pub fn next_value_in(conn: &Connection, year: i32) -> Result<u64> {
+        conn.query_row(NEXT_VALUE, [year], |row| row.get(0))
+            .map_err(|e| Error::query(e, NEXT_VALUE))
+    }
+}
@@ -0,0 +1,19 @@
+use super::table_repo::TableRepo;
+use crate::data::model::invoice::Invoice;
+use crate::data::model::invoice_status::InvoiceStatus;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type InvoiceRepo = TableRepo<Invoice, InvoiceSearchCriteria>;
+
+search_criteria! {
+    InvoiceSearchCriteria {
+        id_invoice: u32 => eq,
+        invoice_active: bool => eq,
+        invoice_number: String => like,
+        id_client: u32 => eq,
+        invoice_status: InvoiceStatus => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
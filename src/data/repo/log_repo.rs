@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::log::Log;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type LogRepo = TableRepo<Log, LogSearchCriteria>;
+
+search_criteria! {
+    LogSearchCriteria {
+        id_log: u32 => eq,
+        log_active: bool => eq,
+        msg: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
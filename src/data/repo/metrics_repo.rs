@@ -0,0 +1,56 @@
+use super::conector::Connector;
+use crate::data::model::turnaround::ResultTurnaround;
+use crate::error::{Error, Result};
+
+// Resultados activos de muestras activas; el cliente sale del sitio de la muestra
+const RESULT_TURNAROUNDS: &str = "
+    SELECT Sample.id_sample, Sample.control_number, Sample.reception,
+           SampleResult.analysis_date,
+           SampleResult.id_analysis, Analysis.analysis_name,
+           SampleResult.id_signatary,
+           Signatary.first_name || ' ' || Signatary.father_last_name,
+           Client.id_client, Client.client_name
+    FROM SampleResult
+    JOIN Sample ON Sample.id_sample = SampleResult.id_sample
+    LEFT JOIN Analysis ON Analysis.id_analysis = SampleResult.id_analysis
+    LEFT JOIN Signatary ON Signatary.id_signatary = SampleResult.id_signatary
+    LEFT JOIN Site ON Site.id_site = Sample.id_site
+    LEFT JOIN Client ON Client.id_client = Site.id_client
+    WHERE SampleResult.result_active = 1 AND Sample.sample_active = 1
+    ORDER BY Sample.reception, SampleResult.id_sample_result";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsRepo {
+    connector: Connector,
+}
+
+impl MetricsRepo {
+    pub fn new(connector: Connector) -> Self {
+        Self { connector }
+    }
+
+    pub fn result_turnarounds(&self) -> Result<Vec<ResultTurnaround>> {
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn
+            .prepare(RESULT_TURNAROUNDS)
+            .map_err(|e| Error::query(e, RESULT_TURNAROUNDS))?;
+        let mut rows = stmt.query([])?;
+
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            results.push(ResultTurnaround {
+                id_sample: row.get(0).map_err(Error::from_row("id_sample"))?,
+                control_number: row.get(1).map_err(Error::from_row("control_number"))?,
+                reception: row.get(2).map_err(Error::from_row("reception"))?,
+                analysis_date: row.get(3).map_err(Error::from_row("analysis_date"))?,
+                id_analysis: row.get(4).map_err(Error::from_row("id_analysis"))?,
+                analysis_name: row.get(5).map_err(Error::from_row("analysis_name"))?,
+                id_signatary: row.get(6).map_err(Error::from_row("id_signatary"))?,
+                signatary_name: row.get(7).map_err(Error::from_row("signatary_name"))?,
+                id_client: row.get(8).map_err(Error::from_row("id_client"))?,
+                client_name: row.get(9).map_err(Error::from_row("client_name"))?,
+            });
+        }
+        Ok(results)
+    }
+}
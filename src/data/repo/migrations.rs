@@ -0,0 +1,147 @@
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+
+use crate::error::{Error, Result};
+use crate::logic::password;
+
+// Cambios de esquema posteriores a db/script.sql. La version aplicada se
+// guarda en `PRAGMA user_version`; nunca modificar una migracion publicada,
+// agregar una nueva al final.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "full_text_search",
+        sql: include_str!("../../../db/migrations/0001_full_text_search.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "standard_limits",
+        sql: include_str!("../../../db/migrations/0002_standard_limits.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "control_numbers",
+        sql: include_str!("../../../db/migrations/0003_control_numbers.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "timestamps",
+        sql: include_str!("../../../db/migrations/0004_timestamps.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "row_version",
+        sql: include_str!("../../../db/migrations/0005_row_version.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "client_contacts",
+        sql: include_str!("../../../db/migrations/0006_client_contacts.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "sample_status",
+        sql: include_str!("../../../db/migrations/0007_sample_status.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "result_units",
+        sql: include_str!("../../../db/migrations/0008_result_units.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "detection_limits",
+        sql: include_str!("../../../db/migrations/0009_detection_limits.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "signatary_roles",
+        sql: include_str!("../../../db/migrations/0010_signatary_roles.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "result_signatures",
+        sql: include_str!("../../../db/migrations/0011_result_signatures.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "sampling_schedule",
+        sql: include_str!("../../../db/migrations/0012_sampling_schedule.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "quotations",
+        sql: include_str!("../../../db/migrations/0013_quotations.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "invoices",
+        sql: include_str!("../../../db/migrations/0014_invoices.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "password_hashes",
+        sql: include_str!("../../../db/migrations/0015_password_hashes.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "signed_result_lock",
+        sql: include_str!("../../../db/migrations/0016_signed_result_lock.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "invoiced_sample_once",
+        sql: include_str!("../../../db/migrations/0017_invoiced_sample_once.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "quoted_invoice_prices",
+        sql: include_str!("../../../db/migrations/0018_quoted_invoice_prices.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "contact_cascade",
+        sql: include_str!("../../../db/migrations/0019_contact_cascade.sql"),
+    },
+];
+
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+// Aplica en orden las migraciones pendientes, cada una en su transaccion.
+// Devuelve los nombres de las migraciones aplicadas.
+pub fn migrate(conn: &mut Connection) -> Result<Vec<&'static str>> {
+    let mut applied = Vec::new();
+    let version = current_version(conn)?;
+    register_functions(conn)?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)
+            .map_err(|e| Error::query(e, &format!("migration {}", migration.name)))?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        applied.push(migration.name);
+    }
+
+    Ok(applied)
+}
+
+// `password_hash(texto)` cifra las contraseñas que siguen en texto plano; no
+// es determinista porque cada hash lleva su propia sal
+fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function("password_hash", 1, FunctionFlags::SQLITE_UTF8, |ctx| {
+        let text: Option<String> = ctx.get(0)?;
+        text.map(|text| password::hash(&text))
+            .transpose()
+            .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e)))
+    })?;
+    Ok(())
+}
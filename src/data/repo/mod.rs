@@ -1,2 +1,35 @@
+pub mod analysis_package_repo;
+pub mod analysis_repo;
+pub mod client_contact_repo;
 pub mod client_repo;
+pub mod compliance_repo;
 pub mod conector;
+pub mod control_number_repo;
+pub mod invoice_line_repo;
+pub mod invoice_number_repo;
+pub mod invoice_repo;
+pub mod log_repo;
+pub mod metrics_repo;
+pub mod migrations;
+pub mod package_detail_repo;
+pub mod parameter_repo;
+pub mod planning_repo;
+pub mod quotation_item_repo;
+pub mod quotation_repo;
+pub mod result_signature_repo;
+pub mod sample_analysis_repo;
+pub mod sample_repo;
+pub mod sample_result_repo;
+pub mod sample_transition_repo;
+pub mod sampler_repo;
+pub mod sampling_schedule_repo;
+pub mod sampling_visit_repo;
+pub mod schedule_analysis_repo;
+pub mod search_repo;
+pub mod signatary_detail_repo;
+pub mod signatary_repo;
+pub mod site_repo;
+pub mod standard_detail_repo;
+pub mod standard_repo;
+pub mod table_repo;
+pub mod trend_repo;
@@ -0,0 +1,13 @@
+use super::table_repo::TableRepo;
+use crate::data::model::package_detail::PackageDetail;
+use crate::search_criteria;
+
+pub type PackageDetailRepo = TableRepo<PackageDetail, PackageDetailSearchCriteria>;
+
+search_criteria! {
+    PackageDetailSearchCriteria {
+        id_package_detail: u32 => eq,
+        id_package: u32 => eq,
+        id_analysis: u32 => eq,
+    }
+}
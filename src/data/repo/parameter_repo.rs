@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::parameter::Parameter;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type ParameterRepo = TableRepo<Parameter, ParameterSearchCriteria>;
+
+search_criteria! {
+    ParameterSearchCriteria {
+        id_parameter: u32 => eq,
+        parameter_active: bool => eq,
+        parameter_name: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
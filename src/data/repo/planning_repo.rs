@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+
+use super::conector::Connector;
+use crate::data::model::planned_visit::PlannedVisit;
+use crate::error::{Error, Result};
+
+// Visitas sin muestra de programas activos hasta una fecha; el muestreador
+// se muestra con su usuario
+const PENDING_VISITS: &str = "
+    SELECT SamplingVisit.id_visit, SamplingVisit.id_schedule,
+           SamplingVisit.planned_date, SamplingSchedule.project,
+           Site.id_site, Site.site_key, Site.site_name, Client.client_name,
+           SamplingVisit.id_sampler, Signatary.user
+    FROM SamplingVisit
+    JOIN SamplingSchedule ON SamplingSchedule.id_schedule = SamplingVisit.id_schedule
+    JOIN Site ON Site.id_site = SamplingSchedule.id_site
+    LEFT JOIN Client ON Client.id_client = Site.id_client
+    LEFT JOIN Sampler ON Sampler.id_sampler = SamplingVisit.id_sampler
+    LEFT JOIN Signatary ON Signatary.id_signatary = Sampler.id_signatary
+    WHERE SamplingVisit.id_sample IS NULL
+      AND SamplingSchedule.schedule_active = 1
+      AND SamplingVisit.planned_date <= ?1
+    ORDER BY SamplingVisit.planned_date, Site.site_key, SamplingVisit.id_visit";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanningRepo {
+    connector: Connector,
+}
+
+impl PlanningRepo {
+    pub fn new(connector: Connector) -> Self {
+        Self { connector }
+    }
+
+    pub fn pending_visits(&self, until: NaiveDate) -> Result<Vec<PlannedVisit>> {
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn
+            .prepare(PENDING_VISITS)
+            .map_err(|e| Error::query(e, PENDING_VISITS))?;
+        let mut rows = stmt.query([until])?;
+
+        let mut visits = Vec::new();
+        while let Some(row) = rows.next()? {
+            visits.push(PlannedVisit {
+                id_visit: row.get(0).map_err(Error::from_row("id_visit"))?,
+                id_schedule: row.get(1).map_err(Error::from_row("id_schedule"))?,
+                planned_date: row.get(2).map_err(Error::from_row("planned_date"))?,
+                project: row.get(3).map_err(Error::from_row("project"))?,
+                id_site: row.get(4).map_err(Error::from_row("id_site"))?,
+                site_key: row.get(5).map_err(Error::from_row("site_key"))?,
+                site_name: row.get(6).map_err(Error::from_row("site_name"))?,
+                client_name: row.get(7).map_err(Error::from_row("client_name"))?,
+                id_sampler: row.get(8).map_err(Error::from_row("id_sampler"))?,
+                sampler_user: row.get(9).map_err(Error::from_row("sampler_user"))?,
+            });
+        }
+        Ok(visits)
+    }
+}
@@ -0,0 +1,14 @@
+use super::table_repo::TableRepo;
+use crate::data::model::quotation_item::QuotationItem;
+use crate::search_criteria;
+
+pub type QuotationItemRepo = TableRepo<QuotationItem, QuotationItemSearchCriteria>;
+
+search_criteria! {
+    QuotationItemSearchCriteria {
+        id_item: u32 => eq,
+        id_quotation: u32 => eq,
+        id_package: u32 => eq,
+        id_analysis: u32 => eq,
+    }
+}
@@ -0,0 +1,17 @@
+use super::table_repo::TableRepo;
+use crate::data::model::quotation::Quotation;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type QuotationRepo = TableRepo<Quotation, QuotationSearchCriteria>;
+
+search_criteria! {
+    QuotationSearchCriteria {
+        id_quotation: u32 => eq,
+        quotation_active: bool => eq,
+        id_client: u32 => eq,
+        project: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,15 @@
+use super::table_repo::TableRepo;
+use crate::data::model::result_signature::ResultSignature;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type ResultSignatureRepo = TableRepo<ResultSignature, SignatureSearchCriteria>;
+
+search_criteria! {
+    SignatureSearchCriteria {
+        id_signature: u32 => eq,
+        id_sample_result: u32 => eq,
+        id_signatary: u32 => eq,
+        signed_at: DateRange => within,
+    }
+}
@@ -0,0 +1,13 @@
+use super::table_repo::TableRepo;
+use crate::data::model::sample_analysis::SampleAnalysis;
+use crate::search_criteria;
+
+pub type SampleAnalysisRepo = TableRepo<SampleAnalysis, SampleAnalysisSearchCriteria>;
+
+search_criteria! {
+    SampleAnalysisSearchCriteria {
+        id_sample_analysis: u32 => eq,
+        id_sample: u32 => eq,
+        id_analysis: u32 => eq,
+    }
+}
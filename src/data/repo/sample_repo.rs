@@ -0,0 +1,26 @@
+use chrono::NaiveDate;
+
+use super::table_repo::TableRepo;
+use crate::data::model::sample::Sample;
+use crate::data::model::sample_status::SampleStatus;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SampleRepo = TableRepo<Sample, SampleSearchCriteria>;
+
+search_criteria! {
+    SampleSearchCriteria {
+        id_sample: u32 => eq,
+        sample_active: bool => eq,
+        control_number: String => like,
+        project: String => like,
+        sampling_date: NaiveDate => eq,
+        reception: NaiveDate => eq,
+        id_sampler: u32 => eq,
+        id_site: u32 => eq,
+        id_quotation: u32 => eq,
+        sample_status: SampleStatus => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::NaiveDate;
+
+use super::table_repo::TableRepo;
+use crate::data::model::sample_result::SampleResult;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SampleResultRepo = TableRepo<SampleResult, SampleResultSearchCriteria>;
+
+search_criteria! {
+    SampleResultSearchCriteria {
+        id_sample_result: u32 => eq,
+        result_active: bool => eq,
+        analysis_date: NaiveDate => eq,
+        id_signatary: u32 => eq,
+        id_analysis: u32 => eq,
+        id_standard: u32 => eq,
+        id_sample: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
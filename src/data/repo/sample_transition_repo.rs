@@ -0,0 +1,17 @@
+use super::table_repo::TableRepo;
+use crate::data::model::sample_status::SampleStatus;
+use crate::data::model::sample_transition::SampleTransition;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SampleTransitionRepo = TableRepo<SampleTransition, TransitionSearchCriteria>;
+
+search_criteria! {
+    TransitionSearchCriteria {
+        id_transition: u32 => eq,
+        id_sample: u32 => eq,
+        to_status: SampleStatus => eq,
+        actor: String => like,
+        transitioned_at: DateRange => within,
+    }
+}
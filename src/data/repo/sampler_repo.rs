@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::sampler::Sampler;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SamplerRepo = TableRepo<Sampler, SamplerSearchCriteria>;
+
+search_criteria! {
+    SamplerSearchCriteria {
+        id_sampler: u32 => eq,
+        sampler_active: bool => eq,
+        id_signatary: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,18 @@
+use super::table_repo::TableRepo;
+use crate::data::model::sampling_schedule::SamplingSchedule;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SamplingScheduleRepo = TableRepo<SamplingSchedule, ScheduleSearchCriteria>;
+
+search_criteria! {
+    ScheduleSearchCriteria {
+        id_schedule: u32 => eq,
+        schedule_active: bool => eq,
+        id_site: u32 => eq,
+        id_sampler: u32 => eq,
+        project: String => like,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
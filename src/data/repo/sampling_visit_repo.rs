@@ -0,0 +1,17 @@
+use super::table_repo::TableRepo;
+use crate::data::model::sampling_visit::SamplingVisit;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SamplingVisitRepo = TableRepo<SamplingVisit, VisitSearchCriteria>;
+
+search_criteria! {
+    VisitSearchCriteria {
+        id_visit: u32 => eq,
+        id_schedule: u32 => eq,
+        id_sampler: u32 => eq,
+        id_sample: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
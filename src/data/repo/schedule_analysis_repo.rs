@@ -0,0 +1,13 @@
+use super::table_repo::TableRepo;
+use crate::data::model::schedule_analysis::ScheduleAnalysis;
+use crate::search_criteria;
+
+pub type ScheduleAnalysisRepo = TableRepo<ScheduleAnalysis, ScheduleAnalysisSearchCriteria>;
+
+search_criteria! {
+    ScheduleAnalysisSearchCriteria {
+        id_schedule_analysis: u32 => eq,
+        id_schedule: u32 => eq,
+        id_analysis: u32 => eq,
+    }
+}
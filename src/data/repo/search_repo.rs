@@ -0,0 +1,102 @@
+use rusqlite::params;
+
+use super::conector::Connector;
+use crate::data::model::search_hit::SearchHit;
+use crate::error::{Error, Result};
+
+pub const HIGHLIGHT_START: &str = "[";
+pub const HIGHLIGHT_END: &str = "]";
+
+// Una subconsulta por indice; bm25 es menor mientras mas relevante, por eso
+// se ordena de forma ascendente
+const SEARCH_QUERY: &str = "
+    SELECT 'client', ClientFts.rowid, Client.client_active,
+           highlight(ClientFts, 0, ?3, ?4),
+           snippet(ClientFts, -1, ?3, ?4, '...', 10),
+           bm25(ClientFts) AS rank
+    FROM ClientFts JOIN Client ON Client.id_client = ClientFts.rowid
+    WHERE ClientFts MATCH ?1
+    UNION ALL
+    SELECT 'site', SiteFts.rowid, Site.site_active,
+           Site.site_key || ' ' || highlight(SiteFts, 1, ?3, ?4),
+           snippet(SiteFts, -1, ?3, ?4, '...', 10),
+           bm25(SiteFts, 2.0, 1.0, 0.5) AS rank
+    FROM SiteFts JOIN Site ON Site.id_site = SiteFts.rowid
+    WHERE SiteFts MATCH ?1
+    UNION ALL
+    SELECT 'sample', SampleFts.rowid, Sample.sample_active,
+           highlight(SampleFts, 0, ?3, ?4),
+           snippet(SampleFts, -1, ?3, ?4, '...', 10),
+           bm25(SampleFts, 2.0, 1.0) AS rank
+    FROM SampleFts JOIN Sample ON Sample.id_sample = SampleFts.rowid
+    WHERE SampleFts MATCH ?1
+    ORDER BY rank
+    LIMIT ?2";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchRepo {
+    connector: Connector,
+    limit: u64,
+}
+
+impl SearchRepo {
+    pub fn new(connector: Connector, limit: u64) -> Self {
+        Self { connector, limit }
+    }
+
+    pub fn search(&self, text: &str) -> Result<Vec<SearchHit>> {
+        let Some(fts_query) = fts_query(text) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn
+            .prepare(SEARCH_QUERY)
+            .map_err(|e| Error::query(e, SEARCH_QUERY))?;
+        let mut rows = stmt.query(params![
+            fts_query,
+            self.limit,
+            HIGHLIGHT_START,
+            HIGHLIGHT_END
+        ])?;
+
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next()? {
+            let entity: String = row.get(0).map_err(Error::from_row("entity"))?;
+            hits.push(SearchHit {
+                entity: entity.parse().map_err(|_| Error::FromRow {
+                    column: "entity",
+                    source: rusqlite::Error::InvalidColumnType(
+                        0,
+                        entity,
+                        rusqlite::types::Type::Text,
+                    ),
+                })?,
+                id: row.get(1).map_err(Error::from_row("rowid"))?,
+                active: row.get(2).map_err(Error::from_row("active"))?,
+                title: row.get(3).map_err(Error::from_row("title"))?,
+                snippet: row.get(4).map_err(Error::from_row("snippet"))?,
+                rank: row.get(5).map_err(Error::from_row("rank"))?,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+// Convierte el texto del usuario en una consulta FTS5 segura: cada palabra
+// se busca como prefijo y todas deben aparecer. Las comillas se descartan
+// para que la sintaxis de FTS5 no se interprete.
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
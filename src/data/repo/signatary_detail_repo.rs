@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::signatary_detail::SignataryDetail;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SignataryDetailRepo = TableRepo<SignataryDetail, SignataryDetailSearchCriteria>;
+
+search_criteria! {
+    SignataryDetailSearchCriteria {
+        id_sig_detail: u32 => eq,
+        id_signatary: u32 => eq,
+        id_analysis: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
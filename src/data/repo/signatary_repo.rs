@@ -0,0 +1,21 @@
+use super::table_repo::TableRepo;
+use crate::data::model::role::Role;
+use crate::data::model::signatary::Signatary;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type SignataryRepo = TableRepo<Signatary, SignatarySearchCriteria>;
+
+search_criteria! {
+    SignatarySearchCriteria {
+        id_signatary: u32 => eq,
+        signatary_active: bool => eq,
+        first_name: String => like,
+        father_last_name: String => like,
+        mother_last_name: String => like,
+        user: String => eq,
+        role: Role => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
@@ -0,0 +1,86 @@
+use rusqlite::types::Value;
+
+use super::table_repo::TableRepo;
+use crate::data::model::coordinate::BoundingBox;
+use crate::data::model::site::Site;
+use crate::data::table::{Criteria, DateRange, Filter};
+use crate::error::Result;
+
+pub type SiteRepo = TableRepo<Site, SiteSearchCriteria>;
+
+// Igual que los criterios de `search_criteria!` mas la caja geografica, que
+// se evalua con las funciones `latitude_degrees`/`longitude_degrees`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SiteSearchCriteria {
+    pub id_site: Option<u32>,
+    pub site_active: Option<bool>,
+    pub site_key: Option<String>,
+    pub site_name: Option<String>,
+    pub site_state: Option<String>,
+    pub site_municipality: Option<String>,
+    pub body_of_water: Option<String>,
+    pub id_client: Option<u32>,
+    pub bounding_box: Option<BoundingBox>,
+    pub created_at: Option<DateRange>,
+    pub updated_at: Option<DateRange>,
+}
+
+impl Criteria for SiteSearchCriteria {
+    fn filters(&self) -> Result<Vec<Filter>> {
+        let mut filters = Vec::new();
+        if let Some(value) = &self.id_site {
+            filters.push(Filter::eq("id_site", value)?);
+        }
+        if let Some(value) = &self.site_active {
+            filters.push(Filter::eq("site_active", value)?);
+        }
+        if let Some(value) = &self.site_key {
+            filters.push(Filter::like("site_key", value)?);
+        }
+        if let Some(value) = &self.site_name {
+            filters.push(Filter::like("site_name", value)?);
+        }
+        if let Some(value) = &self.site_state {
+            filters.push(Filter::like("site_state", value)?);
+        }
+        if let Some(value) = &self.site_municipality {
+            filters.push(Filter::like("site_municipality", value)?);
+        }
+        if let Some(value) = &self.body_of_water {
+            filters.push(Filter::like("body_of_water", value)?);
+        }
+        if let Some(value) = &self.id_client {
+            filters.push(Filter::eq("id_client", value)?);
+        }
+        if let Some(bounding_box) = &self.bounding_box {
+            filters.push(bounding_box_filter(bounding_box));
+        }
+        if let Some(value) = &self.created_at {
+            filters.push(Filter::within("created_at", value)?);
+        }
+        if let Some(value) = &self.updated_at {
+            filters.push(Filter::within("updated_at", value)?);
+        }
+        Ok(filters)
+    }
+}
+
+fn bounding_box_filter(bounding_box: &BoundingBox) -> Filter {
+    let mut params = vec![
+        Value::Real(bounding_box.min_latitude),
+        Value::Real(bounding_box.max_latitude),
+    ];
+    let mut longitudes = Vec::new();
+    for (min, max) in bounding_box.longitude_ranges() {
+        longitudes.push("longitude_degrees(longitude) BETWEEN ? AND ?");
+        params.extend([Value::Real(min), Value::Real(max)]);
+    }
+
+    Filter::Sql(
+        format!(
+            "latitude_degrees(latitude) BETWEEN ? AND ? AND ({})",
+            longitudes.join(" OR ")
+        ),
+        params,
+    )
+}
@@ -0,0 +1,16 @@
+use super::table_repo::TableRepo;
+use crate::data::model::standard_detail::StandardDetail;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type StandardDetailRepo = TableRepo<StandardDetail, StandardDetailSearchCriteria>;
+
+search_criteria! {
+    StandardDetailSearchCriteria {
+        id_std_detail: u32 => eq,
+        id_standard: u32 => eq,
+        id_analysis: u32 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
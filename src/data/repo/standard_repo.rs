@@ -0,0 +1,18 @@
+use super::table_repo::TableRepo;
+use crate::data::model::standard::Standard;
+use crate::data::table::DateRange;
+use crate::search_criteria;
+
+pub type StandardRepo = TableRepo<Standard, StandardSearchCriteria>;
+
+search_criteria! {
+    StandardSearchCriteria {
+        id_standard: u32 => eq,
+        std_active: bool => eq,
+        std_name: String => like,
+        units: String => like,
+        window_type: i64 => eq,
+        created_at: DateRange => within,
+        updated_at: DateRange => within,
+    }
+}
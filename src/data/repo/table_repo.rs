@@ -0,0 +1,437 @@
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+use rusqlite::{Connection, Row, TransactionBehavior};
+
+use super::conector::Connector;
+use crate::data::table::{display_value, to_value, Criteria, Patch, Table};
+use crate::data_management::{Finder, LastSearch, Repository};
+use crate::error::{Error, Result};
+
+// Repositorio generico: todo el SQL sale de la descripcion `Table` del modelo
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRepo<M, C> {
+    connector: Connector,
+    page_size: u64,
+    _model: PhantomData<(M, C)>,
+}
+
+#[allow(unused)]
+impl<M, C> TableRepo<M, C>
+where
+    M: Table,
+    C: Criteria,
+{
+    pub fn new(connector: Connector, page_size: u64) -> Self {
+        Self {
+            connector,
+            page_size,
+            _model: PhantomData,
+        }
+    }
+
+    pub fn connector(&self) -> &Connector {
+        &self.connector
+    }
+
+    // Igual que `add` pero devuelve el id asignado al registro
+    pub fn insert(&mut self, item: &M) -> Result<u32> {
+        let conn = self.connector.get_connection()?;
+        Self::insert_in(&conn, item)
+    }
+
+    // Alta sobre una conexion ya abierta, para insertar dentro de una transaccion
+    pub fn insert_in(conn: &Connection, item: &M) -> Result<u32> {
+        let mut item = item.clone();
+        let now = Utc::now();
+        item.set_timestamps(now, now);
+        item.set_version(1);
+
+        let placeholders = vec!["?"; M::COLUMNS.len()].join(",");
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            M::TABLE,
+            M::COLUMNS.join(", "),
+            placeholders
+        );
+
+        conn.prepare(&sql)
+            .map_err(|e| Error::query(e, &sql))?
+            .execute(params_from_iter(item.values()?))?;
+
+        Ok(conn.last_insert_rowid() as u32)
+    }
+
+    // Todos los registros que cumplen el criterio, sin paginar
+    pub fn find_all(&self, criteria: &C) -> Result<Vec<M>> {
+        let (where_str, params) = criteria.where_clause()?;
+        let query = format!(
+            "SELECT {} FROM {} {} ORDER BY {}",
+            M::select_columns(),
+            M::TABLE,
+            where_str,
+            M::ORDER_BY
+        );
+        self.query(&query, params)
+    }
+
+    pub fn query(&self, query: &str, params: Vec<Value>) -> Result<Vec<M>> {
+        let conn = self.connector.get_connection()?;
+        Self::query_in(&conn, query, params)
+    }
+
+    fn query_in(conn: &Connection, query: &str, params: Vec<Value>) -> Result<Vec<M>> {
+        let mut stmt = conn.prepare(query).map_err(|e| Error::query(e, query))?;
+        let mut rows = stmt.query(params_from_iter(params))?;
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            result.push(M::from_row(row)?);
+        }
+        Ok(result)
+    }
+
+    // Error de conflicto con las columnas en que difieren la fila guardada y la editada
+    fn conflict(stored: &M, edited: &M, edited_version: u32) -> Result<Error> {
+        let bookkeeping = M::bookkeeping_columns();
+        let mut differences = Vec::new();
+        let columns = M::COLUMNS
+            .iter()
+            .zip(stored.values()?.into_iter().zip(edited.values()?));
+        for (column, (stored_value, edited_value)) in columns {
+            if stored_value != edited_value && !bookkeeping.contains(column) {
+                differences.push((
+                    *column,
+                    display_value(&stored_value),
+                    display_value(&edited_value),
+                ));
+            }
+        }
+
+        Ok(Error::Conflict {
+            entity: M::ENTITY,
+            id: stored.id().unwrap_or_default(),
+            stored_version: stored.version().unwrap_or_default(),
+            edited_version,
+            differences,
+        })
+    }
+
+    fn execute_by_id(
+        conn: &Connection,
+        query: &str,
+        mut params: Vec<Value>,
+        id: Option<u32>,
+    ) -> Result<()> {
+        let id = id.ok_or(Error::NotFound {
+            entity: M::ENTITY,
+            id: None,
+        })?;
+
+        params.push(Value::Integer(id.into()));
+        conn.prepare(query)
+            .map_err(|e| Error::query(e, query))?
+            .execute(params_from_iter(params))?;
+        Ok(())
+    }
+
+    // Ejecuta `f` en una transaccion: si falla, no queda ningun cambio. Es
+    // IMMEDIATE para tomar el bloqueo de escritura desde el inicio.
+    pub fn in_transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T>,
+    {
+        let mut conn = self.connector.get_connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let result = f(&tx)?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    pub fn search_by_id_in(conn: &Connection, id: u32) -> Result<Option<M>> {
+        let query = format!(
+            "SELECT {} FROM {} WHERE {} = ?",
+            M::select_columns(),
+            M::TABLE,
+            M::ID_COLUMN
+        );
+        Ok(
+            Self::query_in(conn, &query, vec![Value::Integer(id.into())])?
+                .into_iter()
+                .next(),
+        )
+    }
+
+    pub fn drop_in(conn: &Connection, item: &mut M) -> Result<()> {
+        // Una fila de relacion no puede quedar inactiva, se elimina
+        let Some(active_column) = M::ACTIVE_COLUMN else {
+            return Self::delete_in(conn, item);
+        };
+
+        let now = Utc::now();
+        let mut assignments = format!("{} = 0", active_column);
+        let mut params = Vec::new();
+        if let Some((_, updated_column)) = M::TIMESTAMP_COLUMNS {
+            assignments.push_str(&format!(", {} = ?", updated_column));
+            params.push(to_value(&now)?);
+        }
+        if let Some(version_column) = M::VERSION_COLUMN {
+            assignments.push_str(&format!(", {0} = {0} + 1", version_column));
+        }
+
+        let query = format!(
+            "UPDATE {} SET {} WHERE {} = ?",
+            M::TABLE,
+            assignments,
+            M::ID_COLUMN
+        );
+        Self::execute_by_id(conn, &query, params, item.id())?;
+
+        item.set_active(false);
+        if let Some((created_at, _)) = item.timestamps() {
+            item.set_timestamps(created_at, now);
+        }
+        if let Some(version) = item.version() {
+            item.set_version(version + 1);
+        }
+        Ok(())
+    }
+
+    // Cambia la referencia `column` de `from` a `to` en todas las filas que la
+    // tengan, p. ej. los sitios de un cliente repetido; devuelve cuantas cambio
+    pub fn repoint_in(conn: &Connection, column: &str, from: u32, to: u32) -> Result<usize> {
+        let mut assignments = format!("{} = ?", column);
+        let mut params = vec![Value::Integer(to.into())];
+        if let Some((_, updated_column)) = M::TIMESTAMP_COLUMNS {
+            assignments.push_str(&format!(", {} = ?", updated_column));
+            params.push(to_value(&Utc::now())?);
+        }
+        if let Some(version_column) = M::VERSION_COLUMN {
+            assignments.push_str(&format!(", {0} = {0} + 1", version_column));
+        }
+        params.push(Value::Integer(from.into()));
+
+        let query = format!(
+            "UPDATE {} SET {} WHERE {} = ?",
+            M::TABLE,
+            assignments,
+            column
+        );
+        conn.execute(&query, params_from_iter(params))
+            .map_err(|e| Error::query(e, &query))
+    }
+
+    pub fn delete_in(conn: &Connection, item: &M) -> Result<()> {
+        let query = format!("DELETE FROM {} WHERE {} = ?", M::TABLE, M::ID_COLUMN);
+        Self::execute_by_id(conn, &query, Vec::new(), item.id())
+    }
+
+    pub fn modify_in(conn: &Connection, item: &M) -> Result<()> {
+        let not_found = || Error::NotFound {
+            entity: M::ENTITY,
+            id: item.id(),
+        };
+
+        let id = item.id().ok_or_else(not_found)?;
+        let original = Self::search_by_id_in(conn, id)?.ok_or_else(not_found)?;
+
+        // Las fechas y la version no se modifican desde fuera: se toman de la
+        // fila guardada y solo se actualizan si cambio otra columna
+        let edited_version = item.version().unwrap_or_default();
+        let mut item = item.clone();
+        if let Some((created_at, updated_at)) = original.timestamps() {
+            item.set_timestamps(created_at, updated_at);
+        }
+        if let Some(version) = original.version() {
+            item.set_version(version);
+        }
+        if original == item {
+            return Ok(());
+        }
+
+        // La fila cambio desde que el usuario la cargo
+        if original
+            .version()
+            .is_some_and(|version| version != edited_version)
+        {
+            return Err(Self::conflict(&original, &item, edited_version)?);
+        }
+
+        if let Some((created_at, _)) = original.timestamps() {
+            item.set_timestamps(created_at, Utc::now());
+        }
+        if let Some(version) = original.version() {
+            item.set_version(version + 1);
+        }
+
+        // Solo se actualizan las columnas que cambiaron
+        let mut assignments = Vec::new();
+        let mut params = Vec::new();
+        let changes = M::COLUMNS
+            .iter()
+            .zip(original.values()?.into_iter().zip(item.values()?));
+        for (column, (before, after)) in changes {
+            if before != after {
+                assignments.push(format!("{} = ?", column));
+                params.push(after);
+            }
+        }
+
+        if assignments.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = format!(
+            "UPDATE {} SET {} WHERE {} = ?",
+            M::TABLE,
+            assignments.join(", "),
+            M::ID_COLUMN
+        );
+        params.push(Value::Integer(id.into()));
+        if let Some(version_column) = M::VERSION_COLUMN {
+            query.push_str(&format!(" AND {} = ?", version_column));
+            params.push(Value::Integer(edited_version.into()));
+        }
+
+        let updated = conn
+            .execute(&query, params_from_iter(params))
+            .map_err(|e| Error::query(e, &query))?;
+
+        // Otro usuario guardo entre la lectura y la escritura
+        if updated == 0 {
+            let stored = Self::search_by_id_in(conn, id)?.ok_or_else(not_found)?;
+            return Err(Self::conflict(&stored, &item, edited_version)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl<M, C> Repository<M, Error> for TableRepo<M, C>
+where
+    M: Table,
+    C: Criteria,
+{
+    fn add(&mut self, item: &M) -> Result<()> {
+        self.insert(item)?;
+        Ok(())
+    }
+
+    fn drop(&mut self, item: &mut M) -> Result<()> {
+        let conn = self.connector.get_connection()?;
+        Self::drop_in(&conn, item)
+    }
+
+    fn delete(&mut self, item: &M) -> Result<()> {
+        let conn = self.connector.get_connection()?;
+        Self::delete_in(&conn, item)
+    }
+
+    fn modify(&mut self, item: &M) -> Result<()> {
+        let conn = self.connector.get_connection()?;
+        Self::modify_in(&conn, item)
+    }
+
+    fn add_many(&mut self, items: &[M]) -> Result<()> {
+        self.in_transaction(|conn| {
+            for item in items {
+                Self::insert_in(conn, item)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn drop_many(&mut self, items: &mut [M]) -> Result<()> {
+        // Se trabaja sobre copias para no dejar registros a medias si falla uno
+        let mut dropped = items.to_vec();
+        self.in_transaction(|conn| {
+            for item in dropped.iter_mut() {
+                Self::drop_in(conn, item)?;
+            }
+            Ok(())
+        })?;
+        items.clone_from_slice(&dropped);
+        Ok(())
+    }
+
+    fn delete_many(&mut self, items: &[M]) -> Result<()> {
+        self.in_transaction(|conn| {
+            for item in items {
+                Self::delete_in(conn, item)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn modify_many(&mut self, items: &[M], patch: &Patch) -> Result<()> {
+        let patched = items
+            .iter()
+            .map(|item| patch.apply(item))
+            .collect::<Result<Vec<M>>>()?;
+        self.in_transaction(|conn| {
+            for item in &patched {
+                Self::modify_in(conn, item)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(unused)]
+impl<M, C> Finder<M, C, Error> for TableRepo<M, C>
+where
+    M: Table,
+    C: Criteria,
+{
+    fn from_row(row: &Row) -> Result<M> {
+        M::from_row(row)
+    }
+
+    fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    fn search_by_id(&self, id: u32) -> Result<Option<M>> {
+        let conn = self.connector.get_connection()?;
+        Self::search_by_id_in(&conn, id)
+    }
+
+    fn search_by(&mut self, criteria: &C, page_number: u64) -> Result<LastSearch<C>> {
+        let (where_str, params) = criteria.where_clause()?;
+
+        let count_query = format!("SELECT COUNT(*) FROM {} {}", M::TABLE, where_str);
+        let query = format!(
+            "SELECT {} FROM {} {} ORDER BY {} LIMIT {} OFFSET {}",
+            M::select_columns(),
+            M::TABLE,
+            where_str,
+            M::ORDER_BY,
+            self.page_size(),
+            page_number.saturating_sub(1) * self.page_size()
+        );
+
+        let conn = self.connector.get_connection()?;
+        let total_registers: u64 = conn
+            .query_row(&count_query, params_from_iter(params.iter()), |row| {
+                row.get(0)
+            })
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Error::MissingRow {
+                    query: count_query.clone(),
+                },
+                e => Error::query(e, &count_query),
+            })?;
+
+        let result = self.query(&query, params)?;
+        let result = serde_json::to_string(&result)?;
+
+        Ok(LastSearch::new(
+            page_number,
+            Self::total_pages(total_registers, self.page_size()),
+            criteria.clone(),
+            result,
+        ))
+    }
+}
@@ -0,0 +1,53 @@
+use super::conector::Connector;
+use crate::data::model::result_point::ResultPoint;
+use crate::error::{Error, Result};
+
+// Resultados activos de un analisis en las muestras activas de un sitio, del
+// muestreo mas antiguo al mas reciente
+const SITE_ANALYSIS_RESULTS: &str = "
+    SELECT SampleResult.id_sample_result, Sample.id_sample, Sample.control_number,
+           Sample.sampling_date, SampleResult.analysis_date, SampleResult.result,
+           Analysis.detection_limit
+    FROM SampleResult
+    JOIN Sample ON Sample.id_sample = SampleResult.id_sample
+    LEFT JOIN Analysis ON Analysis.id_analysis = SampleResult.id_analysis
+    WHERE Sample.id_site = ?1 AND SampleResult.id_analysis = ?2
+      AND Sample.sample_active = 1 AND SampleResult.result_active = 1
+    ORDER BY Sample.sampling_date, Sample.id_sample, SampleResult.id_sample_result";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendRepo {
+    connector: Connector,
+}
+
+impl TrendRepo {
+    pub fn new(connector: Connector) -> Self {
+        Self { connector }
+    }
+
+    pub fn site_analysis_results(
+        &self,
+        id_site: u32,
+        id_analysis: u32,
+    ) -> Result<Vec<ResultPoint>> {
+        let conn = self.connector.get_connection()?;
+        let mut stmt = conn
+            .prepare(SITE_ANALYSIS_RESULTS)
+            .map_err(|e| Error::query(e, SITE_ANALYSIS_RESULTS))?;
+        let mut rows = stmt.query([id_site, id_analysis])?;
+
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            results.push(ResultPoint {
+                id_sample_result: row.get(0).map_err(Error::from_row("id_sample_result"))?,
+                id_sample: row.get(1).map_err(Error::from_row("id_sample"))?,
+                control_number: row.get(2).map_err(Error::from_row("control_number"))?,
+                sampling_date: row.get(3).map_err(Error::from_row("sampling_date"))?,
+                analysis_date: row.get(4).map_err(Error::from_row("analysis_date"))?,
+                result: row.get(5).map_err(Error::from_row("result"))?,
+                detection_limit: row.get(6).map_err(Error::from_row("detection_limit"))?,
+            });
+        }
+        Ok(results)
+    }
+}
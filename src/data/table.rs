@@ -0,0 +1,392 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::types::{ToSql, ToSqlOutput, Value};
+use rusqlite::Row;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+// Mapeo declarativo entre un modelo y su tabla. `TableRepo` usa esta
+// descripcion para generar el SQL de altas, bajas, cambios y busquedas, asi
+// cada entidad solo declara sus columnas (normalmente con `table_model!`).
+pub trait Table: Sized + Clone + PartialEq + Serialize + DeserializeOwned {
+    const TABLE: &'static str;
+    const ENTITY: &'static str;
+    const ID_COLUMN: &'static str;
+    // Columna usada para la baja logica; las tablas de relacion no tienen
+    const ACTIVE_COLUMN: Option<&'static str>;
+    // Fecha de alta y de ultimo cambio, las llena `TableRepo`
+    const TIMESTAMP_COLUMNS: Option<(&'static str, &'static str)>;
+    // Version de la fila para detectar cambios concurrentes en `modify`
+    const VERSION_COLUMN: Option<&'static str>;
+    // Columnas sin incluir el id, en el mismo orden que `values`
+    const COLUMNS: &'static [&'static str];
+    const ORDER_BY: &'static str;
+
+    fn id(&self) -> Option<u32>;
+    fn set_active(&mut self, active: bool);
+    fn timestamps(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)>;
+    fn set_timestamps(&mut self, created_at: DateTime<Utc>, updated_at: DateTime<Utc>);
+    fn version(&self) -> Option<u32>;
+    fn set_version(&mut self, version: u32);
+    fn values(&self) -> Result<Vec<Value>>;
+    fn from_row(row: &Row) -> Result<Self>;
+
+    fn select_columns() -> String {
+        let mut columns = vec![Self::ID_COLUMN];
+        columns.extend_from_slice(Self::COLUMNS);
+        columns.join(", ")
+    }
+
+    // Columnas que llena `TableRepo` y no se editan desde fuera
+    fn bookkeeping_columns() -> Vec<&'static str> {
+        let mut columns: Vec<&str> = Self::VERSION_COLUMN.into_iter().collect();
+        if let Some((created_column, updated_column)) = Self::TIMESTAMP_COLUMNS {
+            columns.extend([created_column, updated_column]);
+        }
+        columns
+    }
+}
+
+pub fn to_value(value: &dyn ToSql) -> Result<Value> {
+    Ok(match value.to_sql()? {
+        ToSqlOutput::Borrowed(value) => value.into(),
+        ToSqlOutput::Owned(value) => value,
+        _ => {
+            let reason = "value cannot be stored as a column value";
+            return Err(rusqlite::Error::ToSqlConversionFailure(reason.into()).into());
+        }
+    })
+}
+
+// Valor de una columna para mostrarlo al usuario
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(value) => value.to_string(),
+        Value::Real(value) => value.to_string(),
+        Value::Text(value) => format!("\"{}\"", value),
+        Value::Blob(value) => format!("<{} bytes>", value.len()),
+    }
+}
+
+// Condicion de busqueda; cada criterio de busqueda se traduce a una lista de
+// filtros que se combinan con AND
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Eq(&'static str, Value),
+    Like(&'static str, String),
+    Gte(&'static str, Value),
+    Lte(&'static str, Value),
+    // Condicion libre con sus parametros, para lo que no cubren las anteriores
+    Sql(String, Vec<Value>),
+}
+
+impl Filter {
+    pub fn eq(column: &'static str, value: &dyn ToSql) -> Result<Self> {
+        Ok(Self::Eq(column, to_value(value)?))
+    }
+
+    pub fn like(column: &'static str, value: &dyn std::fmt::Display) -> Result<Self> {
+        Ok(Self::Like(column, value.to_string()))
+    }
+
+    pub fn gte(column: &'static str, value: &dyn ToSql) -> Result<Self> {
+        Ok(Self::Gte(column, to_value(value)?))
+    }
+
+    pub fn lte(column: &'static str, value: &dyn ToSql) -> Result<Self> {
+        Ok(Self::Lte(column, to_value(value)?))
+    }
+
+    pub fn within(column: &'static str, range: &DateRange) -> Result<Self> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        if let Some(from) = &range.from {
+            conditions.push(format!("{} >= ?", column));
+            params.push(to_value(from)?);
+        }
+        if let Some(to) = &range.to {
+            conditions.push(format!("{} < ?", column));
+            params.push(to_value(to)?);
+        }
+        if conditions.is_empty() {
+            conditions.push("1 = 1".to_string());
+        }
+        Ok(Self::Sql(conditions.join(" AND "), params))
+    }
+
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        match self {
+            Self::Eq(column, value) => (format!("{} = ?", column), vec![value.clone()]),
+            Self::Like(column, value) => (
+                format!("{} LIKE ?", column),
+                vec![Value::Text(format!("%{}%", value))],
+            ),
+            Self::Gte(column, value) => (format!("{} >= ?", column), vec![value.clone()]),
+            Self::Lte(column, value) => (format!("{} <= ?", column), vec![value.clone()]),
+            Self::Sql(sql, values) => (sql.clone(), values.clone()),
+        }
+    }
+}
+
+// Rango de fechas para `created_at`/`updated_at`: `from` incluido, `to` excluido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DateRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    pub fn new(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        Self { from, to }
+    }
+
+    // Dias completos de `first` a `last`, ambos incluidos
+    pub fn days(first: NaiveDate, last: NaiveDate) -> Self {
+        let start_of = |date: NaiveDate| date.and_time(chrono::NaiveTime::MIN).and_utc();
+        Self {
+            from: Some(start_of(first)),
+            to: last.succ_opt().map(start_of),
+        }
+    }
+
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = first
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?;
+        Some(Self::days(first, last))
+    }
+
+    pub fn contains(&self, moment: &DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| *moment >= from) && self.to.is_none_or(|to| *moment < to)
+    }
+}
+
+// Cambios de campos que `modify_many` aplica a cada registro: columna y nuevo valor
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Patch {
+    fields: Vec<(&'static str, serde_json::Value)>,
+}
+
+impl Patch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, column: &'static str, value: impl Serialize) -> Result<Self> {
+        self.fields.push((column, serde_json::to_value(value)?));
+        Ok(self)
+    }
+
+    pub fn columns(&self) -> Vec<&'static str> {
+        self.fields.iter().map(|(column, _)| *column).collect()
+    }
+
+    // Copia del registro con los campos cambiados; los campos del modelo se
+    // llaman igual que sus columnas. El id, las fechas y la version no se tocan.
+    pub fn apply<M: Table>(&self, item: &M) -> Result<M> {
+        let mut errors = Vec::new();
+        if self.fields.is_empty() {
+            errors.push(Message::new("common.patch_empty"));
+        }
+
+        let bookkeeping = M::bookkeeping_columns();
+        let mut json = serde_json::to_value(item)?;
+        for (column, value) in &self.fields {
+            if !M::COLUMNS.contains(column) || bookkeeping.contains(column) {
+                errors.push(Message::new("common.patch_column").arg("column", column));
+            } else {
+                json[*column] = value.clone();
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: M::ENTITY,
+                errors,
+            });
+        }
+        Ok(serde_json::from_value(json)?)
+    }
+}
+
+pub trait Criteria: Clone + PartialEq + Default {
+    fn filters(&self) -> Result<Vec<Filter>>;
+
+    // Clausula WHERE (vacia si no hay filtros) y sus parametros
+    fn where_clause(&self) -> Result<(String, Vec<Value>)> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        for filter in self.filters()? {
+            let (condition, values) = filter.to_sql();
+            conditions.push(condition);
+            params.extend(values);
+        }
+
+        if conditions.is_empty() {
+            Ok((String::new(), params))
+        } else {
+            Ok((format!("WHERE {}", conditions.join(" AND ")), params))
+        }
+    }
+}
+
+// Implementa `Table` para un struct cuyos campos se llaman igual que sus columnas
+#[macro_export]
+macro_rules! table_model {
+    (
+        $model:ident in $table:literal {
+            id: $id:ident,
+            $(active: $active:ident,)?
+            $(timestamps: ($created:ident, $updated:ident),)?
+            $(version: $version:ident,)?
+            order_by: $order_by:literal,
+            columns: [$($column:ident),* $(,)?] $(,)?
+        }
+    ) => {
+        impl $crate::data::table::Table for $model {
+            const TABLE: &'static str = $table;
+            const ENTITY: &'static str = stringify!($model);
+            const ID_COLUMN: &'static str = stringify!($id);
+            const ACTIVE_COLUMN: Option<&'static str> =
+                $crate::table_model!(@column $($active)?);
+            const TIMESTAMP_COLUMNS: Option<(&'static str, &'static str)> =
+                $crate::table_model!(@timestamps $($created, $updated)?);
+            const VERSION_COLUMN: Option<&'static str> =
+                $crate::table_model!(@column $($version)?);
+            const COLUMNS: &'static [&'static str] = &[
+                $(stringify!($column),)*
+                $(stringify!($created), stringify!($updated),)?
+                $(stringify!($version),)?
+            ];
+            const ORDER_BY: &'static str = $order_by;
+
+            fn id(&self) -> Option<u32> {
+                self.$id
+            }
+
+            #[allow(unused_variables)]
+            fn set_active(&mut self, active: bool) {
+                $(self.$active = active;)?
+            }
+
+            fn timestamps(
+                &self,
+            ) -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
+                $crate::table_model!(@timestamps_of self $($created, $updated)?)
+            }
+
+            #[allow(unused_variables)]
+            fn set_timestamps(
+                &mut self,
+                created_at: chrono::DateTime<chrono::Utc>,
+                updated_at: chrono::DateTime<chrono::Utc>,
+            ) {
+                $(
+                    self.$created = created_at;
+                    self.$updated = updated_at;
+                )?
+            }
+
+            fn version(&self) -> Option<u32> {
+                $crate::table_model!(@version_of self $($version)?)
+            }
+
+            #[allow(unused_variables)]
+            fn set_version(&mut self, version: u32) {
+                $(self.$version = version;)?
+            }
+
+            fn values(&self) -> $crate::error::Result<Vec<rusqlite::types::Value>> {
+                Ok(vec![
+                    $($crate::data::table::to_value(&self.$column)?,)*
+                    $(
+                        $crate::data::table::to_value(&self.$created)?,
+                        $crate::data::table::to_value(&self.$updated)?,
+                    )?
+                    $($crate::data::table::to_value(&self.$version)?,)?
+                ])
+            }
+
+            fn from_row(row: &rusqlite::Row) -> $crate::error::Result<Self> {
+                Ok(Self {
+                    $id: row
+                        .get(stringify!($id))
+                        .map_err($crate::error::Error::from_row(stringify!($id)))?,
+                    $($column: row
+                        .get(stringify!($column))
+                        .map_err($crate::error::Error::from_row(stringify!($column)))?,)*
+                    $(
+                        $created: row
+                            .get(stringify!($created))
+                            .map_err($crate::error::Error::from_row(stringify!($created)))?,
+                        $updated: row
+                            .get(stringify!($updated))
+                            .map_err($crate::error::Error::from_row(stringify!($updated)))?,
+                    )?
+                    $(
+                        $version: row
+                            .get(stringify!($version))
+                            .map_err($crate::error::Error::from_row(stringify!($version)))?,
+                    )?
+                })
+            }
+        }
+    };
+    (@column $column:ident) => {
+        Some(stringify!($column))
+    };
+    (@column) => {
+        None
+    };
+    (@timestamps $created:ident, $updated:ident) => {
+        Some((stringify!($created), stringify!($updated)))
+    };
+    (@timestamps) => {
+        None
+    };
+    (@timestamps_of $self:ident $created:ident, $updated:ident) => {
+        Some(($self.$created, $self.$updated))
+    };
+    (@timestamps_of $self:ident) => {
+        None
+    };
+    (@version_of $self:ident $version:ident) => {
+        Some($self.$version)
+    };
+    (@version_of $self:ident) => {
+        None
+    };
+}
+
+// Criterio de busqueda con un `Option` por campo; `eq` compara por igualdad y
+// `like` busca el texto en cualquier parte de la columna
+#[macro_export]
+macro_rules! search_criteria {
+    (
+        $criteria:ident {
+            $($field:ident: $ty:ty => $op:ident),* $(,)?
+        }
+    ) => {
+        #[derive(Debug, Clone, PartialEq, Default)]
+        pub struct $criteria {
+            $(pub $field: Option<$ty>,)*
+        }
+
+        impl $crate::data::table::Criteria for $criteria {
+            fn filters(&self) -> $crate::error::Result<Vec<$crate::data::table::Filter>> {
+                #[allow(unused_mut)]
+                let mut filters = Vec::new();
+                $(
+                    if let Some(value) = &self.$field {
+                        filters.push($crate::data::table::Filter::$op(stringify!($field), value)?);
+                    }
+                )*
+                Ok(filters)
+            }
+        }
+    };
+}
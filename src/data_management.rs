@@ -1,6 +1,6 @@
 use rusqlite::Row;
 
-use crate::data::repo::client_repo::SearchCriteria;
+use crate::data::table::Patch;
 
 #[allow(unused)]
 pub trait Repository<Model, Err>
@@ -12,14 +12,19 @@ where
     fn delete(&mut self, item: &Model) -> Result<(), Err>;
     fn modify(&mut self, item: &Model) -> Result<(), Err>;
 
+    // Variantes en lote: se aplican en una sola transaccion, todo o nada
+    fn add_many(&mut self, items: &[Model]) -> Result<(), Err>;
+    fn drop_many(&mut self, items: &mut [Model]) -> Result<(), Err>;
+    fn delete_many(&mut self, items: &[Model]) -> Result<(), Err>;
+    fn modify_many(&mut self, items: &[Model], patch: &Patch) -> Result<(), Err>;
+
     fn total_pages(total_registers: u64, page_size: u64) -> u64 {
         if page_size == 0 {
             panic!("Page size must be non-zero");
         }
 
         // Convertir a f64, realizar la división y redondear
-        let result = (total_registers as f64 / page_size as f64).ceil() as u64;
-        result
+        (total_registers as f64 / page_size as f64).ceil() as u64
     }
 }
 
@@ -60,7 +65,7 @@ pub struct LastSearch<SearchCriteria> {
 }
 
 #[allow(unused)]
-impl LastSearch<SearchCriteria> {
+impl<SearchCriteria> LastSearch<SearchCriteria> {
     pub fn new(
         page: u64,
         total_pages: u64,
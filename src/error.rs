@@ -0,0 +1,493 @@
+use std::io::Error as IoError;
+
+use rusqlite::Error as RusqliteError;
+use serde_json::Error as SerdeJsonError;
+
+use crate::config::Error as ConfigError;
+use crate::data::model::role::{Permission, Role};
+use crate::i18n::{t, tf, Message};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Codigos estables para scripts, logs y soporte: no cambiar los existentes,
+// solo agregar nuevos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Config,
+    Io,
+    Database,
+    Serialization,
+    NotFound,
+    InvalidField,
+    MissingRow,
+    FromRow,
+    Conflict,
+    Forbidden,
+    Locked,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "E001_CONFIG",
+            Self::Io => "E002_IO",
+            Self::Database => "E003_DATABASE",
+            Self::Serialization => "E004_SERIALIZATION",
+            Self::NotFound => "E005_NOT_FOUND",
+            Self::InvalidField => "E006_INVALID_FIELD",
+            Self::MissingRow => "E007_MISSING_ROW",
+            Self::FromRow => "E008_FROM_ROW",
+            Self::Conflict => "E009_CONFLICT",
+            Self::Forbidden => "E010_FORBIDDEN",
+            Self::Locked => "E011_LOCKED",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Config(ConfigError),
+    Io(IoError),
+    Database {
+        source: RusqliteError,
+        query: Option<String>,
+    },
+    Serialization(SerdeJsonError),
+    NotFound {
+        entity: &'static str,
+        id: Option<u32>,
+    },
+    InvalidField {
+        entity: &'static str,
+        errors: Vec<Message>,
+    },
+    MissingRow {
+        query: String,
+    },
+    FromRow {
+        column: &'static str,
+        source: RusqliteError,
+    },
+    // Otro usuario cambio la fila despues de que se cargo para editarla
+    Conflict {
+        entity: &'static str,
+        id: u32,
+        stored_version: u32,
+        edited_version: u32,
+        // Columnas que difieren: (columna, valor guardado, valor editado)
+        differences: Vec<(&'static str, String, String)>,
+    },
+    // El rol del usuario no tiene el permiso que pide la operacion
+    Forbidden {
+        role: Role,
+        permission: Permission,
+    },
+    // El registro esta firmado; lo rechaza el manager o el trigger de la tabla
+    Locked {
+        entity: &'static str,
+        id: Option<u32>,
+    },
+}
+
+// Mensajes de RAISE de los triggers: resultados firmados (migracion 0016) y
+// muestras que ya estan en otra factura vigente (migraciones 0017 y 0018)
+const SIGNED_RESULT_LOCKED: &str = "signed_result_locked";
+const SAMPLE_ALREADY_INVOICED: &str = "sample_already_invoiced";
+
+impl Error {
+    pub fn query(source: RusqliteError, query: &str) -> Self {
+        Self::database(source, Some(query.to_string()))
+    }
+
+    fn database(source: RusqliteError, query: Option<String>) -> Self {
+        let raised = match &source {
+            RusqliteError::SqliteFailure(e, Some(message))
+                if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_TRIGGER =>
+            {
+                Some(message.as_str())
+            }
+            _ => None,
+        };
+        match raised {
+            Some(SIGNED_RESULT_LOCKED) => Self::Locked {
+                entity: "SampleResult",
+                id: None,
+            },
+            Some(SAMPLE_ALREADY_INVOICED) => Self::InvalidField {
+                entity: "Invoice",
+                errors: vec![Message::new("invoice.error.sample_invoiced")],
+            },
+            _ => Self::Database { source, query },
+        }
+    }
+
+    pub fn from_row(column: &'static str) -> impl FnOnce(RusqliteError) -> Self {
+        move |source| Self::FromRow { column, source }
+    }
+
+    // Violacion de un indice UNIQUE, p. ej. un numero de control repetido
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            Self::Database {
+                source: RusqliteError::SqliteFailure(e, _),
+                ..
+            } => e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE,
+            _ => false,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Config(_) => ErrorCode::Config,
+            Self::Io(_) => ErrorCode::Io,
+            Self::Database { .. } => ErrorCode::Database,
+            Self::Serialization(_) => ErrorCode::Serialization,
+            Self::NotFound { .. } => ErrorCode::NotFound,
+            Self::InvalidField { .. } => ErrorCode::InvalidField,
+            Self::MissingRow { .. } => ErrorCode::MissingRow,
+            Self::FromRow { .. } => ErrorCode::FromRow,
+            Self::Conflict { .. } => ErrorCode::Conflict,
+            Self::Forbidden { .. } => ErrorCode::Forbidden,
+            Self::Locked { .. } => ErrorCode::Locked,
+        }
+    }
+
+    // Mensaje para el usuario final en el idioma configurado, sin detalles internos
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::Config(e) => tf("error.config", &[("detail", e)]),
+            Self::Io(_) => t("error.io"),
+            Self::Database { .. } | Self::MissingRow { .. } | Self::FromRow { .. } => {
+                t("error.database")
+            }
+            Self::Serialization(_) => t("error.serialization"),
+            Self::NotFound { entity, id } => match id {
+                Some(id) => tf("error.not_found_id", &[("entity", entity), ("id", id)]),
+                None => tf("error.not_found", &[("entity", entity)]),
+            },
+            Self::InvalidField { errors, .. } => {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                let mut message = errors.join(", ");
+                if let Some(first_char) = message.chars().next() {
+                    let upper: String = first_char.to_uppercase().collect();
+                    message.replace_range(..first_char.len_utf8(), &upper);
+                }
+                message
+            }
+            Self::Conflict {
+                entity,
+                id,
+                stored_version,
+                edited_version,
+                differences,
+            } => {
+                let mut message = tf(
+                    "error.conflict",
+                    &[
+                        ("entity", entity),
+                        ("id", id),
+                        ("stored", stored_version),
+                        ("edited", edited_version),
+                    ],
+                );
+                for (column, stored, edited) in differences {
+                    message.push_str("\n  ");
+                    message.push_str(&tf(
+                        "error.conflict_field",
+                        &[("column", column), ("stored", stored), ("edited", edited)],
+                    ));
+                }
+                message
+            }
+            Self::Forbidden { role, permission } => tf(
+                "error.forbidden",
+                &[
+                    ("role", &t(&format!("role.{}", role.as_str()))),
+                    ("action", &t(&format!("permission.{}", permission.as_str()))),
+                ],
+            ),
+            Self::Locked { entity, id } => match id {
+                Some(id) => tf("error.locked_id", &[("entity", entity), ("id", id)]),
+                None => tf("error.locked", &[("entity", entity)]),
+            },
+        }
+    }
+
+    // Detalle para desarrolladores: codigo, contexto y cadena de causas
+    pub fn diagnostic(&self) -> String {
+        let mut diagnostic = format!("[{}] ", self.code());
+        match self {
+            Self::Config(e) => diagnostic.push_str(&e.to_string()),
+            Self::Io(e) => diagnostic.push_str(&format!("IoError: {}", e)),
+            Self::Database { source, query } => {
+                diagnostic.push_str(&format!("RusqliteError: {}", source));
+                if let Some(query) = query {
+                    diagnostic.push_str(&format!(" Query: {}", query));
+                }
+            }
+            Self::Serialization(e) => diagnostic.push_str(&format!("SerdeError: {}", e)),
+            Self::NotFound { entity, id } => diagnostic.push_str(&format!(
+                "NotFound: {} {}",
+                entity,
+                id.map_or("None".to_string(), |id| id.to_string())
+            )),
+            Self::InvalidField { entity, errors } => {
+                let keys: Vec<&str> = errors.iter().map(|e| e.key).collect();
+                diagnostic.push_str(&format!("InvalidField: {} {:?}", entity, keys))
+            }
+            Self::MissingRow { query } => {
+                diagnostic.push_str(&format!("MissingRow: no rows returned by {}", query))
+            }
+            Self::FromRow { column, source } => {
+                diagnostic.push_str(&format!("FromRowError: column {}: {}", column, source))
+            }
+            Self::Conflict {
+                entity,
+                id,
+                stored_version,
+                edited_version,
+                differences,
+            } => {
+                let columns: Vec<&str> = differences.iter().map(|d| d.0).collect();
+                diagnostic.push_str(&format!(
+                    "Conflict: {} {} stored version {} edited version {} columns {:?}",
+                    entity, id, stored_version, edited_version, columns
+                ))
+            }
+            Self::Forbidden { role, permission } => diagnostic.push_str(&format!(
+                "Forbidden: role {} lacks permission {}",
+                role.as_str(),
+                permission.as_str()
+            )),
+            Self::Locked { entity, id } => diagnostic.push_str(&format!(
+                "Locked: {} {}",
+                entity,
+                id.map_or("None".to_string(), |id| id.to_string())
+            )),
+        }
+
+        // La cadena empieza en la primera causa que el mensaje no incluye ya
+        // (config::Error, por ejemplo, muestra su causa en Display)
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source.filter(|cause| diagnostic.contains(&cause.to_string())) {
+            source = cause.source();
+        }
+        while let Some(cause) = source {
+            diagnostic.push_str(&format!("\n  caused by: {}", cause));
+            source = cause.source();
+        }
+        diagnostic
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.user_message())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Config(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Database { source, .. } => Some(source),
+            Self::Serialization(e) => Some(e),
+            Self::FromRow { source, .. } => Some(source),
+            Self::NotFound { .. }
+            | Self::InvalidField { .. }
+            | Self::MissingRow { .. }
+            | Self::Conflict { .. }
+            | Self::Forbidden { .. }
+            | Self::Locked { .. } => None,
+        }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        Self::Config(e)
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(e: IoError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<RusqliteError> for Error {
+    fn from(e: RusqliteError) -> Self {
+        Self::database(e, None)
+    }
+}
+
+impl From<SerdeJsonError> for Error {
+    fn from(e: SerdeJsonError) -> Self {
+        Self::Serialization(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn each_variant_keeps_its_stable_code() {
+        let errors = [
+            (
+                Error::from(ConfigError::UnknownFlag {
+                    flag: "--x".to_string(),
+                }),
+                "E001_CONFIG",
+            ),
+            (Error::from(IoError::other("disco lleno")), "E002_IO"),
+            (
+                Error::from(RusqliteError::QueryReturnedNoRows),
+                "E003_DATABASE",
+            ),
+            (
+                Error::from(serde_json::from_str::<u32>("x").unwrap_err()),
+                "E004_SERIALIZATION",
+            ),
+            (
+                Error::NotFound {
+                    entity: "Client",
+                    id: Some(1),
+                },
+                "E005_NOT_FOUND",
+            ),
+            (
+                Error::InvalidField {
+                    entity: "Client",
+                    errors: vec![Message::new("client.error.name_empty")],
+                },
+                "E006_INVALID_FIELD",
+            ),
+            (
+                Error::MissingRow {
+                    query: "SELECT 1".to_string(),
+                },
+                "E007_MISSING_ROW",
+            ),
+            (
+                Error::from_row("client_name")(RusqliteError::InvalidQuery),
+                "E008_FROM_ROW",
+            ),
+            (
+                Error::Conflict {
+                    entity: "Client",
+                    id: 1,
+                    stored_version: 2,
+                    edited_version: 1,
+                    differences: Vec::new(),
+                },
+                "E009_CONFLICT",
+            ),
+            (
+                Error::Forbidden {
+                    role: Role::ReadOnly,
+                    permission: Permission::EditClients,
+                },
+                "E010_FORBIDDEN",
+            ),
+            (
+                Error::Locked {
+                    entity: "SampleResult",
+                    id: Some(3),
+                },
+                "E011_LOCKED",
+            ),
+        ];
+
+        for (error, code) in &errors {
+            assert_eq!(error.code().as_str(), *code);
+            assert!(error.diagnostic().starts_with(&format!("[{}] ", code)));
+        }
+    }
+
+    #[test]
+    fn diagnostic_renders_the_source_chain() {
+        let error = Error::from(ConfigError::ReadFile {
+            path: PathBuf::from("config.toml"),
+            source: IoError::new(std::io::ErrorKind::NotFound, "no existe"),
+        });
+        assert_eq!(
+            error.diagnostic(),
+            "[E001_CONFIG] ConfigError: cannot read config.toml: no existe"
+        );
+
+        // Una causa que el mensaje no muestra si aparece en la cadena
+        #[derive(Debug)]
+        struct Wrapper(IoError);
+        impl std::fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "no se pudo guardar")
+            }
+        }
+        impl std::error::Error for Wrapper {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+        let error = Error::Io(IoError::other(Wrapper(IoError::other("disco lleno"))));
+        assert_eq!(
+            error.diagnostic(),
+            "[E002_IO] IoError: no se pudo guardar\n  caused by: disco lleno"
+        );
+
+        let error = Error::query(RusqliteError::QueryReturnedNoRows, "SELECT 1");
+        assert_eq!(
+            error.diagnostic(),
+            "[E003_DATABASE] RusqliteError: Query returned no rows Query: SELECT 1"
+        );
+    }
+
+    #[test]
+    fn trigger_errors_become_friendly_errors() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE Result (value REAL);
+             CREATE TRIGGER ResultLocked BEFORE DELETE ON Result
+             BEGIN SELECT RAISE(ABORT, 'signed_result_locked'); END;
+             INSERT INTO Result VALUES (1.0);",
+        )
+        .unwrap();
+
+        let error = Error::from(conn.execute("DELETE FROM Result", []).unwrap_err());
+        assert!(matches!(
+            error,
+            Error::Locked {
+                entity: "SampleResult",
+                id: None
+            }
+        ));
+
+        conn.execute_batch(
+            "CREATE TABLE Line (id_sample INTEGER);
+             CREATE TRIGGER LineOnce BEFORE INSERT ON Line
+             BEGIN SELECT RAISE(ABORT, 'sample_already_invoiced'); END;",
+        )
+        .unwrap();
+        let error = Error::from(conn.execute("INSERT INTO Line VALUES (1)", []).unwrap_err());
+        assert!(matches!(
+            error,
+            Error::InvalidField { entity: "Invoice", ref errors }
+                if errors[0].key == "invoice.error.sample_invoiced"
+        ));
+
+        let error = Error::query(
+            conn.execute("SELECT * FROM Missing", []).unwrap_err(),
+            "SELECT * FROM Missing",
+        );
+        assert_eq!(error.code(), ErrorCode::Database);
+    }
+}
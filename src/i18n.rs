@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use crate::config::Error as ConfigError;
+
+const ES_MX_CATALOG: &str = include_str!("../locales/es-MX.toml");
+const EN_CATALOG: &str = include_str!("../locales/en.toml");
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+static CATALOGS: OnceLock<HashMap<Locale, HashMap<String, String>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    EsMx,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::EsMx, Locale::En];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EsMx => "es-MX",
+            Self::En => "en",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::EsMx => ES_MX_CATALOG,
+            Self::En => EN_CATALOG,
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "es-MX" | "es_MX" | "es" => Ok(Self::EsMx),
+            "en" | "en-US" | "en_US" => Ok(Self::En),
+            _ => Err(ConfigError::InvalidValue {
+                key: "locale".to_string(),
+                value: s.to_string(),
+                reason: "supported locales are es-MX, en".to_string(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    CURRENT_LOCALE.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::ALL[CURRENT_LOCALE.load(Ordering::Relaxed) as usize]
+}
+
+// Texto de la clave en el idioma actual; si falta, se devuelve la clave misma
+pub fn t(key: &str) -> String {
+    t_in(locale(), key)
+}
+
+pub fn t_in(locale: Locale, key: &str) -> String {
+    catalog(locale)
+        .get(key)
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+// Igual que `t` pero sustituye los marcadores `{nombre}` por los argumentos
+pub fn tf(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut text = t(key);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+fn catalog(locale: Locale) -> &'static HashMap<String, String> {
+    let catalogs = CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .iter()
+            .map(|locale| (*locale, parse_catalog(locale.source())))
+            .collect()
+    });
+    &catalogs[&locale]
+}
+
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    let table: toml::Table = source.parse().expect("message catalogs are valid TOML");
+    let mut messages = HashMap::new();
+    flatten("", &table, &mut messages);
+    messages
+}
+
+fn flatten(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match value {
+            toml::Value::Table(table) => flatten(&key, table, messages),
+            toml::Value::String(text) => {
+                messages.insert(key, text.clone());
+            }
+            other => {
+                messages.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+// Mensaje diferido: se traduce al mostrarse, con el idioma vigente en ese momento
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl std::fmt::Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let args: Vec<(&str, &dyn std::fmt::Display)> = self
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn std::fmt::Display))
+            .collect();
+        write!(f, "{}", tf(self.key, &args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<String> {
+        let mut names: Vec<String> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name.to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn every_key_exists_in_each_locale() {
+        let reference = catalog(Locale::EsMx);
+        for locale in Locale::ALL {
+            let messages = catalog(locale);
+            for key in reference.keys() {
+                assert!(messages.contains_key(key), "{} is missing {}", locale, key);
+            }
+            for key in messages.keys() {
+                assert!(
+                    reference.contains_key(key),
+                    "{} has extra key {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn translations_are_not_empty_and_share_placeholders() {
+        let reference = catalog(Locale::EsMx);
+        for locale in Locale::ALL {
+            for (key, text) in catalog(locale) {
+                assert!(!text.trim().is_empty(), "{} has empty {}", locale, key);
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(&reference[key]),
+                    "{} placeholders differ for {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn locale_parses_config_values() {
+        assert_eq!("es-MX".parse::<Locale>().unwrap(), Locale::EsMx);
+        assert_eq!("en".parse::<Locale>().unwrap(), Locale::En);
+        assert!("fr".parse::<Locale>().is_err());
+    }
+}
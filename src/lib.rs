@@ -0,0 +1,8 @@
+pub mod cli;
+pub mod config;
+pub mod data;
+pub mod data_management;
+pub mod error;
+pub mod i18n;
+pub mod logic;
+pub mod view;
@@ -0,0 +1,82 @@
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+// Code 128: ancho de barras y espacios de cada simbolo, empezando por barra.
+// Todos miden 11 modulos salvo el de paro (13).
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const START_B: usize = 104;
+const STOP: usize = 106;
+
+// Modulos en blanco a cada lado que piden los lectores
+pub const CODE128_QUIET_ZONE: usize = 10;
+pub const QR_QUIET_ZONE: usize = 4;
+
+fn barcode_error(message: Message) -> Error {
+    Error::InvalidField {
+        entity: "Label",
+        errors: vec![message],
+    }
+}
+
+// Simbolos del codigo con el juego B (ASCII imprimible): inicio, datos,
+// digito verificador (modulo 103) y paro
+pub fn code128_symbols(text: &str) -> Result<Vec<usize>> {
+    let mut symbols = vec![START_B];
+    for c in text.chars() {
+        if !(' '..='~').contains(&c) {
+            return Err(barcode_error(
+                Message::new("label.error.barcode_char").arg("char", c),
+            ));
+        }
+        symbols.push(c as usize - 32);
+    }
+
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(position, symbol)| position.max(1) * symbol)
+        .sum::<usize>()
+        % 103;
+    symbols.push(checksum);
+    symbols.push(STOP);
+    Ok(symbols)
+}
+
+// Modulos del codigo de izquierda a derecha, sin zona de silencio; `true`
+// es barra
+pub fn code128_modules(text: &str) -> Result<Vec<bool>> {
+    let mut modules = Vec::new();
+    for symbol in code128_symbols(text)? {
+        let widths = CODE128_PATTERNS[symbol].bytes().map(|width| width - b'0');
+        for (index, width) in widths.enumerate() {
+            modules.extend(std::iter::repeat_n(index % 2 == 0, width as usize));
+        }
+    }
+    Ok(modules)
+}
+
+// Matriz del codigo QR por renglones, sin zona de silencio
+pub fn qr_modules(text: &str) -> Result<Vec<Vec<bool>>> {
+    let qr = QrCode::encode_text(text, QrCodeEcc::Medium)
+        .map_err(|_| barcode_error(Message::new("label.error.too_long").arg("text", text)))?;
+    let size = qr.size();
+    Ok((0..size)
+        .map(|y| (0..size).map(|x| qr.get_module(x, y)).collect())
+        .collect())
+}
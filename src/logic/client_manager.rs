@@ -1,47 +1,314 @@
 use crate::data::model::client::Client;
-use crate::data::repo::client_repo::{ClientRepo, Error as RepoError, SearchCriteria};
+use crate::data::model::client_contact::ClientContact;
+use crate::data::model::invoice_status::InvoiceStatus;
+use crate::data::model::money::Money;
+use crate::data::model::role::Permission;
+use crate::data::model::site::Site;
+use crate::data::repo::client_contact_repo::{ClientContactRepo, ContactSearchCriteria};
+use crate::data::repo::client_repo::{ClientRepo, SearchCriteria};
+use crate::data::repo::conector::Connector;
+use crate::data::repo::invoice_repo::{InvoiceRepo, InvoiceSearchCriteria};
+use crate::data::repo::quotation_repo::{QuotationRepo, QuotationSearchCriteria};
+use crate::data::repo::site_repo::{SiteRepo, SiteSearchCriteria};
+use crate::data::table::Patch;
 use crate::data_management::{Finder, LastSearch, Manager, Repository};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::session::Session;
+use crate::logic::{contact, duplicates};
 
-#[allow(unused)]
-#[derive(Debug)]
-pub enum Error<'a> {
-    RepoError(RepoError<'a>),
-    InvalidField {
-        source: String,
-        file: &'a str,
-        line: u32,
-    },
-}
-
-#[allow(unused)]
-impl<'a> std::fmt::Display for Error<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::RepoError(e) => write!(f, "{}", e),
-            Self::InvalidField { source, file, line } => {
-                write!(f, "InvalidField: {} (on {}: {})", source, file, line)
-            }
-        }
-    }
+// Registros que pasan del cliente repetido al que se conserva
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct MergeCounts {
+    pub sites: usize,
+    pub contacts: usize,
+    pub quotations: usize,
+    pub invoices: usize,
 }
 
 pub struct ClientManager<SearchCriteria> {
     repository: ClientRepo,
+    sites: SiteRepo,
+    contacts: ClientContactRepo,
+    invoices: InvoiceRepo,
+    quotations: QuotationRepo,
+    session: Session,
     last_search: Option<LastSearch<SearchCriteria>>,
     last_selected: Option<Client>,
 }
 
 #[allow(unused)]
-impl<'a> ClientManager<SearchCriteria> {
-    pub fn new(page_size: u64) -> Self {
+impl ClientManager<SearchCriteria> {
+    pub fn new(connector: Connector, page_size: u64) -> Self {
         Self {
-            repository: ClientRepo::new(page_size),
+            sites: SiteRepo::new(connector.clone(), page_size),
+            contacts: ClientContactRepo::new(connector.clone(), page_size),
+            invoices: InvoiceRepo::new(connector.clone(), page_size),
+            quotations: QuotationRepo::new(connector.clone(), page_size),
+            repository: ClientRepo::new(connector, page_size),
+            session: Session::default(),
             last_search: None,
             last_selected: None,
         }
     }
 
-    fn update_last_search(&mut self) -> Result<(), Error<'a>> {
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    // Todos los clientes del criterio, para revisar antes de una operacion en lote
+    pub fn find_all(&self, criteria: &SearchCriteria) -> Result<Vec<Client>> {
+        self.repository.find_all(criteria)
+    }
+
+    // Clientes con un nombre parecido, para advertir antes de dar de alta uno repetido
+    pub fn likely_duplicates(&self, client: &Client) -> Result<Vec<Client>> {
+        let candidates: Vec<Client> = self
+            .repository
+            .find_all(&SearchCriteria::default())?
+            .into_iter()
+            .filter(|candidate| {
+                client.id_client.is_none() || candidate.id_client != client.id_client
+            })
+            .collect();
+        Ok(duplicates::likely_duplicates(
+            &client.client_name,
+            candidates,
+            |candidate| &candidate.client_name,
+        ))
+    }
+
+    pub fn sites_of(&self, id_client: u32) -> Result<Vec<Site>> {
+        let criteria = SiteSearchCriteria {
+            id_client: Some(id_client),
+            ..SiteSearchCriteria::default()
+        };
+        self.sites.find_all(&criteria)
+    }
+
+    // Total de las facturas emitidas que el cliente no ha pagado
+    pub fn unpaid_balance(&self, id_client: u32) -> Result<Money> {
+        let criteria = InvoiceSearchCriteria {
+            invoice_active: Some(true),
+            id_client: Some(id_client),
+            invoice_status: Some(InvoiceStatus::Issued),
+            ..InvoiceSearchCriteria::default()
+        };
+        Ok(self
+            .invoices
+            .find_all(&criteria)?
+            .iter()
+            .map(|invoice| invoice.total)
+            .sum())
+    }
+
+    // Cuantos registros moveria `merge` si el cliente se une con otro
+    pub fn merge_preview(&self, id_duplicate: u32) -> Result<MergeCounts> {
+        let contacts = ContactSearchCriteria {
+            id_client: Some(id_duplicate),
+            ..ContactSearchCriteria::default()
+        };
+        let quotations = QuotationSearchCriteria {
+            id_client: Some(id_duplicate),
+            ..QuotationSearchCriteria::default()
+        };
+        let invoices = InvoiceSearchCriteria {
+            id_client: Some(id_duplicate),
+            ..InvoiceSearchCriteria::default()
+        };
+        Ok(MergeCounts {
+            sites: self.sites_of(id_duplicate)?.len(),
+            contacts: self.contacts.find_all(&contacts)?.len(),
+            quotations: self.quotations.find_all(&quotations)?.len(),
+            invoices: self.invoices.find_all(&invoices)?.len(),
+        })
+    }
+
+    // Une el cliente repetido con el que se conserva: sus sitios, contactos,
+    // cotizaciones y facturas pasan al conservado y el repetido se da de baja
+    pub fn merge(&mut self, id_survivor: u32, id_duplicate: u32) -> Result<MergeCounts> {
+        self.session.require(Permission::EditClients)?;
+        let find = |id| {
+            self.repository.search_by_id(id)?.ok_or(Error::NotFound {
+                entity: "Client",
+                id: Some(id),
+            })
+        };
+        let survivor = find(id_survivor)?;
+        let mut duplicate = find(id_duplicate)?;
+
+        let mut errors = Vec::new();
+        if id_survivor == id_duplicate {
+            errors.push(Message::new("client.error.merge_same"));
+        }
+        if !survivor.client_active {
+            errors.push(Message::new("client.error.merge_inactive"));
+        }
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Client",
+                errors,
+            });
+        }
+
+        let moved = self.repository.in_transaction(|conn| {
+            let moved = MergeCounts {
+                sites: SiteRepo::repoint_in(conn, "id_client", id_duplicate, id_survivor)?,
+                contacts: ClientContactRepo::repoint_in(
+                    conn,
+                    "id_client",
+                    id_duplicate,
+                    id_survivor,
+                )?,
+                quotations: QuotationRepo::repoint_in(
+                    conn,
+                    "id_client",
+                    id_duplicate,
+                    id_survivor,
+                )?,
+                invoices: InvoiceRepo::repoint_in(conn, "id_client", id_duplicate, id_survivor)?,
+            };
+            ClientRepo::drop_in(conn, &mut duplicate)?;
+            Ok(moved)
+        })?;
+        self.update_last_search()?;
+        Ok(moved)
+    }
+
+    // RFC en mayusculas; los campos opcionales vacios se guardan como NULL
+    fn normalized(item: &Client) -> Client {
+        let mut item = item.clone();
+        item.client_rfc = item
+            .client_rfc
+            .as_deref()
+            .map(contact::normalize_rfc)
+            .filter(|rfc| !rfc.is_empty());
+        item.billing_address = item
+            .billing_address
+            .as_deref()
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(str::to_string);
+        item
+    }
+
+    // Un cliente con facturas o cotizaciones no se puede eliminar, solo dar de baja
+    fn document_errors(&self, item: &Client) -> Result<Vec<Message>> {
+        let mut errors = Vec::new();
+        let Some(id_client) = item.id_client else {
+            return Ok(errors);
+        };
+        let invoices = InvoiceSearchCriteria {
+            id_client: Some(id_client),
+            ..InvoiceSearchCriteria::default()
+        };
+        if !self.invoices.find_all(&invoices)?.is_empty() {
+            errors.push(Message::new("client.error.has_invoices").arg("name", &item.client_name));
+        }
+        let quotations = QuotationSearchCriteria {
+            id_client: Some(id_client),
+            ..QuotationSearchCriteria::default()
+        };
+        if !self.quotations.find_all(&quotations)?.is_empty() {
+            errors.push(Message::new("client.error.has_quotations").arg("name", &item.client_name));
+        }
+        Ok(errors)
+    }
+
+    // Validaciones que aplican igual al dar de alta que al editar
+    fn field_errors(item: &Client) -> Vec<Message> {
+        let mut errors = Vec::new();
+        if item.client_name.trim().is_empty() {
+            errors.push(Message::new("client.error.name_empty"));
+        }
+        if let Some(rfc) = item
+            .client_rfc
+            .as_deref()
+            .filter(|rfc| !contact::valid_rfc(rfc))
+        {
+            errors.push(Message::new("client.error.rfc").arg("value", rfc));
+        }
+        errors
+    }
+
+    pub fn contacts_of(&self, id_client: u32) -> Result<Vec<ClientContact>> {
+        let criteria = ContactSearchCriteria {
+            id_client: Some(id_client),
+            contact_active: Some(true),
+            ..ContactSearchCriteria::default()
+        };
+        self.contacts.find_all(&criteria)
+    }
+
+    fn valid_contact(&self, contact: &ClientContact) -> Result<ClientContact> {
+        let mut contact = contact.clone();
+        contact.contact_name = contact.contact_name.trim().to_string();
+        for field in [
+            &mut contact.contact_role,
+            &mut contact.email,
+            &mut contact.phone,
+        ] {
+            *field = field
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string);
+        }
+
+        self.repository
+            .search_by_id(contact.id_client)?
+            .ok_or(Error::NotFound {
+                entity: "Client",
+                id: Some(contact.id_client),
+            })?;
+
+        let mut errors = Vec::new();
+        if contact.contact_name.is_empty() {
+            errors.push(Message::new("client.error.contact_name_empty"));
+        }
+        if let Some(email) = contact
+            .email
+            .as_deref()
+            .filter(|e| !contact::valid_email(e))
+        {
+            errors.push(Message::new("client.error.email").arg("value", email));
+        }
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "ClientContact",
+                errors,
+            });
+        }
+        Ok(contact)
+    }
+
+    // Alta de un contacto; devuelve su id
+    pub fn add_contact(&mut self, contact: &ClientContact) -> Result<u32> {
+        self.session.require(Permission::EditClients)?;
+        if contact.id_contact.is_some() {
+            return Err(Error::InvalidField {
+                entity: "ClientContact",
+                errors: vec![Message::new("client.error.existing")],
+            });
+        }
+        let mut contact = self.valid_contact(contact)?;
+        contact.contact_active = true;
+        self.contacts.insert(&contact)
+    }
+
+    pub fn modify_contact(&mut self, contact: &ClientContact) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        let contact = self.valid_contact(contact)?;
+        self.contacts.modify(&contact)
+    }
+
+    pub fn drop_contact(&mut self, contact: &mut ClientContact) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        self.contacts.drop(contact)
+    }
+
+    fn update_last_search(&mut self) -> Result<()> {
         if self.last_search.is_none() {
             return Ok(());
         }
@@ -54,32 +321,24 @@ impl<'a> ClientManager<SearchCriteria> {
 }
 
 #[allow(unused)]
-impl<'a> Manager<Client, SearchCriteria, Error<'a>> for ClientManager<SearchCriteria> {
-    fn valid_item(&self, item: &Client) -> Result<(), Error<'a>> {
+impl Manager<Client, SearchCriteria, Error> for ClientManager<SearchCriteria> {
+    fn valid_item(&self, item: &Client) -> Result<()> {
         let mut errors = Vec::new();
 
         if item.id_client.is_some() {
-            errors.push("se intenta agregar un elemento existente".to_string());
+            errors.push(Message::new("client.error.existing"));
         }
 
         if !item.client_active {
-            errors.push("el campo client_active debe ser true".to_string());
+            errors.push(Message::new("client.error.inactive"));
         }
 
-        if item.client_name.is_empty() {
-            errors.push("el nombre del cliente no puede estar vacío".to_string());
-        }
+        errors.extend(Self::field_errors(item));
 
         if !errors.is_empty() {
-            let mut error_message = errors.join(", ");
-            if let Some(first_char) = error_message.get_mut(0..1) {
-                first_char.make_ascii_uppercase();
-            }
-
             return Err(Error::InvalidField {
-                source: error_message,
-                file: file!(),
-                line: line!(),
+                entity: "Client",
+                errors,
             });
         }
 
@@ -103,43 +362,108 @@ impl<'a> Manager<Client, SearchCriteria, Error<'a>> for ClientManager<SearchCrit
     }
 }
 
-impl<'a> Repository<Client, Error<'a>> for ClientManager<SearchCriteria> {
-    fn add(&mut self, item: &Client) -> Result<(), Error<'a>> {
-        self.valid_item(item)?;
-        self.repository.add(item).map_err(|e| Error::RepoError(e))?;
+impl Repository<Client, Error> for ClientManager<SearchCriteria> {
+    fn add(&mut self, item: &Client) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        let item = Self::normalized(item);
+        self.valid_item(&item)?;
+        self.repository.add(&item)?;
         self.update_last_search()?;
         Ok(())
     }
 
-    fn drop(&mut self, item: &mut Client) -> Result<(), Error<'a>> {
-        self.repository
-            .drop(item)
-            .map_err(|e| Error::RepoError(e))?;
+    fn drop(&mut self, item: &mut Client) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        self.repository.drop(item)?;
         self.update_last_search()?;
         Ok(())
     }
 
-    fn delete(&mut self, item: &Client) -> Result<(), Error<'a>> {
-        self.repository
-            .delete(item)
-            .map_err(|e| Error::RepoError(e))?;
+    fn delete(&mut self, item: &Client) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        let errors = self.document_errors(item)?;
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Client",
+                errors,
+            });
+        }
+        self.repository.delete(item)?;
         self.update_last_search()?;
         Ok(())
     }
 
-    fn modify(&mut self, item: &Client) -> Result<(), Error<'a>> {
-        self.repository
-            .modify(item)
-            .map_err(|e| Error::RepoError(e))?;
+    fn modify(&mut self, item: &Client) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        let item = Self::normalized(item);
+        let errors = Self::field_errors(&item);
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Client",
+                errors,
+            });
+        }
+        self.repository.modify(&item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn add_many(&mut self, items: &[Client]) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        let items: Vec<Client> = items.iter().map(Self::normalized).collect();
+        for item in &items {
+            self.valid_item(item)?;
+        }
+        self.repository.add_many(&items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn drop_many(&mut self, items: &mut [Client]) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        self.repository.drop_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn delete_many(&mut self, items: &[Client]) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        let mut errors = Vec::new();
+        for item in items {
+            errors.extend(self.document_errors(item)?);
+        }
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Client",
+                errors,
+            });
+        }
+        self.repository.delete_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn modify_many(&mut self, items: &[Client], patch: &Patch) -> Result<()> {
+        self.session.require(Permission::EditClients)?;
+        for item in items {
+            let errors = Self::field_errors(&patch.apply(item)?);
+            if !errors.is_empty() {
+                return Err(Error::InvalidField {
+                    entity: "Client",
+                    errors,
+                });
+            }
+        }
+        self.repository.modify_many(items, patch)?;
         self.update_last_search()?;
         Ok(())
     }
 }
 
 #[allow(unused)]
-impl<'a> Finder<Client, SearchCriteria, Error<'a>> for ClientManager<SearchCriteria> {
-    fn from_row(row: &rusqlite::Row) -> Result<Client, Error<'a>> {
-        let row = ClientRepo::from_row(row).map_err(|e| Error::RepoError(e))?;
+impl Finder<Client, SearchCriteria, Error> for ClientManager<SearchCriteria> {
+    fn from_row(row: &rusqlite::Row) -> Result<Client> {
+        let row = ClientRepo::from_row(row)?;
         Ok(row)
     }
 
@@ -151,20 +475,14 @@ impl<'a> Finder<Client, SearchCriteria, Error<'a>> for ClientManager<SearchCrite
         &mut self,
         criteria: &SearchCriteria,
         page_number: u64,
-    ) -> Result<LastSearch<SearchCriteria>, Error<'a>> {
-        let result = self
-            .repository
-            .search_by(criteria, page_number)
-            .map_err(|e| Error::RepoError(e))?;
+    ) -> Result<LastSearch<SearchCriteria>> {
+        let result = self.repository.search_by(criteria, page_number)?;
         self.set_last_search(result.clone());
         Ok(result)
     }
 
-    fn search_by_id(&self, id: u32) -> Result<Option<Client>, Error<'a>> {
-        let search = self
-            .repository
-            .search_by_id(id)
-            .map_err(|e| Error::RepoError(e))?;
+    fn search_by_id(&self, id: u32) -> Result<Option<Client>> {
+        let search = self.repository.search_by_id(id)?;
         Ok(search)
     }
 }
@@ -0,0 +1,149 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::data::model::result_limits::ResultLimits;
+use crate::data::model::result_value::{BelowLimitPolicy, ResultValue};
+use crate::data::repo::compliance_repo::ComplianceRepo;
+use crate::error::Result;
+use crate::logic::units;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    Compliant,
+    NonCompliant,
+    // Sin limites en la norma o con un resultado no numerico
+    Unknown,
+}
+
+impl ComplianceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Compliant => "compliant",
+            Self::NonCompliant => "non_compliant",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SiteCompliance {
+    pub id_sample: u32,
+    pub control_number: String,
+    pub sampling_date: NaiveDate,
+    pub status: ComplianceStatus,
+}
+
+fn evaluate_number(value: f64, min_value: Option<f64>, max_value: Option<f64>) -> ComplianceStatus {
+    let above_min = min_value.is_none_or(|min| value >= min);
+    let below_max = max_value.is_none_or(|max| value <= max);
+    if above_min && below_max {
+        ComplianceStatus::Compliant
+    } else {
+        ComplianceStatus::NonCompliant
+    }
+}
+
+// "<L" y ">L" se evaluan con lo que se sabe del valor real; si no alcanza
+// (p. ej. "<0.01" contra un maximo de 0.005) un resultado debajo del limite
+// toma el valor que indique `policy`
+pub fn evaluate(
+    value: &ResultValue,
+    min_value: Option<f64>,
+    max_value: Option<f64>,
+    policy: BelowLimitPolicy,
+) -> ComplianceStatus {
+    if min_value.is_none() && max_value.is_none() {
+        return ComplianceStatus::Unknown;
+    }
+
+    match value {
+        ResultValue::Numeric(value) => evaluate_number(*value, min_value, max_value),
+        ResultValue::BelowLimit(Some(limit)) => {
+            if min_value.is_some_and(|min| *limit <= min) {
+                return ComplianceStatus::NonCompliant;
+            }
+            if min_value.is_none() && max_value.is_some_and(|max| *limit <= max) {
+                return ComplianceStatus::Compliant;
+            }
+            match policy.substitute(*limit) {
+                Some(value) => evaluate_number(value, min_value, max_value),
+                None => ComplianceStatus::Unknown,
+            }
+        }
+        ResultValue::AboveRange(Some(limit)) => {
+            if max_value.is_some_and(|max| *limit >= max) {
+                return ComplianceStatus::NonCompliant;
+            }
+            if max_value.is_none() && min_value.is_some_and(|min| *limit >= min) {
+                return ComplianceStatus::Compliant;
+            }
+            ComplianceStatus::Unknown
+        }
+        _ => ComplianceStatus::Unknown,
+    }
+}
+
+// Una muestra incumple si cualquiera de sus resultados incumple
+pub fn combine(statuses: impl IntoIterator<Item = ComplianceStatus>) -> ComplianceStatus {
+    let mut combined = ComplianceStatus::Unknown;
+    for status in statuses {
+        match status {
+            ComplianceStatus::NonCompliant => return status,
+            ComplianceStatus::Compliant => combined = status,
+            ComplianceStatus::Unknown => {}
+        }
+    }
+    combined
+}
+
+// Evalua el resultado en la unidad de la norma; si se capturo en otra se
+// convierte, y falla si la unidad no existe o no es de la misma magnitud.
+// El limite de deteccion del analisis esta en la unidad de la norma.
+pub fn evaluate_in_units(
+    result: &ResultLimits,
+    policy: BelowLimitPolicy,
+) -> Result<ComplianceStatus> {
+    if result.min_value.is_none() && result.max_value.is_none() {
+        return Ok(ComplianceStatus::Unknown);
+    }
+
+    let mut value = result.result.clone();
+    if let (Some(result_unit), Some(std_units)) = (&result.result_unit, &result.std_units) {
+        if result_unit.trim() != std_units.trim() {
+            value = value.try_map(|v| units::convert_text(v, result_unit, std_units))?;
+        }
+    }
+    let value = value.with_detection_limit(result.detection_limit);
+    Ok(evaluate(&value, result.min_value, result.max_value, policy))
+}
+
+pub fn sample_status(
+    results: &[ResultLimits],
+    policy: BelowLimitPolicy,
+) -> Result<ComplianceStatus> {
+    let statuses = results
+        .iter()
+        .map(|result| evaluate_in_units(result, policy))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(combine(statuses))
+}
+
+// Estado de la muestra mas reciente del sitio; None si no tiene resultados
+pub fn latest_site_compliance(
+    repo: &ComplianceRepo,
+    id_site: u32,
+    policy: BelowLimitPolicy,
+) -> Result<Option<SiteCompliance>> {
+    let results = repo.latest_site_results(id_site)?;
+    let Some(first) = results.first() else {
+        return Ok(None);
+    };
+
+    Ok(Some(SiteCompliance {
+        id_sample: first.id_sample,
+        control_number: first.control_number.clone(),
+        sampling_date: first.sampling_date,
+        status: sample_status(&results, policy)?,
+    }))
+}
@@ -0,0 +1,52 @@
+use chrono::NaiveDate;
+
+// Validacion de los datos de contacto y fiscales de los clientes
+
+// Forma basica usuario@dominio.tld, sin espacios
+pub fn valid_email(email: &str) -> bool {
+    let Some((user, domain)) = email.split_once('@') else {
+        return false;
+    };
+    if user.is_empty() || email.chars().any(char::is_whitespace) || domain.contains('@') {
+        return false;
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+// RFC del SAT: 3 letras (persona moral) o 4 (persona fisica), fecha AAMMDD y
+// homoclave de 3 caracteres, p. ej. "APS850101AB1"
+pub fn valid_rfc(rfc: &str) -> bool {
+    let chars: Vec<char> = rfc.chars().collect();
+    let letters = match chars.len() {
+        12 => 3,
+        13 => 4,
+        _ => return false,
+    };
+
+    let (name, rest) = chars.split_at(letters);
+    let (date, homoclave) = rest.split_at(6);
+    // El año no trae siglo: basta con que la fecha exista en alguno
+    let date: String = date.iter().collect();
+
+    name.iter()
+        .all(|c| c.is_ascii_uppercase() || *c == 'Ñ' || *c == '&')
+        && ["19", "20"].iter().any(|century| {
+            NaiveDate::parse_from_str(&format!("{}{}", century, date), "%Y%m%d").is_ok()
+        })
+        && homoclave
+            .iter()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+// El RFC se guarda en mayusculas y sin espacios
+pub fn normalize_rfc(rfc: &str) -> String {
+    rfc.split_whitespace().collect::<String>().to_uppercase()
+}
@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::config::Error as ConfigError;
+
+pub const DEFAULT_PATTERN: &str = "{YYYY}-{client}-{seq:05}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Year,
+    ShortYear,
+    Month,
+    Client,
+    Seq,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    // Campo con el ancho minimo que se rellena con ceros
+    Field(Field, usize),
+}
+
+// Formato del numero de control, p. ej. `{YYYY}-{client}-{seq:05}`:
+// {YYYY}/{YY} año y {MM} mes de recepcion, {client} id del cliente del sitio
+// (0 si no tiene) y {seq} consecutivo del año; `:0N` rellena con ceros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlNumberPattern {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl ControlNumberPattern {
+    pub fn format(&self, date: NaiveDate, id_client: Option<u32>, seq: u64) -> String {
+        let mut number = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => number.push_str(text),
+                Part::Field(field, width) => {
+                    let value = match field {
+                        Field::Year => date.year_ce().1.into(),
+                        Field::ShortYear => (date.year_ce().1 % 100).into(),
+                        Field::Month => date.month().into(),
+                        Field::Client => id_client.unwrap_or(0).into(),
+                        Field::Seq => seq,
+                    };
+                    number.push_str(&format!("{:0width$}", value, width = *width));
+                }
+            }
+        }
+        number
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn invalid(pattern: &str, reason: &str) -> ConfigError {
+        ConfigError::InvalidValue {
+            key: "control_number_pattern".to_string(),
+            value: pattern.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Default for ControlNumberPattern {
+    fn default() -> Self {
+        DEFAULT_PATTERN.parse().expect("valid default pattern")
+    }
+}
+
+impl std::fmt::Display for ControlNumberPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for ControlNumberPattern {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            let Some(start) = rest.find(['{', '}']) else {
+                parts.push(Part::Text(rest.to_string()));
+                break;
+            };
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            if rest[start..].starts_with('}') {
+                return Err(Self::invalid(s, "unmatched }"));
+            }
+
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| Self::invalid(s, "unmatched {"))?
+                + start;
+            let (name, width) = match rest[start + 1..end].split_once(':') {
+                Some((name, width)) => {
+                    let width = width
+                        .strip_prefix('0')
+                        .and_then(|width| width.parse::<usize>().ok())
+                        .filter(|width| (1..=20).contains(width))
+                        .ok_or_else(|| {
+                            Self::invalid(s, "widths are written as :0N, e.g. {seq:05}")
+                        })?;
+                    (name, Some(width))
+                }
+                None => (&rest[start + 1..end], None),
+            };
+            let field = match name {
+                "YYYY" => Field::Year,
+                "YY" => Field::ShortYear,
+                "MM" => Field::Month,
+                "client" => Field::Client,
+                "seq" => Field::Seq,
+                _ => {
+                    return Err(Self::invalid(
+                        s,
+                        "expected {YYYY}, {YY}, {MM}, {client} or {seq}",
+                    ))
+                }
+            };
+            let default_width = match field {
+                Field::ShortYear | Field::Month => 2,
+                _ => 0,
+            };
+            parts.push(Part::Field(field, width.unwrap_or(default_width)));
+            rest = &rest[end + 1..];
+        }
+
+        let has = |expected: &[Field]| {
+            parts
+                .iter()
+                .any(|part| matches!(part, Part::Field(field, _) if expected.contains(field)))
+        };
+        // El consecutivo reinicia cada año, sin el año los numeros se repetirian
+        if !has(&[Field::Seq]) || !has(&[Field::Year, Field::ShortYear]) {
+            return Err(Self::invalid(s, "must contain {seq} and {YYYY} or {YY}"));
+        }
+
+        Ok(Self {
+            source: s.to_string(),
+            parts,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+// Deteccion de registros repetidos por nombre: se comparan sin mayusculas,
+// acentos ni puntuacion y se toleran errores de captura con la distancia de
+// Levenshtein.
+
+// Por cada 5 caracteres del nombre mas largo se tolera una edicion
+const CHARS_PER_EDIT: usize = 5;
+
+// "AGUA POTABLE, S.A." -> "agua potable sa"
+pub fn normalize(text: &str) -> String {
+    let folded: String = text
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' | 'ã' => Some('a'),
+            'é' | 'è' | 'ë' | 'ê' => Some('e'),
+            'í' | 'ì' | 'ï' | 'î' => Some('i'),
+            'ó' | 'ò' | 'ö' | 'ô' | 'õ' => Some('o'),
+            'ú' | 'ù' | 'ü' | 'û' => Some('u'),
+            'ñ' => Some('n'),
+            'ç' => Some('c'),
+            c if c.is_alphanumeric() || c.is_whitespace() => Some(c),
+            _ => None,
+        })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Minimo de inserciones, borrados y sustituciones para pasar de `a` a `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// Distancia entre los nombres normalizados si parecen el mismo
+pub fn name_distance(a: &str, b: &str) -> Option<usize> {
+    let (a, b) = (normalize(a), normalize(b));
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let tolerance = a.chars().count().max(b.chars().count()) / CHARS_PER_EDIT;
+    let distance = levenshtein(&a, &b);
+    (distance <= tolerance).then_some(distance)
+}
+
+// Candidatos cuyo nombre se parece a `name`, del mas parecido al menos
+pub fn likely_duplicates<T, F>(name: &str, candidates: Vec<T>, name_of: F) -> Vec<T>
+where
+    F: Fn(&T) -> &str,
+{
+    let mut matches: Vec<(usize, T)> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((name_distance(name, name_of(&candidate))?, candidate)))
+        .collect();
+    matches.sort_by_key(|(distance, _)| *distance);
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
@@ -0,0 +1,156 @@
+use serde::Serialize;
+
+use crate::data::model::client::Client;
+use crate::data::model::invoice::Invoice;
+use crate::data::model::invoice_line::InvoiceLine;
+use crate::data::model::invoice_status::InvoiceStatus;
+use crate::i18n::{t, tf};
+use crate::logic::totals::{Totals, DESCRIPTION_WIDTH, NUMBER_WIDTH};
+
+// Folio de la factura: "F2024-00001"
+pub fn invoice_number(year: i32, seq: u64) -> String {
+    format!("F{}-{:05}", year, seq)
+}
+
+pub fn status_label(status: InvoiceStatus) -> String {
+    t(&format!("invoice.status.{}", status.as_str()))
+}
+
+// Importes de la factura con sus partidas
+pub fn apply_totals(invoice: &mut Invoice, lines: &[InvoiceLine]) {
+    let totals = Totals::new(
+        lines.iter().map(|line| line.unit_price).sum(),
+        invoice.discount_percent,
+        invoice.tax_percent,
+    );
+    invoice.subtotal = totals.subtotal;
+    invoice.discount = totals.discount;
+    invoice.tax = totals.tax;
+    invoice.total = totals.total;
+}
+
+// Importes guardados de la factura, para imprimirlos
+pub fn totals_of(invoice: &Invoice) -> Totals {
+    Totals {
+        discount_percent: invoice.discount_percent,
+        tax_percent: invoice.tax_percent,
+        subtotal: invoice.subtotal,
+        discount: invoice.discount,
+        tax: invoice.tax,
+        total: invoice.total,
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct DocumentLine {
+    pub control_number: String,
+    #[serde(flatten)]
+    pub line: InvoiceLine,
+}
+
+// Factura lista para imprimirse o exportarse
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct InvoiceDocument {
+    pub invoice: Invoice,
+    pub client: Client,
+    pub lines: Vec<DocumentLine>,
+}
+
+const SAMPLE_WIDTH: usize = 14;
+
+const CSV_HEADER: &str =
+    "invoice_number,status,client,rfc,drafted_on,issued_on,paid_on,cancelled_on,subtotal,discount,tax,total";
+
+// Comillas solo cuando el valor las necesita, como lo pide RFC 4180
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Un renglon por factura con sus importes; los montos van sin formato
+pub fn to_csv(documents: &[InvoiceDocument]) -> String {
+    let mut rows = vec![CSV_HEADER.to_string()];
+    for document in documents {
+        let invoice = &document.invoice;
+        let date =
+            |date: Option<chrono::NaiveDate>| date.map(|d| d.to_string()).unwrap_or_default();
+        let fields = [
+            invoice.invoice_number.clone().unwrap_or_default(),
+            invoice.invoice_status.as_str().to_string(),
+            document.client.client_name.clone(),
+            document.client.client_rfc.clone().unwrap_or_default(),
+            invoice.drafted_on.to_string(),
+            date(invoice.issued_on),
+            date(invoice.paid_on),
+            date(invoice.cancelled_on),
+            invoice.subtotal.to_decimal(),
+            invoice.discount.to_decimal(),
+            invoice.tax.to_decimal(),
+            invoice.total.to_decimal(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        rows.push(fields.join(","));
+    }
+    rows.join("\n") + "\n"
+}
+
+impl InvoiceDocument {
+    pub fn render(&self) -> String {
+        let invoice = &self.invoice;
+        let title = match &invoice.invoice_number {
+            Some(number) => tf("invoice.document.title", &[("number", number)]),
+            None => tf(
+                "invoice.document.draft_title",
+                &[("id", &invoice.id_invoice.unwrap_or_default())],
+            ),
+        };
+        let mut rows = vec![
+            title,
+            tf(
+                "invoice.document.status",
+                &[("status", &status_label(invoice.invoice_status))],
+            ),
+            tf("invoice.document.drafted", &[("date", &invoice.drafted_on)]),
+        ];
+        for (key, date) in [
+            ("invoice.document.issued", invoice.issued_on),
+            ("invoice.document.paid", invoice.paid_on),
+            ("invoice.document.cancelled", invoice.cancelled_on),
+        ] {
+            if let Some(date) = date {
+                rows.push(tf(key, &[("date", &date)]));
+            }
+        }
+        rows.push(tf(
+            "invoice.document.client",
+            &[("name", &self.client.client_name)],
+        ));
+        if let Some(rfc) = &self.client.client_rfc {
+            rows.push(tf("invoice.document.rfc", &[("rfc", rfc)]));
+        }
+        if let Some(address) = &self.client.billing_address {
+            rows.push(tf("invoice.document.address", &[("address", address)]));
+        }
+
+        rows.push(String::new());
+        rows.push(format!(
+            "{:<SAMPLE_WIDTH$} {:<DESCRIPTION_WIDTH$} {:>NUMBER_WIDTH$}",
+            t("invoice.document.sample"),
+            t("invoice.document.description"),
+            t("invoice.document.amount"),
+        ));
+        for line in &self.lines {
+            rows.push(format!(
+                "{:<SAMPLE_WIDTH$} {:<DESCRIPTION_WIDTH$} {:>NUMBER_WIDTH$}",
+                line.control_number, line.line.description, line.line.unit_price,
+            ));
+        }
+
+        rows.push(String::new());
+        rows.extend(totals_of(invoice).rows(SAMPLE_WIDTH + DESCRIPTION_WIDTH + 1));
+        rows.join("\n") + "\n"
+    }
+}
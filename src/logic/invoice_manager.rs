@@ -0,0 +1,482 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::data::model::analysis::Analysis;
+use crate::data::model::client::Client;
+use crate::data::model::invoice::Invoice;
+use crate::data::model::invoice_line::InvoiceLine;
+use crate::data::model::invoice_status::InvoiceStatus;
+use crate::data::model::role::Permission;
+use crate::data::model::sample::Sample;
+use crate::data::model::sample_status::SampleStatus;
+use crate::data::repo::analysis_repo::AnalysisRepo;
+use crate::data::repo::client_repo::ClientRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::invoice_line_repo::{
+    InvoiceLineRepo, InvoiceLineSearchCriteria, InvoicedSampleRepo,
+};
+use crate::data::repo::invoice_number_repo::InvoiceNumberRepo;
+use crate::data::repo::invoice_repo::{InvoiceRepo, InvoiceSearchCriteria};
+use crate::data::repo::quotation_item_repo::QuotationItemRepo;
+use crate::data::repo::quotation_repo::{QuotationRepo, QuotationSearchCriteria};
+use crate::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use crate::data::repo::sample_result_repo::{SampleResultRepo, SampleResultSearchCriteria};
+use crate::data::repo::site_repo::{SiteRepo, SiteSearchCriteria};
+use crate::data_management::Finder;
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::invoice::{self, DocumentLine, InvoiceDocument};
+use crate::logic::session::Session;
+
+// Facturas de las muestras reportadas: una partida por cada analisis con
+// resultado, al precio de lista vigente al crear el borrador, o por muestra
+// al precio de la cotizacion de la que salio
+pub struct InvoiceManager {
+    invoices: InvoiceRepo,
+    lines: InvoiceLineRepo,
+    clients: ClientRepo,
+    sites: SiteRepo,
+    samples: SampleRepo,
+    results: SampleResultRepo,
+    analyses: AnalysisRepo,
+    quotations: QuotationRepo,
+    items: QuotationItemRepo,
+    tax_percent: u32,
+    session: Session,
+}
+
+impl InvoiceManager {
+    pub fn new(connector: Connector, page_size: u64, tax_percent: u32) -> Self {
+        Self {
+            invoices: InvoiceRepo::new(connector.clone(), page_size),
+            lines: InvoiceLineRepo::new(connector.clone(), page_size),
+            clients: ClientRepo::new(connector.clone(), page_size),
+            sites: SiteRepo::new(connector.clone(), page_size),
+            samples: SampleRepo::new(connector.clone(), page_size),
+            results: SampleResultRepo::new(connector.clone(), page_size),
+            analyses: AnalysisRepo::new(connector.clone(), page_size),
+            quotations: QuotationRepo::new(connector.clone(), page_size),
+            items: QuotationItemRepo::new(connector, page_size),
+            tax_percent,
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    fn invoice_error(message: Message) -> Error {
+        Error::InvalidField {
+            entity: "Invoice",
+            errors: vec![message],
+        }
+    }
+
+    fn client(&self, id_client: u32) -> Result<Client> {
+        self.clients
+            .search_by_id(id_client)?
+            .ok_or(Error::NotFound {
+                entity: "Client",
+                id: Some(id_client),
+            })
+    }
+
+    fn is_billable(status: SampleStatus) -> bool {
+        matches!(status, SampleStatus::Reported | SampleStatus::Archived)
+    }
+
+    // La muestra ya esta en una factura que no se cancelo
+    fn is_invoiced(&self, id_sample: u32) -> Result<bool> {
+        let conn = self.lines.connector().get_connection()?;
+        Ok(InvoicedSampleRepo::invoice_of_in(&conn, id_sample)?.is_some())
+    }
+
+    // El cliente del sitio; una muestra de una cotizacion sin sitio es del
+    // cliente de la cotizacion
+    fn client_of(&self, sample: &Sample) -> Result<Option<u32>> {
+        match (sample.id_site, sample.id_quotation) {
+            (Some(id_site), _) => Ok(self
+                .sites
+                .search_by_id(id_site)?
+                .and_then(|site| site.id_client)),
+            (None, Some(id_quotation)) => Ok(self
+                .quotations
+                .search_by_id(id_quotation)?
+                .map(|quotation| quotation.id_client)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    // Muestras reportadas o archivadas de los sitios del cliente, o de sus
+    // cotizaciones sin sitio, que todavia no estan en una factura
+    pub fn billable(&self, id_client: u32) -> Result<Vec<Sample>> {
+        let mut candidates = Vec::new();
+        let criteria = SiteSearchCriteria {
+            id_client: Some(id_client),
+            ..SiteSearchCriteria::default()
+        };
+        for site in self.sites.find_all(&criteria)? {
+            let criteria = SampleSearchCriteria {
+                sample_active: Some(true),
+                id_site: site.id_site,
+                ..SampleSearchCriteria::default()
+            };
+            candidates.extend(self.samples.find_all(&criteria)?);
+        }
+        let criteria = QuotationSearchCriteria {
+            id_client: Some(id_client),
+            ..QuotationSearchCriteria::default()
+        };
+        for quotation in self.quotations.find_all(&criteria)? {
+            let criteria = SampleSearchCriteria {
+                sample_active: Some(true),
+                id_quotation: quotation.id_quotation,
+                ..SampleSearchCriteria::default()
+            };
+            candidates.extend(
+                self.samples
+                    .find_all(&criteria)?
+                    .into_iter()
+                    .filter(|sample| sample.id_site.is_none()),
+            );
+        }
+
+        let mut samples = Vec::new();
+        for sample in candidates {
+            if Self::is_billable(sample.sample_status)
+                && !self.is_invoiced(sample.id_sample.unwrap_or_default())?
+            {
+                samples.push(sample);
+            }
+        }
+        samples.sort_by(|a, b| a.control_number.cmp(&b.control_number));
+        Ok(samples)
+    }
+
+    // Analisis con resultado activo de la muestra, sin repetir
+    pub fn analyses_of(&self, id_sample: u32) -> Result<Vec<Analysis>> {
+        let criteria = SampleResultSearchCriteria {
+            id_sample: Some(id_sample),
+            result_active: Some(true),
+            ..SampleResultSearchCriteria::default()
+        };
+        let mut ids: Vec<u32> = self
+            .results
+            .find_all(&criteria)?
+            .into_iter()
+            .filter_map(|result| result.id_analysis)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut analyses = Vec::new();
+        for id_analysis in ids {
+            analyses.extend(self.analyses.search_by_id(id_analysis)?);
+        }
+        Ok(analyses)
+    }
+
+    // Partidas del borrador y el descuento que lleva. Las muestras de una
+    // cotizacion se facturan con el precio de su partida y el descuento de la
+    // cotizacion; las demas, con el precio de lista de cada analisis con
+    // resultado y el descuento del cliente. Cada factura lleva un solo
+    // descuento, asi que no se mezclan muestras de distintas cotizaciones.
+    fn valid_draft(&self, id_client: u32, id_samples: &[u32]) -> Result<(Vec<InvoiceLine>, f64)> {
+        let mut errors = Vec::new();
+
+        let client = match self.clients.search_by_id(id_client)? {
+            Some(client) if client.client_active => Some(client),
+            _ => {
+                errors.push(Message::new("invoice.error.client").arg("id", id_client));
+                None
+            }
+        };
+
+        if id_samples.is_empty() {
+            errors.push(Message::new("invoice.error.no_samples"));
+        }
+        let mut lines = Vec::new();
+        let mut unpriced: Vec<String> = Vec::new();
+        let mut quotations: Vec<Option<u32>> = Vec::new();
+        for id_sample in id_samples {
+            let sample = match self.samples.search_by_id(*id_sample)? {
+                Some(sample) if sample.sample_active => sample,
+                _ => {
+                    errors.push(Message::new("invoice.error.sample").arg("id", id_sample));
+                    continue;
+                }
+            };
+            let number = &sample.control_number;
+            if self.client_of(&sample)? != Some(id_client) {
+                errors.push(Message::new("invoice.error.sample_client").arg("number", number));
+            } else if !Self::is_billable(sample.sample_status) {
+                errors.push(Message::new("invoice.error.not_reported").arg("number", number));
+            }
+            if !quotations.contains(&sample.id_quotation) {
+                quotations.push(sample.id_quotation);
+            }
+
+            if let Some(id_quotation) = sample.id_quotation {
+                let item = match sample.id_quotation_item {
+                    Some(id_item) => self.items.search_by_id(id_item)?,
+                    None => None,
+                };
+                match item {
+                    Some(item) => lines.push(InvoiceLine {
+                        id_line: None,
+                        id_invoice: 0,
+                        id_sample: *id_sample,
+                        id_analysis: None,
+                        id_item: item.id_item,
+                        description: item.description,
+                        unit_price: item.unit_price,
+                    }),
+                    None => errors.push(
+                        Message::new("invoice.error.quoted_item")
+                            .arg("number", number)
+                            .arg("id", id_quotation),
+                    ),
+                }
+                continue;
+            }
+
+            let analyses = self.analyses_of(*id_sample)?;
+            if analyses.is_empty() {
+                errors.push(Message::new("invoice.error.no_analyses").arg("number", number));
+            }
+            for analysis in analyses {
+                let Some(unit_price) = analysis.price else {
+                    if !unpriced.contains(&analysis.analysis_name) {
+                        unpriced.push(analysis.analysis_name);
+                    }
+                    continue;
+                };
+                lines.push(InvoiceLine {
+                    id_line: None,
+                    id_invoice: 0,
+                    id_sample: *id_sample,
+                    id_analysis: analysis.id_analysis,
+                    id_item: None,
+                    description: analysis.analysis_name,
+                    unit_price,
+                });
+            }
+        }
+        for name in unpriced {
+            errors.push(Message::new("quotation.error.no_price").arg("name", name));
+        }
+        if quotations.len() > 1 {
+            errors.push(Message::new("invoice.error.mixed_quotations"));
+        }
+
+        let discount_percent = match (quotations.first(), client) {
+            (Some(Some(id_quotation)), _) => match self.quotations.search_by_id(*id_quotation)? {
+                Some(quotation) => Some(quotation.discount_percent),
+                None => {
+                    errors.push(Message::new("invoice.error.quotation").arg("id", id_quotation));
+                    None
+                }
+            },
+            (_, Some(client)) => Some(client.discount_percent),
+            _ => None,
+        };
+
+        match discount_percent {
+            Some(discount_percent) if errors.is_empty() => Ok((lines, discount_percent)),
+            _ => Err(Error::InvalidField {
+                entity: "Invoice",
+                errors,
+            }),
+        }
+    }
+
+    // Borrador con las muestras indicadas, el descuento del cliente y el IVA
+    // configurado; el folio se asigna hasta emitirla
+    pub fn draft(
+        &mut self,
+        id_client: u32,
+        id_samples: &[u32],
+        drafted_on: NaiveDate,
+    ) -> Result<u32> {
+        self.session.require(Permission::ManageInvoices)?;
+        let mut id_samples = id_samples.to_vec();
+        id_samples.sort_unstable();
+        id_samples.dedup();
+        let (lines, discount_percent) = self.valid_draft(id_client, &id_samples)?;
+
+        let mut invoice = Invoice {
+            invoice_active: true,
+            id_client,
+            drafted_on,
+            discount_percent,
+            tax_percent: self.tax_percent,
+            ..Invoice::default()
+        };
+        invoice::apply_totals(&mut invoice, &lines);
+
+        // Se revisa dentro de la transaccion; el trigger de InvoiceLine
+        // (migracion 0017) lo respalda si otro proceso se adelanta
+        self.invoices.in_transaction(|conn| {
+            let mut errors = Vec::new();
+            for id_sample in &id_samples {
+                if InvoicedSampleRepo::invoice_of_in(conn, *id_sample)?.is_some() {
+                    let number = SampleRepo::search_by_id_in(conn, *id_sample)?
+                        .map(|sample| sample.control_number)
+                        .unwrap_or_default();
+                    errors.push(Message::new("invoice.error.invoiced").arg("number", number));
+                }
+            }
+            if !errors.is_empty() {
+                return Err(Error::InvalidField {
+                    entity: "Invoice",
+                    errors,
+                });
+            }
+
+            let id_invoice = InvoiceRepo::insert_in(conn, &invoice)?;
+            for line in &lines {
+                let line = InvoiceLine {
+                    id_invoice,
+                    ..line.clone()
+                };
+                InvoiceLineRepo::insert_in(conn, &line)?;
+            }
+            Ok(id_invoice)
+        })
+    }
+
+    pub fn invoice(&self, id_invoice: u32) -> Result<Invoice> {
+        match self.invoices.search_by_id(id_invoice)? {
+            Some(invoice) if invoice.invoice_active => Ok(invoice),
+            _ => Err(Error::NotFound {
+                entity: "Invoice",
+                id: Some(id_invoice),
+            }),
+        }
+    }
+
+    pub fn lines_of(&self, id_invoice: u32) -> Result<Vec<InvoiceLine>> {
+        let criteria = InvoiceLineSearchCriteria {
+            id_invoice: Some(id_invoice),
+            ..InvoiceLineSearchCriteria::default()
+        };
+        self.lines.find_all(&criteria)
+    }
+
+    // Facturas activas de un cliente o de todos
+    pub fn invoices(&self, id_client: Option<u32>) -> Result<Vec<Invoice>> {
+        let criteria = InvoiceSearchCriteria {
+            invoice_active: Some(true),
+            id_client,
+            ..InvoiceSearchCriteria::default()
+        };
+        self.invoices.find_all(&criteria)
+    }
+
+    fn document_of(&self, invoice: Invoice) -> Result<InvoiceDocument> {
+        let client = self.client(invoice.id_client)?;
+        let mut lines = Vec::new();
+        for line in self.lines_of(invoice.id_invoice.unwrap_or_default())? {
+            let control_number = self
+                .samples
+                .search_by_id(line.id_sample)?
+                .map(|sample| sample.control_number)
+                .unwrap_or_default();
+            lines.push(DocumentLine {
+                control_number,
+                line,
+            });
+        }
+        Ok(InvoiceDocument {
+            invoice,
+            client,
+            lines,
+        })
+    }
+
+    pub fn document(&self, id_invoice: u32) -> Result<InvoiceDocument> {
+        self.document_of(self.invoice(id_invoice)?)
+    }
+
+    fn transition(
+        &mut self,
+        id_invoice: u32,
+        to: InvoiceStatus,
+        date: NaiveDate,
+    ) -> Result<Invoice> {
+        self.session.require(Permission::ManageInvoices)?;
+        let mut invoice = self.invoice(id_invoice)?;
+        let from = invoice.invoice_status;
+        if !from.can_change_to(to) {
+            return Err(Self::invoice_error(
+                Message::new("invoice.error.transition")
+                    .arg("id", id_invoice)
+                    .arg("from", invoice::status_label(from))
+                    .arg("to", invoice::status_label(to)),
+            ));
+        }
+        if date < invoice.drafted_on {
+            return Err(Self::invoice_error(
+                Message::new("invoice.error.date")
+                    .arg("date", date)
+                    .arg("drafted_on", invoice.drafted_on),
+            ));
+        }
+
+        invoice.invoice_status = to;
+        match to {
+            InvoiceStatus::Issued => invoice.issued_on = Some(date),
+            InvoiceStatus::Paid => invoice.paid_on = Some(date),
+            InvoiceStatus::Cancelled => invoice.cancelled_on = Some(date),
+            InvoiceStatus::Draft => {}
+        }
+        self.invoices.in_transaction(|conn| {
+            if to == InvoiceStatus::Issued {
+                let seq = InvoiceNumberRepo::next_value_in(conn, date.year())?;
+                invoice.invoice_number = Some(invoice::invoice_number(date.year(), seq));
+            }
+            InvoiceRepo::modify_in(conn, &invoice)
+        })?;
+        self.invoice(id_invoice)
+    }
+
+    // Asigna el siguiente folio del año de emision
+    pub fn issue(&mut self, id_invoice: u32, issued_on: NaiveDate) -> Result<Invoice> {
+        self.transition(id_invoice, InvoiceStatus::Issued, issued_on)
+    }
+
+    pub fn pay(&mut self, id_invoice: u32, paid_on: NaiveDate) -> Result<Invoice> {
+        self.transition(id_invoice, InvoiceStatus::Paid, paid_on)
+    }
+
+    // Las muestras de una factura cancelada se pueden volver a facturar
+    pub fn cancel(&mut self, id_invoice: u32, cancelled_on: NaiveDate) -> Result<Invoice> {
+        self.transition(id_invoice, InvoiceStatus::Cancelled, cancelled_on)
+    }
+
+    // Exporta las facturas (de un cliente o todas) segun la extension del
+    // archivo: CSV con un renglon por factura o JSON con sus partidas; los
+    // importes en JSON van en centavos
+    pub fn export(&self, path: &str, id_client: Option<u32>) -> Result<usize> {
+        let mut documents = Vec::new();
+        for invoice in self.invoices(id_client)? {
+            documents.push(self.document_of(invoice)?);
+        }
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let text = match extension.as_deref() {
+            Some("csv") => invoice::to_csv(&documents),
+            Some("json") => serde_json::to_string_pretty(&documents)?,
+            _ => {
+                return Err(Self::invoice_error(
+                    Message::new("invoice.error.format").arg("path", path),
+                ))
+            }
+        };
+        std::fs::write(path, text)?;
+        Ok(documents.len())
+    }
+}
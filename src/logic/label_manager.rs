@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::data::model::role::Permission;
+use crate::data::model::sample::Sample;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use crate::data::repo::site_repo::SiteRepo;
+use crate::data_management::Finder;
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::labels::{self, Label, LabelFormat};
+use crate::logic::session::Session;
+
+// Etiquetas para los frascos de las muestras, una o un lote
+pub struct LabelManager {
+    samples: SampleRepo,
+    sites: SiteRepo,
+    session: Session,
+}
+
+impl LabelManager {
+    pub fn new(connector: Connector, page_size: u64) -> Self {
+        Self {
+            samples: SampleRepo::new(connector.clone(), page_size),
+            sites: SiteRepo::new(connector, page_size),
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    fn label_of(&self, sample: &Sample) -> Result<Label> {
+        let site_key = match sample.id_site {
+            Some(id_site) => self.sites.search_by_id(id_site)?.map(|site| site.site_key),
+            None => None,
+        };
+        Ok(Label {
+            control_number: sample.control_number.clone(),
+            site_key,
+            sampling_date: sample.sampling_date,
+        })
+    }
+
+    // Etiquetas en el orden pedido; reporta juntos todos los numeros que no existen
+    pub fn labels_for(&self, control_numbers: &[String]) -> Result<Vec<Label>> {
+        let mut labels = Vec::new();
+        let mut errors = Vec::new();
+        for control_number in control_numbers {
+            let control_number = control_number.trim();
+            let criteria = SampleSearchCriteria {
+                control_number: Some(control_number.to_string()),
+                ..SampleSearchCriteria::default()
+            };
+            let sample = self
+                .samples
+                .find_all(&criteria)?
+                .into_iter()
+                .find(|sample| sample.control_number == control_number);
+            match sample {
+                Some(sample) => labels.push(self.label_of(&sample)?),
+                None => errors
+                    .push(Message::new("label.error.unknown_sample").arg("number", control_number)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Label",
+                errors,
+            });
+        }
+        Ok(labels)
+    }
+
+    // Lote de las muestras activas recibidas en un dia, por numero de control
+    pub fn labels_received_on(&self, reception: NaiveDate) -> Result<Vec<Label>> {
+        let criteria = SampleSearchCriteria {
+            sample_active: Some(true),
+            reception: Some(reception),
+            ..SampleSearchCriteria::default()
+        };
+        let mut samples = self.samples.find_all(&criteria)?;
+        samples.sort_by(|a, b| a.control_number.cmp(&b.control_number));
+        samples.iter().map(|sample| self.label_of(sample)).collect()
+    }
+
+    pub fn export(
+        &self,
+        labels: &[Label],
+        format: LabelFormat,
+        path: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        // Las etiquetas se imprimen al recibir las muestras
+        self.session.require(Permission::RegisterSamples)?;
+        labels::write(labels, format, path)
+    }
+}
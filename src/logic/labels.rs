@@ -0,0 +1,470 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::barcode::{self, CODE128_QUIET_ZONE, QR_QUIET_ZONE};
+use crate::logic::bitmap_font::{self, ADVANCE, GLYPH_HEIGHT};
+
+// Hoja A4 de 3 x 8 etiquetas de 70 x 37 mm; todas las medidas en mm
+pub const SHEET_WIDTH: f64 = 210.0;
+pub const SHEET_HEIGHT: f64 = 297.0;
+pub const LABEL_WIDTH: f64 = 70.0;
+pub const LABEL_HEIGHT: f64 = 37.0;
+pub const COLUMNS: usize = 3;
+pub const ROWS: usize = 8;
+const TOP_MARGIN: f64 = 0.5;
+
+const QR_SIZE: f64 = 22.0;
+const BARCODE_HEIGHT: f64 = 10.0;
+const PADDING: f64 = 2.0;
+// Alto de las mayusculas respecto al tamaño de la letra
+const CAP_HEIGHT: f64 = 0.7;
+pub const PNG_DPI: f64 = 300.0;
+
+const MM_PER_INCH: f64 = 25.4;
+const POINTS_PER_INCH: f64 = 72.0;
+
+// Datos que van en la etiqueta de cada muestra
+#[derive(Clone, PartialEq, Debug)]
+pub struct Label {
+    pub control_number: String,
+    pub site_key: Option<String>,
+    pub sampling_date: NaiveDate,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LabelFormat {
+    #[default]
+    Svg,
+    Pdf,
+    Png,
+}
+
+impl LabelFormat {
+    pub const ALL: [Self; 3] = [Self::Svg, Self::Pdf, Self::Png];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+        }
+    }
+
+    // Formato segun la extension del archivo
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl std::str::FromStr for LabelFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("unknown label format {}", s))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    // `y` es la linea base y `size` el tamaño de la letra
+    Text {
+        x: f64,
+        y: f64,
+        size: f64,
+        bold: bool,
+        text: String,
+    },
+}
+
+// Una hoja con lo que se dibuja en negro, con el origen arriba a la izquierda
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Page {
+    pub shapes: Vec<Shape>,
+}
+
+// Rectangulos de los tramos seguidos de modulos negros de un renglon
+fn runs(modules: &[bool], x: f64, y: f64, module: f64, height: f64) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+    let mut start = None;
+    for (index, dark) in modules.iter().chain([&false]).enumerate() {
+        match (dark, start) {
+            (true, None) => start = Some(index),
+            (false, Some(first)) => {
+                shapes.push(Shape::Rect {
+                    x: x + first as f64 * module,
+                    y,
+                    width: (index - first) as f64 * module,
+                    height,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    shapes
+}
+
+fn label_shapes(label: &Label, x: f64, y: f64) -> Result<Vec<Shape>> {
+    let mut shapes = Vec::new();
+
+    let qr = barcode::qr_modules(&label.control_number)?;
+    let module = QR_SIZE / (qr.len() + 2 * QR_QUIET_ZONE) as f64;
+    let qr_x = x + PADDING + QR_QUIET_ZONE as f64 * module;
+    for (row, modules) in qr.iter().enumerate() {
+        let qr_y = y + PADDING + (QR_QUIET_ZONE + row) as f64 * module;
+        shapes.extend(runs(modules, qr_x, qr_y, module, module));
+    }
+
+    let text_x = x + PADDING + QR_SIZE + 1.0;
+    let lines = [
+        (9.0, 4.5, true, label.control_number.clone()),
+        (15.0, 3.5, false, label.site_key.clone().unwrap_or_default()),
+        (20.0, 3.5, false, label.sampling_date.to_string()),
+    ];
+    for (baseline, size, bold, text) in lines {
+        if !text.is_empty() {
+            shapes.push(Shape::Text {
+                x: text_x,
+                y: y + baseline,
+                size,
+                bold,
+                text,
+            });
+        }
+    }
+
+    let bars = barcode::code128_modules(&label.control_number)?;
+    let width = LABEL_WIDTH - 2.0 * PADDING;
+    let module = width / (bars.len() + 2 * CODE128_QUIET_ZONE) as f64;
+    let bars_x = x + PADDING + CODE128_QUIET_ZONE as f64 * module;
+    let bars_y = y + LABEL_HEIGHT - PADDING - BARCODE_HEIGHT;
+    shapes.extend(runs(&bars, bars_x, bars_y, module, BARCODE_HEIGHT));
+    Ok(shapes)
+}
+
+// Acomoda las etiquetas en hojas, de izquierda a derecha y de arriba abajo
+pub fn layout(labels: &[Label]) -> Result<Vec<Page>> {
+    if labels.is_empty() {
+        return Err(Error::InvalidField {
+            entity: "Label",
+            errors: vec![Message::new("label.error.empty")],
+        });
+    }
+
+    let mut pages = Vec::new();
+    for sheet in labels.chunks(COLUMNS * ROWS) {
+        let mut page = Page::default();
+        for (index, label) in sheet.iter().enumerate() {
+            let x = (index % COLUMNS) as f64 * LABEL_WIDTH;
+            let y = TOP_MARGIN + (index / COLUMNS) as f64 * LABEL_HEIGHT;
+            page.shapes.extend(label_shapes(label, x, y)?);
+        }
+        pages.push(page);
+    }
+    Ok(pages)
+}
+
+// Numero con a lo mas 3 decimales y sin ceros de sobra
+fn number(value: f64) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_svg(page: &Page) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+        w = number(SHEET_WIDTH),
+        h = number(SHEET_HEIGHT),
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+    svg.push_str("<g fill=\"black\" font-family=\"Helvetica, Arial, sans-serif\">\n");
+    for shape in &page.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n",
+                number(*x),
+                number(*y),
+                number(*width),
+                number(*height)
+            )),
+            Shape::Text {
+                x,
+                y,
+                size,
+                bold,
+                text,
+            } => svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\"{}>{}</text>\n",
+                number(*x),
+                number(*y),
+                number(*size),
+                if *bold { " font-weight=\"bold\"" } else { "" },
+                escape_xml(text)
+            )),
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn points(mm: f64) -> f64 {
+    mm * POINTS_PER_INCH / MM_PER_INCH
+}
+
+// Texto de PDF en WinAnsi (Latin-1 para lo que usan las etiquetas)
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for c in text.chars() {
+        let byte = u8::try_from(c as u32).unwrap_or(b'?');
+        if matches!(byte, b'(' | b')' | b'\\') {
+            bytes.push(b'\\');
+        }
+        bytes.push(byte);
+    }
+    bytes.push(b')');
+    bytes
+}
+
+fn pdf_content(page: &Page) -> Vec<u8> {
+    let height = points(SHEET_HEIGHT);
+    let mut content = b"0 g\n".to_vec();
+    for shape in &page.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height: h,
+            } => content.extend(
+                format!(
+                    "{} {} {} {} re f\n",
+                    number(points(*x)),
+                    number(height - points(y + h)),
+                    number(points(*width)),
+                    number(points(*h))
+                )
+                .bytes(),
+            ),
+            Shape::Text {
+                x,
+                y,
+                size,
+                bold,
+                text,
+            } => {
+                content.extend(
+                    format!(
+                        "BT /{} {} Tf {} {} Td ",
+                        if *bold { "F2" } else { "F1" },
+                        number(points(*size)),
+                        number(points(*x)),
+                        number(height - points(*y))
+                    )
+                    .bytes(),
+                );
+                content.extend(pdf_string(text));
+                content.extend(b" Tj ET\n");
+            }
+        }
+    }
+    content
+}
+
+// PDF de una pagina por hoja con las fuentes estandar Helvetica
+pub fn render_pdf(pages: &[Page]) -> Vec<u8> {
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let kids: Vec<String> = (0..pages.len())
+        .map(|index| format!("{} 0 R", 5 + 2 * index))
+        .collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    );
+    for font in ["Helvetica", "Helvetica-Bold"] {
+        objects.push(
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font
+            )
+            .into_bytes(),
+        );
+    }
+    for (index, page) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                number(points(SHEET_WIDTH)),
+                number(points(SHEET_HEIGHT)),
+                6 + 2 * index
+            )
+            .into_bytes(),
+        );
+        let content = pdf_content(page);
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .bytes(),
+    );
+    pdf
+}
+
+// Imagen en escala de grises; el texto se dibuja con la letra de 5x7
+pub fn render_png(page: &Page, dpi: f64) -> Result<Vec<u8>> {
+    let scale = dpi / MM_PER_INCH;
+    let width = (SHEET_WIDTH * scale).round() as usize;
+    let height = (SHEET_HEIGHT * scale).round() as usize;
+    let mut pixels = vec![255u8; width * height];
+
+    let mut fill = |x: f64, y: f64, w: f64, h: f64| {
+        let left = ((x * scale).round() as usize).min(width);
+        let right = (((x + w) * scale).round() as usize).min(width);
+        let top = ((y * scale).round() as usize).min(height);
+        let bottom = (((y + h) * scale).round() as usize).min(height);
+        for row in top..bottom {
+            pixels[row * width + left..row * width + right].fill(0);
+        }
+    };
+
+    for shape in &page.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => fill(*x, *y, *width, *height),
+            Shape::Text {
+                x,
+                y,
+                size,
+                bold,
+                text,
+            } => {
+                let dot = size * CAP_HEIGHT / GLYPH_HEIGHT as f64;
+                let top = y - size * CAP_HEIGHT;
+                // La negrita engruesa cada punto hacia la derecha
+                let dot_width = if *bold { dot * 1.4 } else { dot };
+                for (index, c) in text.chars().enumerate() {
+                    let left = x + (index * ADVANCE) as f64 * dot;
+                    for (column, row) in bitmap_font::dots(c) {
+                        fill(
+                            left + column as f64 * dot,
+                            top + row as f64 * dot,
+                            dot_width,
+                            dot,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let dots_per_meter = (dpi / MM_PER_INCH * 1000.0).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: dots_per_meter,
+        yppu: dots_per_meter,
+        unit: png::Unit::Meter,
+    }));
+    let mut writer = encoder.write_header().map_err(std::io::Error::from)?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(std::io::Error::from)?;
+    writer.finish().map_err(std::io::Error::from)?;
+    Ok(png)
+}
+
+// Archivo de cada hoja: la primera usa la ruta pedida y las demas le agregan
+// el numero de hoja ("etiquetas-2.svg"); el PDF lleva todas las hojas
+fn sheet_path(path: &Path, sheet: usize) -> PathBuf {
+    if sheet == 1 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, sheet, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, sheet),
+    };
+    path.with_file_name(name)
+}
+
+// Escribe las hojas de etiquetas y devuelve los archivos creados
+pub fn write(labels: &[Label], format: LabelFormat, path: &Path) -> Result<Vec<PathBuf>> {
+    let pages = layout(labels)?;
+    if format == LabelFormat::Pdf {
+        std::fs::write(path, render_pdf(&pages))?;
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut paths = Vec::new();
+    for (sheet, page) in (1..).zip(&pages) {
+        let sheet_path = sheet_path(path, sheet);
+        match format {
+            LabelFormat::Png => std::fs::write(&sheet_path, render_png(page, PNG_DPI)?)?,
+            _ => std::fs::write(&sheet_path, render_svg(page))?,
+        }
+        paths.push(sheet_path);
+    }
+    Ok(paths)
+}
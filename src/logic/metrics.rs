@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::data::model::sample_status::SampleStatus;
+use crate::data::model::turnaround::ResultTurnaround;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::metrics_repo::MetricsRepo;
+use crate::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use crate::error::Result;
+
+// Dias entre la recepcion y el resultado de un grupo (analisis, signatario o
+// cliente). Sin id son los resultados que no tienen ese dato capturado.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TurnaroundStats {
+    pub id: Option<u32>,
+    pub name: Option<String>,
+    pub results: usize,
+    pub mean_days: f64,
+    pub min_days: i64,
+    pub max_days: i64,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BacklogCount {
+    pub status: SampleStatus,
+    pub samples: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OverdueSample {
+    pub id_sample: u32,
+    pub control_number: String,
+    pub reception: NaiveDate,
+    pub status: SampleStatus,
+    pub days_open: i64,
+    pub days_late: i64,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub as_of: NaiveDate,
+    pub sla_days: u32,
+    pub per_analysis: Vec<TurnaroundStats>,
+    pub per_signatary: Vec<TurnaroundStats>,
+    pub per_client: Vec<TurnaroundStats>,
+    pub backlog: Vec<BacklogCount>,
+    pub overdue: Vec<OverdueSample>,
+}
+
+// Una muestra sigue abierta mientras no se reporte
+pub fn is_open(status: SampleStatus) -> bool {
+    !matches!(status, SampleStatus::Reported | SampleStatus::Archived)
+}
+
+pub fn turnaround_days(result: &ResultTurnaround) -> i64 {
+    (result.analysis_date - result.reception).num_days()
+}
+
+// Agrupa los resultados con la llave que devuelve `group_of`; los grupos
+// quedan ordenados por nombre y los resultados sin dato al final
+pub fn turnaround_stats<F>(results: &[ResultTurnaround], group_of: F) -> Vec<TurnaroundStats>
+where
+    F: Fn(&ResultTurnaround) -> (Option<u32>, Option<String>),
+{
+    let mut groups: BTreeMap<(bool, Option<String>, Option<u32>), Vec<i64>> = BTreeMap::new();
+    for result in results {
+        let (id, name) = group_of(result);
+        groups
+            .entry((id.is_none(), name, id))
+            .or_default()
+            .push(turnaround_days(result));
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, name, id), days)| TurnaroundStats {
+            id,
+            name,
+            results: days.len(),
+            mean_days: days.iter().sum::<i64>() as f64 / days.len() as f64,
+            min_days: days.iter().copied().min().unwrap_or_default(),
+            max_days: days.iter().copied().max().unwrap_or_default(),
+        })
+        .collect()
+}
+
+pub struct MetricsManager {
+    metrics: MetricsRepo,
+    samples: SampleRepo,
+    sla_days: u32,
+}
+
+impl MetricsManager {
+    pub fn new(connector: Connector, page_size: u64, sla_days: u32) -> Self {
+        Self {
+            metrics: MetricsRepo::new(connector.clone()),
+            samples: SampleRepo::new(connector, page_size),
+            sla_days,
+        }
+    }
+
+    // Metricas al dia `as_of`; las muestras abiertas con mas de `sla_days`
+    // dias desde la recepcion estan atrasadas
+    pub fn dashboard(&self, as_of: NaiveDate) -> Result<Dashboard> {
+        let results = self.metrics.result_turnarounds()?;
+
+        let criteria = SampleSearchCriteria {
+            sample_active: Some(true),
+            ..SampleSearchCriteria::default()
+        };
+        let open: Vec<_> = self
+            .samples
+            .find_all(&criteria)?
+            .into_iter()
+            .filter(|sample| is_open(sample.sample_status))
+            .collect();
+
+        let backlog = SampleStatus::ALL
+            .into_iter()
+            .filter(|status| is_open(*status))
+            .map(|status| BacklogCount {
+                status,
+                samples: open
+                    .iter()
+                    .filter(|sample| sample.sample_status == status)
+                    .count(),
+            })
+            .collect();
+
+        let mut overdue: Vec<OverdueSample> = open
+            .iter()
+            .filter_map(|sample| {
+                let days_open = (as_of - sample.reception).num_days();
+                let days_late = days_open - i64::from(self.sla_days);
+                (days_late > 0).then(|| OverdueSample {
+                    id_sample: sample.id_sample.unwrap_or_default(),
+                    control_number: sample.control_number.clone(),
+                    reception: sample.reception,
+                    status: sample.sample_status,
+                    days_open,
+                    days_late,
+                })
+            })
+            .collect();
+        overdue.sort_by(|a, b| {
+            b.days_late
+                .cmp(&a.days_late)
+                .then_with(|| a.control_number.cmp(&b.control_number))
+        });
+
+        Ok(Dashboard {
+            as_of,
+            sla_days: self.sla_days,
+            per_analysis: turnaround_stats(&results, |r| (r.id_analysis, r.analysis_name.clone())),
+            per_signatary: turnaround_stats(&results, |r| {
+                (r.id_signatary, r.signatary_name.clone())
+            }),
+            per_client: turnaround_stats(&results, |r| (r.id_client, r.client_name.clone())),
+            backlog,
+            overdue,
+        })
+    }
+
+    pub fn export_json(&self, as_of: NaiveDate, path: &str) -> Result<Dashboard> {
+        let dashboard = self.dashboard(as_of)?;
+        std::fs::write(path, serde_json::to_string_pretty(&dashboard)?)?;
+        Ok(dashboard)
+    }
+}
@@ -1 +1,26 @@
+pub mod barcode;
+pub mod bitmap_font;
 pub mod client_manager;
+pub mod compliance;
+pub mod contact;
+pub mod control_number;
+pub mod duplicates;
+pub mod invoice;
+pub mod invoice_manager;
+pub mod label_manager;
+pub mod labels;
+pub mod metrics;
+pub mod password;
+pub mod quotation;
+pub mod quotation_manager;
+pub mod result_manager;
+pub mod sample_manager;
+pub mod schedule_manager;
+pub mod search_manager;
+pub mod session;
+pub mod signature;
+pub mod site_manager;
+pub mod totals;
+pub mod trend;
+pub mod units;
+pub mod user_manager;
@@ -0,0 +1,28 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+// Las contraseñas se guardan como hash Argon2 con sal aleatoria, en formato
+// PHC ("$argon2id$v=19$...") que incluye los parametros y la sal
+pub fn hash(passwd: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passwd.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| Error::InvalidField {
+            entity: "Signatary",
+            errors: vec![Message::new("user.error.passwd_hash").arg("reason", e)],
+        })
+}
+
+// Un hash guardado invalido nunca coincide
+pub fn verify(passwd: &str, stored: &str) -> bool {
+    PasswordHash::new(stored).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(passwd.as_bytes(), &hash)
+            .is_ok()
+    })
+}
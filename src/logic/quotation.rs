@@ -0,0 +1,145 @@
+use crate::data::model::client::Client;
+use crate::data::model::money::Money;
+use crate::data::model::quotation::Quotation;
+use crate::data::model::quotation_item::QuotationItem;
+use crate::data::model::site::Site;
+use crate::i18n::{t, tf};
+use crate::logic::totals::{Totals, DESCRIPTION_WIDTH, NUMBER_WIDTH};
+
+// Dias que se respeta el precio de una cotizacion
+pub const VALIDITY_DAYS: u64 = 30;
+
+// Lo que se cotiza en una partida
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuotedItem {
+    Package(u32),
+    Analysis(u32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct QuoteLine {
+    pub item: QuotedItem,
+    pub samples: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DocumentLine {
+    pub item: QuotationItem,
+    pub analyses: Vec<String>,
+    pub amount: Money,
+}
+
+// Cotizacion lista para imprimirse
+#[derive(Clone, PartialEq, Debug)]
+pub struct QuotationDocument {
+    pub quotation: Quotation,
+    pub client: Client,
+    pub site: Option<Site>,
+    pub lines: Vec<DocumentLine>,
+    pub totals: Totals,
+}
+
+const SAMPLES_WIDTH: usize = 6;
+
+impl QuotationDocument {
+    pub fn new(
+        quotation: Quotation,
+        client: Client,
+        site: Option<Site>,
+        items: Vec<(QuotationItem, Vec<String>)>,
+    ) -> Self {
+        let lines: Vec<DocumentLine> = items
+            .into_iter()
+            .map(|(item, analyses)| DocumentLine {
+                amount: item.unit_price * item.samples,
+                item,
+                analyses,
+            })
+            .collect();
+        let totals = Totals::new(
+            lines.iter().map(|line| line.amount).sum(),
+            quotation.discount_percent,
+            quotation.tax_percent,
+        );
+        Self {
+            totals,
+            quotation,
+            client,
+            site,
+            lines,
+        }
+    }
+
+    // Texto de la cotizacion para imprimirse o enviarse
+    pub fn render(&self) -> String {
+        let quotation = &self.quotation;
+        let mut rows = vec![
+            tf(
+                "quotation.document.title",
+                &[("id", &quotation.id_quotation.unwrap_or_default())],
+            ),
+            tf(
+                "quotation.document.issued",
+                &[
+                    ("issued_on", &quotation.issued_on),
+                    ("valid_until", &quotation.valid_until),
+                ],
+            ),
+            tf(
+                "quotation.document.client",
+                &[("name", &self.client.client_name)],
+            ),
+        ];
+        if let Some(rfc) = &self.client.client_rfc {
+            rows.push(tf("quotation.document.rfc", &[("rfc", rfc)]));
+        }
+        if let Some(address) = &self.client.billing_address {
+            rows.push(tf("quotation.document.address", &[("address", address)]));
+        }
+        if let Some(site) = &self.site {
+            rows.push(tf(
+                "quotation.document.site",
+                &[("key", &site.site_key), ("name", &site.site_name)],
+            ));
+        }
+        if !quotation.project.is_empty() {
+            rows.push(tf(
+                "quotation.document.project",
+                &[("project", &quotation.project)],
+            ));
+        }
+
+        rows.push(String::new());
+        rows.push(format!(
+            "{:<DESCRIPTION_WIDTH$} {:>SAMPLES_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$}",
+            t("quotation.document.description"),
+            t("quotation.document.samples"),
+            t("quotation.document.unit_price"),
+            t("quotation.document.amount"),
+        ));
+        for line in &self.lines {
+            rows.push(format!(
+                "{:<DESCRIPTION_WIDTH$} {:>SAMPLES_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$}",
+                line.item.description, line.item.samples, line.item.unit_price, line.amount,
+            ));
+            if line.item.id_package.is_some() {
+                rows.push(format!("  {}", line.analyses.join(", ")));
+            }
+        }
+
+        rows.push(String::new());
+        rows.extend(
+            self.totals
+                .rows(DESCRIPTION_WIDTH + SAMPLES_WIDTH + NUMBER_WIDTH + 2),
+        );
+
+        if let Some(converted_on) = quotation.converted_on {
+            rows.push(String::new());
+            rows.push(tf(
+                "quotation.document.converted",
+                &[("date", &converted_on)],
+            ));
+        }
+        rows.join("\n") + "\n"
+    }
+}
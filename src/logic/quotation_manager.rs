@@ -0,0 +1,487 @@
+use chrono::NaiveDate;
+
+use crate::data::model::analysis::Analysis;
+use crate::data::model::analysis_package::AnalysisPackage;
+use crate::data::model::client::Client;
+use crate::data::model::money::Money;
+use crate::data::model::package_detail::PackageDetail;
+use crate::data::model::quotation::Quotation;
+use crate::data::model::quotation_item::QuotationItem;
+use crate::data::model::role::Permission;
+use crate::data::model::sample::Sample;
+use crate::data::repo::analysis_package_repo::{AnalysisPackageRepo, PackageSearchCriteria};
+use crate::data::repo::analysis_repo::{AnalysisRepo, AnalysisSearchCriteria};
+use crate::data::repo::client_repo::ClientRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::package_detail_repo::{PackageDetailRepo, PackageDetailSearchCriteria};
+use crate::data::repo::quotation_item_repo::{QuotationItemRepo, QuotationItemSearchCriteria};
+use crate::data::repo::quotation_repo::QuotationRepo;
+use crate::data::repo::sample_repo::SampleSearchCriteria;
+use crate::data::repo::site_repo::SiteRepo;
+use crate::data_management::{Finder, Manager};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::control_number::ControlNumberPattern;
+use crate::logic::duplicates;
+use crate::logic::quotation::{QuotationDocument, QuoteLine, QuotedItem};
+use crate::logic::sample_manager::SampleManager;
+use crate::logic::session::Session;
+
+// Precios de analisis y paquetes, descuentos por cliente y cotizaciones que
+// se convierten en muestras con sus analisis esperados
+pub struct QuotationManager {
+    quotations: QuotationRepo,
+    items: QuotationItemRepo,
+    packages: AnalysisPackageRepo,
+    package_details: PackageDetailRepo,
+    analyses: AnalysisRepo,
+    clients: ClientRepo,
+    sites: SiteRepo,
+    samples: SampleManager<SampleSearchCriteria>,
+    tax_percent: u32,
+    session: Session,
+}
+
+impl QuotationManager {
+    pub fn new(
+        connector: Connector,
+        page_size: u64,
+        pattern: ControlNumberPattern,
+        tax_percent: u32,
+    ) -> Self {
+        Self {
+            quotations: QuotationRepo::new(connector.clone(), page_size),
+            items: QuotationItemRepo::new(connector.clone(), page_size),
+            packages: AnalysisPackageRepo::new(connector.clone(), page_size),
+            package_details: PackageDetailRepo::new(connector.clone(), page_size),
+            analyses: AnalysisRepo::new(connector.clone(), page_size),
+            clients: ClientRepo::new(connector.clone(), page_size),
+            sites: SiteRepo::new(connector.clone(), page_size),
+            samples: SampleManager::new(connector, page_size, pattern),
+            tax_percent,
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.samples = self.samples.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn quotation_error(message: Message) -> Error {
+        Error::InvalidField {
+            entity: "Quotation",
+            errors: vec![message],
+        }
+    }
+
+    // Analisis activos con su precio de lista
+    pub fn price_catalog(&self) -> Result<Vec<Analysis>> {
+        let criteria = AnalysisSearchCriteria {
+            analysis_active: Some(true),
+            ..AnalysisSearchCriteria::default()
+        };
+        self.analyses.find_all(&criteria)
+    }
+
+    pub fn set_price(&mut self, id_analysis: u32, price: Option<Money>) -> Result<Analysis> {
+        self.session.require(Permission::ManageQuotations)?;
+        let mut analysis = self.analysis(id_analysis)?;
+        analysis.price = price;
+        self.analyses
+            .in_transaction(|conn| AnalysisRepo::modify_in(conn, &analysis))?;
+        self.analysis(id_analysis)
+    }
+
+    fn analysis(&self, id_analysis: u32) -> Result<Analysis> {
+        self.analyses
+            .search_by_id(id_analysis)?
+            .ok_or(Error::NotFound {
+                entity: "Analysis",
+                id: Some(id_analysis),
+            })
+    }
+
+    fn valid_package(&self, package: &AnalysisPackage, analyses: &[u32]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if package.id_package.is_some() {
+            errors.push(Message::new("quotation.error.package_existing"));
+        }
+
+        if !package.package_active {
+            errors.push(Message::new("quotation.error.package_inactive"));
+        }
+
+        if package.package_name.trim().is_empty() {
+            errors.push(Message::new("quotation.error.package_name"));
+        } else if self.find_package(&package.package_name)?.is_some() {
+            errors.push(
+                Message::new("quotation.error.package_duplicate")
+                    .arg("name", package.package_name.trim()),
+            );
+        }
+
+        if analyses.is_empty() {
+            errors.push(Message::new("quotation.error.package_analyses"));
+        }
+        for id_analysis in analyses {
+            if self.analyses.search_by_id(*id_analysis)?.is_none() {
+                errors.push(Message::new("quotation.error.analysis").arg("id", id_analysis));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "AnalysisPackage",
+                errors,
+            });
+        }
+        Ok(())
+    }
+
+    // Alta del paquete con sus analisis; sin precio propio vale la suma de
+    // los precios de sus analisis
+    pub fn add_package(&mut self, package: &AnalysisPackage, analyses: &[u32]) -> Result<u32> {
+        self.session.require(Permission::ManageQuotations)?;
+        let mut analyses = analyses.to_vec();
+        analyses.sort_unstable();
+        analyses.dedup();
+        self.valid_package(package, &analyses)?;
+
+        self.packages.in_transaction(|conn| {
+            let id_package = AnalysisPackageRepo::insert_in(conn, package)?;
+            for id_analysis in &analyses {
+                PackageDetailRepo::insert_in(
+                    conn,
+                    &PackageDetail {
+                        id_package_detail: None,
+                        id_package,
+                        id_analysis: *id_analysis,
+                    },
+                )?;
+            }
+            Ok(id_package)
+        })
+    }
+
+    pub fn packages(&self) -> Result<Vec<AnalysisPackage>> {
+        let criteria = PackageSearchCriteria {
+            package_active: Some(true),
+            ..PackageSearchCriteria::default()
+        };
+        self.packages.find_all(&criteria)
+    }
+
+    // Paquete activo con ese nombre, sin distinguir mayusculas ni acentos
+    pub fn find_package(&self, package_name: &str) -> Result<Option<AnalysisPackage>> {
+        let package_name = duplicates::normalize(package_name);
+        Ok(self
+            .packages()?
+            .into_iter()
+            .find(|package| duplicates::normalize(&package.package_name) == package_name))
+    }
+
+    pub fn package_analyses(&self, id_package: u32) -> Result<Vec<Analysis>> {
+        let criteria = PackageDetailSearchCriteria {
+            id_package: Some(id_package),
+            ..PackageDetailSearchCriteria::default()
+        };
+        let mut analyses = Vec::new();
+        for detail in self.package_details.find_all(&criteria)? {
+            analyses.extend(self.analyses.search_by_id(detail.id_analysis)?);
+        }
+        Ok(analyses)
+    }
+
+    fn unpriced(analysis: &Analysis) -> Error {
+        Self::quotation_error(
+            Message::new("quotation.error.no_price").arg("name", &analysis.analysis_name),
+        )
+    }
+
+    pub fn package_price(&self, package: &AnalysisPackage) -> Result<Money> {
+        if let Some(price) = package.package_price {
+            return Ok(price);
+        }
+        self.package_analyses(package.id_package.unwrap_or_default())?
+            .iter()
+            .map(|analysis| analysis.price.ok_or_else(|| Self::unpriced(analysis)))
+            .sum()
+    }
+
+    pub fn set_discount(&mut self, id_client: u32, discount_percent: f64) -> Result<Client> {
+        self.session.require(Permission::ManageQuotations)?;
+        if !(0.0..=100.0).contains(&discount_percent) {
+            return Err(Error::InvalidField {
+                entity: "Client",
+                errors: vec![
+                    Message::new("quotation.error.discount").arg("value", discount_percent)
+                ],
+            });
+        }
+        let mut client = self.client(id_client)?;
+        client.discount_percent = discount_percent;
+        self.clients
+            .in_transaction(|conn| ClientRepo::modify_in(conn, &client))?;
+        self.client(id_client)
+    }
+
+    fn client(&self, id_client: u32) -> Result<Client> {
+        self.clients
+            .search_by_id(id_client)?
+            .ok_or(Error::NotFound {
+                entity: "Client",
+                id: Some(id_client),
+            })
+    }
+
+    // Partida con la descripcion y el precio vigentes
+    fn item_of(&self, line: &QuoteLine) -> Result<QuotationItem> {
+        let (id_package, id_analysis, description, unit_price) = match line.item {
+            QuotedItem::Package(id_package) => match self.packages.search_by_id(id_package)? {
+                Some(package) if package.package_active => {
+                    let price = self.package_price(&package)?;
+                    (Some(id_package), None, package.package_name, price)
+                }
+                _ => {
+                    return Err(Self::quotation_error(
+                        Message::new("quotation.error.package").arg("id", id_package),
+                    ))
+                }
+            },
+            QuotedItem::Analysis(id_analysis) => match self.analyses.search_by_id(id_analysis)? {
+                Some(analysis) if analysis.analysis_active => {
+                    let price = analysis.price.ok_or_else(|| Self::unpriced(&analysis))?;
+                    (None, Some(id_analysis), analysis.analysis_name, price)
+                }
+                _ => {
+                    return Err(Self::quotation_error(
+                        Message::new("quotation.error.analysis").arg("id", id_analysis),
+                    ))
+                }
+            },
+        };
+        if line.samples == 0 {
+            return Err(Self::quotation_error(
+                Message::new("quotation.error.samples").arg("name", description),
+            ));
+        }
+        Ok(QuotationItem {
+            id_item: None,
+            id_quotation: 0,
+            id_package,
+            id_analysis,
+            description,
+            samples: line.samples,
+            unit_price,
+        })
+    }
+
+    fn valid_quotation(
+        &self,
+        quotation: &Quotation,
+        lines: &[QuoteLine],
+    ) -> Result<Vec<QuotationItem>> {
+        let mut errors = Vec::new();
+
+        if quotation.id_quotation.is_some() {
+            errors.push(Message::new("quotation.error.existing"));
+        }
+
+        if !quotation.quotation_active {
+            errors.push(Message::new("quotation.error.inactive"));
+        }
+
+        match self.clients.search_by_id(quotation.id_client)? {
+            Some(client) if client.client_active => {}
+            _ => errors.push(Message::new("quotation.error.client").arg("id", quotation.id_client)),
+        }
+
+        // El sitio debe ser del cliente cotizado: las muestras se facturan al
+        // cliente de su sitio
+        if let Some(id_site) = quotation.id_site {
+            match self.sites.search_by_id(id_site)? {
+                None => errors.push(Message::new("quotation.error.site").arg("id", id_site)),
+                Some(site) if site.id_client != Some(quotation.id_client) => errors.push(
+                    Message::new("quotation.error.site_client")
+                        .arg("key", &site.site_key)
+                        .arg("id", quotation.id_client),
+                ),
+                Some(_) => {}
+            }
+        }
+
+        if quotation.valid_until < quotation.issued_on {
+            errors.push(
+                Message::new("quotation.error.dates")
+                    .arg("issued_on", quotation.issued_on)
+                    .arg("valid_until", quotation.valid_until),
+            );
+        }
+
+        if lines.is_empty() {
+            errors.push(Message::new("quotation.error.no_items"));
+        }
+        let mut items = Vec::new();
+        for line in lines {
+            match self.item_of(line) {
+                Ok(item) => items.push(item),
+                Err(Error::InvalidField {
+                    errors: messages, ..
+                }) => errors.extend(messages),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Quotation",
+                errors,
+            });
+        }
+        Ok(items)
+    }
+
+    // Guarda la cotizacion con los precios vigentes, el descuento del cliente
+    // y el IVA configurado
+    pub fn quote(&mut self, quotation: &Quotation, lines: &[QuoteLine]) -> Result<u32> {
+        self.session.require(Permission::ManageQuotations)?;
+        let items = self.valid_quotation(quotation, lines)?;
+        let mut quotation = quotation.clone();
+        quotation.discount_percent = self.client(quotation.id_client)?.discount_percent;
+        quotation.tax_percent = self.tax_percent;
+
+        self.quotations.in_transaction(|conn| {
+            let id_quotation = QuotationRepo::insert_in(conn, &quotation)?;
+            for item in &items {
+                let item = QuotationItem {
+                    id_quotation,
+                    ..item.clone()
+                };
+                QuotationItemRepo::insert_in(conn, &item)?;
+            }
+            Ok(id_quotation)
+        })
+    }
+
+    pub fn quotation(&self, id_quotation: u32) -> Result<Quotation> {
+        match self.quotations.search_by_id(id_quotation)? {
+            Some(quotation) if quotation.quotation_active => Ok(quotation),
+            _ => Err(Error::NotFound {
+                entity: "Quotation",
+                id: Some(id_quotation),
+            }),
+        }
+    }
+
+    pub fn items_of(&self, id_quotation: u32) -> Result<Vec<QuotationItem>> {
+        let criteria = QuotationItemSearchCriteria {
+            id_quotation: Some(id_quotation),
+            ..QuotationItemSearchCriteria::default()
+        };
+        self.items.find_all(&criteria)
+    }
+
+    pub fn analyses_of_item(&self, item: &QuotationItem) -> Result<Vec<Analysis>> {
+        match (item.id_package, item.id_analysis) {
+            (Some(id_package), _) => self.package_analyses(id_package),
+            (None, Some(id_analysis)) => Ok(self
+                .analyses
+                .search_by_id(id_analysis)?
+                .into_iter()
+                .collect()),
+            (None, None) => Ok(Vec::new()),
+        }
+    }
+
+    pub fn document(&self, id_quotation: u32) -> Result<QuotationDocument> {
+        let quotation = self.quotation(id_quotation)?;
+        let client = self.client(quotation.id_client)?;
+        let site = match quotation.id_site {
+            Some(id_site) => self.sites.search_by_id(id_site)?,
+            None => None,
+        };
+        let mut items = Vec::new();
+        for item in self.items_of(id_quotation)? {
+            let names = self
+                .analyses_of_item(&item)?
+                .into_iter()
+                .map(|analysis| analysis.analysis_name)
+                .collect();
+            items.push((item, names));
+        }
+        Ok(QuotationDocument::new(quotation, client, site, items))
+    }
+
+    pub fn expected_analyses(&self, id_sample: u32) -> Result<Vec<Analysis>> {
+        self.samples.expected_analyses(id_sample)
+    }
+
+    // Registra las muestras cotizadas, recibidas en `reception`, cada una con
+    // los analisis de su partida. Una cotizacion se convierte una sola vez y
+    // solo mientras esta vigente.
+    pub fn convert(&mut self, id_quotation: u32, reception: NaiveDate) -> Result<Vec<Sample>> {
+        self.session.require(Permission::RegisterSamples)?;
+        let mut quotation = self.quotation(id_quotation)?;
+        if let Some(converted_on) = quotation.converted_on {
+            return Err(Self::quotation_error(
+                Message::new("quotation.error.converted")
+                    .arg("id", id_quotation)
+                    .arg("date", converted_on),
+            ));
+        }
+        if reception > quotation.valid_until {
+            return Err(Self::quotation_error(
+                Message::new("quotation.error.expired")
+                    .arg("id", id_quotation)
+                    .arg("date", quotation.valid_until),
+            ));
+        }
+
+        let mut batches = Vec::new();
+        for item in self.items_of(id_quotation)? {
+            let analyses: Vec<u32> = self
+                .analyses_of_item(&item)?
+                .into_iter()
+                .filter_map(|analysis| analysis.id_analysis)
+                .collect();
+            batches.push((item.id_item, item.samples, analyses));
+        }
+        let sample = Sample {
+            sample_active: true,
+            project: quotation.project.clone(),
+            sampling_date: reception,
+            reception,
+            id_site: quotation.id_site,
+            id_quotation: Some(id_quotation),
+            ..Sample::default()
+        };
+        self.samples.valid_item(&sample)?;
+
+        let ids = self.quotations.in_transaction(|conn| {
+            let mut ids = Vec::new();
+            for (id_item, samples, analyses) in &batches {
+                let sample = Sample {
+                    id_quotation_item: *id_item,
+                    ..sample.clone()
+                };
+                for _ in 0..*samples {
+                    let id_sample = self.samples.insert_in(conn, &sample)?;
+                    SampleManager::expect_in(conn, id_sample, analyses)?;
+                    ids.push(id_sample);
+                }
+            }
+            quotation.converted_on = Some(reception);
+            QuotationRepo::modify_in(conn, &quotation)?;
+            Ok(ids)
+        })?;
+
+        let mut samples = Vec::new();
+        for id in ids {
+            samples.extend(self.samples.search_by_id(id)?);
+        }
+        Ok(samples)
+    }
+}
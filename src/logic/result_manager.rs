@@ -0,0 +1,217 @@
+use chrono::{SubsecRound, Utc};
+
+use crate::data::model::result_signature::ResultSignature;
+use crate::data::model::result_value::ResultValue;
+use crate::data::model::role::Permission;
+use crate::data::model::sample_result::SampleResult;
+use crate::data::repo::analysis_repo::AnalysisRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::result_signature_repo::{ResultSignatureRepo, SignatureSearchCriteria};
+use crate::data::repo::sample_result_repo::SampleResultRepo;
+use crate::data::repo::standard_repo::StandardRepo;
+use crate::data_management::{Finder, Repository};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::session::Session;
+use crate::logic::signature::{self, SignatureCheck};
+use crate::logic::units;
+use crate::logic::user_manager::UserManager;
+
+// Captura de resultados: cada resultado se guarda en la unidad de su norma
+pub struct ResultManager {
+    results: SampleResultRepo,
+    standards: StandardRepo,
+    analyses: AnalysisRepo,
+    signatures: ResultSignatureRepo,
+    users: UserManager,
+    session: Session,
+}
+
+impl ResultManager {
+    pub fn new(connector: Connector, page_size: u64) -> Self {
+        Self {
+            results: SampleResultRepo::new(connector.clone(), page_size),
+            signatures: ResultSignatureRepo::new(connector.clone(), page_size),
+            users: UserManager::new(connector.clone(), page_size),
+            standards: StandardRepo::new(connector.clone(), page_size),
+            analyses: AnalysisRepo::new(connector, page_size),
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.users = self.users.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    // Copia del resultado convertida a la unidad de la norma. Sin unidad se
+    // supone la de la norma; la unidad de un resultado sin valor (p. ej.
+    // "NA") tambien debe ser compatible. Un "ND" toma el limite de deteccion
+    // del analisis, que esta en la unidad en que se guardan los resultados.
+    pub fn in_standard_units(&self, result: &SampleResult) -> Result<SampleResult> {
+        let mut converted = self.convert_units(result)?;
+        converted.result = self.with_detection_limit(&converted)?;
+        Ok(converted)
+    }
+
+    fn convert_units(&self, result: &SampleResult) -> Result<SampleResult> {
+        let mut converted = result.clone();
+        let Some(captured) = result
+            .result_unit
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+        else {
+            converted.result_unit = None;
+            return Ok(converted);
+        };
+        let from = units::parse(captured)?;
+        converted.result_unit = Some(from.symbol.to_string());
+
+        let standard = match result.id_standard {
+            Some(id) => Some(self.standards.search_by_id(id)?.ok_or(Error::NotFound {
+                entity: "Standard",
+                id: Some(id),
+            })?),
+            None => None,
+        };
+        let Some(standard) = standard.filter(|s| !s.units.trim().is_empty()) else {
+            return Ok(converted);
+        };
+        let to = units::parse(&standard.units)?;
+
+        units::compatible(from, to)?;
+        converted.result = result
+            .result
+            .clone()
+            .try_map(|value| units::convert(value, from, to))?;
+        converted.result_unit = Some(to.symbol.to_string());
+        Ok(converted)
+    }
+
+    fn with_detection_limit(&self, result: &SampleResult) -> Result<ResultValue> {
+        let detection_limit = match result.id_analysis {
+            Some(id) => self
+                .analyses
+                .search_by_id(id)?
+                .and_then(|analysis| analysis.detection_limit),
+            None => None,
+        };
+        Ok(result.result.clone().with_detection_limit(detection_limit))
+    }
+
+    pub fn record(&mut self, result: &SampleResult) -> Result<u32> {
+        self.session.require(Permission::RecordResults)?;
+        let converted = self.in_standard_units(result)?;
+        self.results.insert(&converted)
+    }
+
+    // Un resultado firmado ya no se modifica; los triggers de la tabla lo
+    // impiden tambien fuera del manager
+    pub fn modify(&mut self, result: &SampleResult) -> Result<()> {
+        self.session.require(Permission::RecordResults)?;
+        if let Some(id) = result.id_sample_result {
+            if self.signature_of(id)?.is_some() {
+                return Err(Error::Locked {
+                    entity: "SampleResult",
+                    id: Some(id),
+                });
+            }
+        }
+        let converted = self.in_standard_units(result)?;
+        self.results.modify(&converted)
+    }
+
+    fn signature_error(key: &'static str) -> Error {
+        Error::InvalidField {
+            entity: "ResultSignature",
+            errors: vec![Message::new(key)],
+        }
+    }
+
+    pub fn signature_of(&self, id_sample_result: u32) -> Result<Option<ResultSignature>> {
+        Ok(self
+            .signatures
+            .find_all(&SignatureSearchCriteria {
+                id_sample_result: Some(id_sample_result),
+                ..SignatureSearchCriteria::default()
+            })?
+            .into_iter()
+            .next())
+    }
+
+    // El firmante vuelve a escribir su usuario y contraseña; debe ser el
+    // usuario de la sesion y tener permiso de revisar resultados. El resultado
+    // queda a su nombre y ya no se puede modificar.
+    pub fn sign(
+        &mut self,
+        id_sample_result: u32,
+        user: &str,
+        passwd: &str,
+    ) -> Result<ResultSignature> {
+        self.session.require(Permission::ReviewResults)?;
+        let signer = self.users.login(user, passwd)?;
+        signer.require(Permission::ReviewResults)?;
+        let id_signatary = signer.id_signatary.ok_or(Error::NotFound {
+            entity: "Signatary",
+            id: None,
+        })?;
+        if self
+            .session
+            .id_signatary
+            .is_some_and(|id| id != id_signatary)
+        {
+            return Err(Self::signature_error("result.error.signer_mismatch"));
+        }
+        if self.signature_of(id_sample_result)?.is_some() {
+            return Err(Self::signature_error("result.error.already_signed"));
+        }
+
+        let signed_at = Utc::now().trunc_subsecs(0);
+        self.results
+            .in_transaction(|conn| {
+                let mut result = SampleResultRepo::search_by_id_in(conn, id_sample_result)?.ok_or(
+                    Error::NotFound {
+                        entity: "SampleResult",
+                        id: Some(id_sample_result),
+                    },
+                )?;
+                result.id_signatary = Some(id_signatary);
+                SampleResultRepo::modify_in(conn, &result)?;
+
+                let mut signature = ResultSignature {
+                    id_signature: None,
+                    id_sample_result,
+                    id_signatary,
+                    signed_at,
+                    payload_hash: signature::payload_hash(&result, id_signatary, signed_at)?,
+                };
+                signature.id_signature = Some(ResultSignatureRepo::insert_in(conn, &signature)?);
+                Ok(signature)
+            })
+            .map_err(|e| {
+                if e.is_unique_violation() {
+                    Self::signature_error("result.error.already_signed")
+                } else {
+                    e
+                }
+            })
+    }
+
+    pub fn verify(&self, signature: &ResultSignature) -> Result<SignatureCheck> {
+        let result = self.results.search_by_id(signature.id_sample_result)?;
+        Ok(SignatureCheck {
+            status: signature::check(signature, result.as_ref())?,
+            signature: signature.clone(),
+        })
+    }
+
+    // Vuelve a calcular el hash de todas las firmas
+    pub fn verify_all(&self) -> Result<Vec<SignatureCheck>> {
+        self.signatures
+            .find_all(&SignatureSearchCriteria::default())?
+            .iter()
+            .map(|signature| self.verify(signature))
+            .collect()
+    }
+}
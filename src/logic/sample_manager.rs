@@ -0,0 +1,423 @@
+use chrono::{Datelike, Utc};
+use rusqlite::Connection;
+
+use crate::data::model::analysis::Analysis;
+use crate::data::model::role::Permission;
+use crate::data::model::sample::Sample;
+use crate::data::model::sample_analysis::SampleAnalysis;
+use crate::data::model::sample_status::SampleStatus;
+use crate::data::model::sample_transition::SampleTransition;
+use crate::data::repo::analysis_repo::AnalysisRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::control_number_repo::ControlNumberRepo;
+use crate::data::repo::sample_analysis_repo::{SampleAnalysisRepo, SampleAnalysisSearchCriteria};
+use crate::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use crate::data::repo::sample_transition_repo::{SampleTransitionRepo, TransitionSearchCriteria};
+use crate::data::repo::site_repo::SiteRepo;
+use crate::data::table::Patch;
+use crate::data_management::{Finder, LastSearch, Manager, Repository};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::control_number::ControlNumberPattern;
+use crate::logic::session::Session;
+
+pub struct SampleManager<SampleSearchCriteria> {
+    repository: SampleRepo,
+    sites: SiteRepo,
+    transitions: SampleTransitionRepo,
+    expected: SampleAnalysisRepo,
+    analyses: AnalysisRepo,
+    pattern: ControlNumberPattern,
+    session: Session,
+    last_search: Option<LastSearch<SampleSearchCriteria>>,
+    last_selected: Option<Sample>,
+}
+
+#[allow(unused)]
+impl SampleManager<SampleSearchCriteria> {
+    pub fn new(connector: Connector, page_size: u64, pattern: ControlNumberPattern) -> Self {
+        Self {
+            sites: SiteRepo::new(connector.clone(), page_size),
+            transitions: SampleTransitionRepo::new(connector.clone(), page_size),
+            expected: SampleAnalysisRepo::new(connector.clone(), page_size),
+            analyses: AnalysisRepo::new(connector.clone(), page_size),
+            repository: SampleRepo::new(connector, page_size),
+            pattern,
+            session: Session::default(),
+            last_search: None,
+            last_selected: None,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    // Iniciar y terminar el analisis es trabajo del analista; revisar,
+    // regresar a analisis, reportar y archivar corresponde al revisor
+    fn transition_permission(from: SampleStatus, to: SampleStatus) -> Permission {
+        match (from, to) {
+            (SampleStatus::Received, SampleStatus::InAnalysis)
+            | (SampleStatus::InAnalysis, SampleStatus::ResultsComplete) => {
+                Permission::RecordResults
+            }
+            _ => Permission::ReviewResults,
+        }
+    }
+
+    fn update_last_search(&mut self) -> Result<()> {
+        if self.last_search.is_none() {
+            return Ok(());
+        }
+
+        let last_search = self.last_search.clone().unwrap();
+        let result = self.search_by(&last_search.criteria, last_search.page)?;
+        self.last_search = Some(result);
+        Ok(())
+    }
+
+    fn control_number_taken(e: Error, sample: &Sample) -> Error {
+        if !e.is_unique_violation() {
+            return e;
+        }
+        Error::InvalidField {
+            entity: "Sample",
+            errors: vec![Message::new("sample.error.control_number_taken")
+                .arg("number", &sample.control_number)],
+        }
+    }
+
+    fn status_error(key: &'static str) -> Error {
+        Error::InvalidField {
+            entity: "Sample",
+            errors: vec![Message::new(key)],
+        }
+    }
+
+    // Cambia el estado de la muestra si la transicion es valida y guarda en el
+    // historial al usuario de la sesion; todo en una transaccion con control
+    // de version
+    pub fn transition(&mut self, id_sample: u32, to: SampleStatus) -> Result<Sample> {
+        let actor = self.session.user.trim().to_string();
+        if actor.is_empty() {
+            return Err(Self::status_error("sample.error.actor_empty"));
+        }
+
+        self.repository.in_transaction(|conn| {
+            let mut sample =
+                SampleRepo::search_by_id_in(conn, id_sample)?.ok_or(Error::NotFound {
+                    entity: "Sample",
+                    id: Some(id_sample),
+                })?;
+            let from = sample.sample_status;
+            self.session
+                .require(Self::transition_permission(from, to))?;
+            if !from.can_change_to(to) {
+                return Err(Error::InvalidField {
+                    entity: "Sample",
+                    errors: vec![Message::new("sample.error.transition")
+                        .arg("from", from.as_str())
+                        .arg("to", to.as_str())],
+                });
+            }
+
+            sample.sample_status = to;
+            SampleRepo::modify_in(conn, &sample)?;
+            SampleTransitionRepo::insert_in(
+                conn,
+                &SampleTransition {
+                    id_transition: None,
+                    id_sample,
+                    from_status: from,
+                    to_status: to,
+                    actor: actor.clone(),
+                    transitioned_at: Utc::now(),
+                },
+            )?;
+            Ok(())
+        })?;
+
+        self.update_last_search()?;
+        self.repository
+            .search_by_id(id_sample)?
+            .ok_or(Error::NotFound {
+                entity: "Sample",
+                id: Some(id_sample),
+            })
+    }
+
+    pub fn start_analysis(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::InAnalysis)
+    }
+
+    pub fn complete_results(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::ResultsComplete)
+    }
+
+    pub fn review(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::Reviewed)
+    }
+
+    // La revision encontro un problema: la muestra regresa a analisis
+    pub fn reopen(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::InAnalysis)
+    }
+
+    pub fn report(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::Reported)
+    }
+
+    pub fn archive(&mut self, id_sample: u32) -> Result<Sample> {
+        self.transition(id_sample, SampleStatus::Archived)
+    }
+
+    pub fn history(&self, id_sample: u32) -> Result<Vec<SampleTransition>> {
+        let criteria = TransitionSearchCriteria {
+            id_sample: Some(id_sample),
+            ..TransitionSearchCriteria::default()
+        };
+        self.transitions.find_all(&criteria)
+    }
+
+    // Muestras activas agrupadas por estado, en el orden del proceso
+    pub fn board(&self) -> Result<Vec<(SampleStatus, Vec<Sample>)>> {
+        let criteria = SampleSearchCriteria {
+            sample_active: Some(true),
+            ..SampleSearchCriteria::default()
+        };
+        let samples = self.repository.find_all(&criteria)?;
+        Ok(SampleStatus::ALL
+            .into_iter()
+            .map(|status| {
+                let group = samples
+                    .iter()
+                    .filter(|sample| sample.sample_status == status)
+                    .cloned()
+                    .collect();
+                (status, group)
+            })
+            .collect())
+    }
+
+    pub fn find_by_control_number(&self, control_number: &str) -> Result<Option<Sample>> {
+        let criteria = SampleSearchCriteria {
+            control_number: Some(control_number.trim().to_string()),
+            ..SampleSearchCriteria::default()
+        };
+        Ok(self
+            .repository
+            .find_all(&criteria)?
+            .into_iter()
+            .find(|sample| sample.control_number == control_number.trim()))
+    }
+
+    // Analisis que se esperan de la muestra (de su cotizacion o su programa)
+    pub fn expected_analyses(&self, id_sample: u32) -> Result<Vec<Analysis>> {
+        let criteria = SampleAnalysisSearchCriteria {
+            id_sample: Some(id_sample),
+            ..SampleAnalysisSearchCriteria::default()
+        };
+        let mut analyses = Vec::new();
+        for expected in self.expected.find_all(&criteria)? {
+            analyses.extend(self.analyses.search_by_id(expected.id_analysis)?);
+        }
+        Ok(analyses)
+    }
+
+    pub(crate) fn expect_in(conn: &Connection, id_sample: u32, analyses: &[u32]) -> Result<()> {
+        for id_analysis in analyses {
+            SampleAnalysisRepo::insert_in(
+                conn,
+                &SampleAnalysis {
+                    id_sample_analysis: None,
+                    id_sample,
+                    id_analysis: *id_analysis,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    // Alta que devuelve la muestra guardada. Sin numero de control se genera
+    // con el patron configurado y el consecutivo del año de recepcion.
+    pub fn register(&mut self, item: &Sample) -> Result<Sample> {
+        self.session.require(Permission::RegisterSamples)?;
+        self.valid_item(item)?;
+        let id = self
+            .repository
+            .in_transaction(|conn| self.insert_in(conn, item))?;
+
+        self.update_last_search()?;
+        self.repository.search_by_id(id)?.ok_or(Error::NotFound {
+            entity: "Sample",
+            id: Some(id),
+        })
+    }
+
+    pub(crate) fn insert_in(&self, conn: &Connection, item: &Sample) -> Result<u32> {
+        if item.control_number.trim().is_empty() {
+            let id_client = match item.id_site {
+                Some(id_site) => self
+                    .sites
+                    .search_by_id(id_site)?
+                    .and_then(|site| site.id_client),
+                None => None,
+            };
+            let reception = item.reception;
+            ControlNumberRepo::insert_numbered_in(conn, item, reception.year(), |seq| {
+                self.pattern.format(reception, id_client, seq)
+            })
+        } else {
+            let mut manual = item.clone();
+            manual.control_number = item.control_number.trim().to_string();
+            SampleRepo::insert_in(conn, &manual).map_err(|e| Self::control_number_taken(e, &manual))
+        }
+    }
+}
+
+#[allow(unused)]
+impl Manager<Sample, SampleSearchCriteria, Error> for SampleManager<SampleSearchCriteria> {
+    fn valid_item(&self, item: &Sample) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if item.id_sample.is_some() {
+            errors.push(Message::new("sample.error.existing"));
+        }
+
+        if !item.sample_active {
+            errors.push(Message::new("sample.error.inactive"));
+        }
+
+        if item.sample_status != SampleStatus::Received {
+            errors.push(Message::new("sample.error.status_direct"));
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Sample",
+                errors,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn last_search(&self) -> Option<LastSearch<SampleSearchCriteria>> {
+        self.last_search.clone()
+    }
+
+    fn last_selected(&self) -> Option<Sample> {
+        self.last_selected.clone()
+    }
+
+    fn set_last_search(&mut self, search: LastSearch<SampleSearchCriteria>) {
+        self.last_search = Some(search)
+    }
+
+    fn set_last_selected(&mut self, item: Sample) {
+        self.last_selected = Some(item)
+    }
+}
+
+impl Repository<Sample, Error> for SampleManager<SampleSearchCriteria> {
+    fn add(&mut self, item: &Sample) -> Result<()> {
+        self.register(item)?;
+        Ok(())
+    }
+
+    fn drop(&mut self, item: &mut Sample) -> Result<()> {
+        self.session.require(Permission::RegisterSamples)?;
+        self.repository.drop(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, item: &Sample) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        self.repository.delete(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn modify(&mut self, item: &Sample) -> Result<()> {
+        self.session.require(Permission::RegisterSamples)?;
+        let stored = item.id_sample.map(|id| self.repository.search_by_id(id));
+        if let Some(Some(stored)) = stored.transpose()? {
+            if stored.sample_status != item.sample_status {
+                return Err(Self::status_error("sample.error.status_direct"));
+            }
+        }
+        self.repository
+            .modify(item)
+            .map_err(|e| Self::control_number_taken(e, item))?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn add_many(&mut self, items: &[Sample]) -> Result<()> {
+        self.session.require(Permission::RegisterSamples)?;
+        for item in items {
+            self.valid_item(item)?;
+        }
+        self.repository.in_transaction(|conn| {
+            for item in items {
+                self.insert_in(conn, item)?;
+            }
+            Ok(())
+        })?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn drop_many(&mut self, items: &mut [Sample]) -> Result<()> {
+        self.session.require(Permission::RegisterSamples)?;
+        self.repository.drop_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn delete_many(&mut self, items: &[Sample]) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        self.repository.delete_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn modify_many(&mut self, items: &[Sample], patch: &Patch) -> Result<()> {
+        self.session.require(Permission::RegisterSamples)?;
+        if patch.columns().contains(&"sample_status") {
+            return Err(Self::status_error("sample.error.status_direct"));
+        }
+        self.repository.modify_many(items, patch)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+impl Finder<Sample, SampleSearchCriteria, Error> for SampleManager<SampleSearchCriteria> {
+    fn from_row(row: &rusqlite::Row) -> Result<Sample> {
+        let row = SampleRepo::from_row(row)?;
+        Ok(row)
+    }
+
+    fn page_size(&self) -> u64 {
+        self.repository.page_size()
+    }
+
+    fn search_by(
+        &mut self,
+        criteria: &SampleSearchCriteria,
+        page_number: u64,
+    ) -> Result<LastSearch<SampleSearchCriteria>> {
+        let result = self.repository.search_by(criteria, page_number)?;
+        self.set_last_search(result.clone());
+        Ok(result)
+    }
+
+    fn search_by_id(&self, id: u32) -> Result<Option<Sample>> {
+        let search = self.repository.search_by_id(id)?;
+        Ok(search)
+    }
+}
@@ -0,0 +1,332 @@
+use chrono::{Days, NaiveDate};
+
+use crate::data::model::analysis::Analysis;
+use crate::data::model::planned_visit::PlannedVisit;
+use crate::data::model::role::Permission;
+use crate::data::model::sample::Sample;
+use crate::data::model::sampling_schedule::SamplingSchedule;
+use crate::data::model::sampling_visit::SamplingVisit;
+use crate::data::model::schedule_analysis::ScheduleAnalysis;
+use crate::data::repo::analysis_repo::AnalysisRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::planning_repo::PlanningRepo;
+use crate::data::repo::sample_repo::SampleSearchCriteria;
+use crate::data::repo::sampler_repo::SamplerRepo;
+use crate::data::repo::sampling_schedule_repo::{SamplingScheduleRepo, ScheduleSearchCriteria};
+use crate::data::repo::sampling_visit_repo::{SamplingVisitRepo, VisitSearchCriteria};
+use crate::data::repo::schedule_analysis_repo::{
+    ScheduleAnalysisRepo, ScheduleAnalysisSearchCriteria,
+};
+use crate::data::repo::site_repo::SiteRepo;
+use crate::data_management::{Finder, Manager};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::control_number::ControlNumberPattern;
+use crate::logic::sample_manager::SampleManager;
+use crate::logic::session::Session;
+
+// Programas de muestreo periodico: planea las visitas, asigna muestreadores
+// y registra la muestra de cada visita realizada
+pub struct ScheduleManager {
+    schedules: SamplingScheduleRepo,
+    schedule_analyses: ScheduleAnalysisRepo,
+    visits: SamplingVisitRepo,
+    planning: PlanningRepo,
+    sites: SiteRepo,
+    samplers: SamplerRepo,
+    analyses: AnalysisRepo,
+    samples: SampleManager<SampleSearchCriteria>,
+    session: Session,
+}
+
+impl ScheduleManager {
+    pub fn new(connector: Connector, page_size: u64, pattern: ControlNumberPattern) -> Self {
+        Self {
+            schedules: SamplingScheduleRepo::new(connector.clone(), page_size),
+            schedule_analyses: ScheduleAnalysisRepo::new(connector.clone(), page_size),
+            visits: SamplingVisitRepo::new(connector.clone(), page_size),
+            planning: PlanningRepo::new(connector.clone()),
+            sites: SiteRepo::new(connector.clone(), page_size),
+            samplers: SamplerRepo::new(connector.clone(), page_size),
+            analyses: AnalysisRepo::new(connector.clone(), page_size),
+            samples: SampleManager::new(connector, page_size, pattern),
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.samples = self.samples.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn schedule_error(message: Message) -> Error {
+        Error::InvalidField {
+            entity: "SamplingSchedule",
+            errors: vec![message],
+        }
+    }
+
+    fn valid_sampler(&self, id_sampler: u32) -> Result<()> {
+        match self.samplers.search_by_id(id_sampler)? {
+            Some(sampler) if sampler.sampler_active => Ok(()),
+            _ => Err(Self::schedule_error(
+                Message::new("schedule.error.sampler").arg("id", id_sampler),
+            )),
+        }
+    }
+
+    fn valid_schedule(&self, schedule: &SamplingSchedule, analyses: &[u32]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if schedule.id_schedule.is_some() {
+            errors.push(Message::new("schedule.error.existing"));
+        }
+
+        if !schedule.schedule_active {
+            errors.push(Message::new("schedule.error.inactive"));
+        }
+
+        if self.sites.search_by_id(schedule.id_site)?.is_none() {
+            errors.push(Message::new("schedule.error.site").arg("id", schedule.id_site));
+        }
+
+        if let Some(ends_on) = schedule.ends_on {
+            if ends_on < schedule.starts_on {
+                errors.push(
+                    Message::new("schedule.error.dates")
+                        .arg("starts_on", schedule.starts_on)
+                        .arg("ends_on", ends_on),
+                );
+            }
+        }
+
+        if analyses.is_empty() {
+            errors.push(Message::new("schedule.error.no_analyses"));
+        }
+        for id_analysis in analyses {
+            if self.analyses.search_by_id(*id_analysis)?.is_none() {
+                errors.push(Message::new("schedule.error.analysis").arg("id", id_analysis));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "SamplingSchedule",
+                errors,
+            });
+        }
+
+        match schedule.id_sampler {
+            Some(id_sampler) => self.valid_sampler(id_sampler),
+            None => Ok(()),
+        }
+    }
+
+    // Alta del programa con los analisis que se hacen en cada visita
+    pub fn add_schedule(&mut self, schedule: &SamplingSchedule, analyses: &[u32]) -> Result<u32> {
+        self.session.require(Permission::EditSites)?;
+        let mut analyses = analyses.to_vec();
+        analyses.sort_unstable();
+        analyses.dedup();
+        self.valid_schedule(schedule, &analyses)?;
+
+        self.schedules.in_transaction(|conn| {
+            let id_schedule = SamplingScheduleRepo::insert_in(conn, schedule)?;
+            for id_analysis in &analyses {
+                ScheduleAnalysisRepo::insert_in(
+                    conn,
+                    &ScheduleAnalysis {
+                        id_schedule_analysis: None,
+                        id_schedule,
+                        id_analysis: *id_analysis,
+                    },
+                )?;
+            }
+            Ok(id_schedule)
+        })
+    }
+
+    pub fn schedules(&self) -> Result<Vec<SamplingSchedule>> {
+        let criteria = ScheduleSearchCriteria {
+            schedule_active: Some(true),
+            ..ScheduleSearchCriteria::default()
+        };
+        self.schedules.find_all(&criteria)
+    }
+
+    pub fn analyses_of(&self, id_schedule: u32) -> Result<Vec<Analysis>> {
+        let criteria = ScheduleAnalysisSearchCriteria {
+            id_schedule: Some(id_schedule),
+            ..ScheduleAnalysisSearchCriteria::default()
+        };
+        let mut analyses = Vec::new();
+        for detail in self.schedule_analyses.find_all(&criteria)? {
+            analyses.extend(self.analyses.search_by_id(detail.id_analysis)?);
+        }
+        Ok(analyses)
+    }
+
+    // Crea las visitas de los programas activos hasta `until` que todavia no
+    // existen y devuelve las nuevas; volver a planear no duplica visitas
+    pub fn plan(&mut self, until: NaiveDate) -> Result<Vec<SamplingVisit>> {
+        self.session.require(Permission::EditSites)?;
+        let mut pending = Vec::new();
+        for schedule in self.schedules()? {
+            let id_schedule = schedule.id_schedule.unwrap_or_default();
+            let criteria = VisitSearchCriteria {
+                id_schedule: Some(id_schedule),
+                ..VisitSearchCriteria::default()
+            };
+            let planned: Vec<NaiveDate> = self
+                .visits
+                .find_all(&criteria)?
+                .into_iter()
+                .map(|visit| visit.planned_date)
+                .collect();
+            let last = schedule.ends_on.map_or(until, |ends_on| ends_on.min(until));
+
+            pending.extend(
+                (0..)
+                    .map_while(|n| schedule.frequency.nth(schedule.starts_on, n))
+                    .take_while(|date| *date <= last)
+                    .filter(|date| !planned.contains(date))
+                    .map(|planned_date| SamplingVisit {
+                        id_schedule,
+                        planned_date,
+                        id_sampler: schedule.id_sampler,
+                        ..SamplingVisit::default()
+                    }),
+            );
+        }
+
+        let ids = self.visits.in_transaction(|conn| {
+            pending
+                .iter()
+                .map(|visit| SamplingVisitRepo::insert_in(conn, visit))
+                .collect::<Result<Vec<u32>>>()
+        })?;
+
+        let mut visits = Vec::new();
+        for id in ids {
+            visits.extend(self.visits.search_by_id(id)?);
+        }
+        Ok(visits)
+    }
+
+    // Visitas pendientes de hoy a `days` dias adelante
+    pub fn upcoming(&self, today: NaiveDate, days: u64) -> Result<Vec<PlannedVisit>> {
+        let until = today.checked_add_days(Days::new(days)).unwrap_or(today);
+        Ok(self
+            .planning
+            .pending_visits(until)?
+            .into_iter()
+            .filter(|visit| visit.planned_date >= today)
+            .collect())
+    }
+
+    // Visitas pendientes con fecha anterior a hoy
+    pub fn overdue(&self, today: NaiveDate) -> Result<Vec<PlannedVisit>> {
+        let yesterday = today.pred_opt().unwrap_or(today);
+        Ok(self
+            .planning
+            .pending_visits(yesterday)?
+            .into_iter()
+            .filter(|visit| visit.planned_date < today)
+            .collect())
+    }
+
+    // Visitas pendientes sin muestreador hasta `days` dias adelante, incluidas
+    // las atrasadas
+    pub fn unassigned(&self, today: NaiveDate, days: u64) -> Result<Vec<PlannedVisit>> {
+        let until = today.checked_add_days(Days::new(days)).unwrap_or(today);
+        Ok(self
+            .planning
+            .pending_visits(until)?
+            .into_iter()
+            .filter(|visit| visit.id_sampler.is_none())
+            .collect())
+    }
+
+    pub fn visit(&self, id_visit: u32) -> Result<SamplingVisit> {
+        self.visits.search_by_id(id_visit)?.ok_or(Error::NotFound {
+            entity: "SamplingVisit",
+            id: Some(id_visit),
+        })
+    }
+
+    fn pending_visit(&self, id_visit: u32) -> Result<SamplingVisit> {
+        let visit = self.visit(id_visit)?;
+        if visit.id_sample.is_some() {
+            return Err(Self::schedule_error(
+                Message::new("schedule.error.completed").arg("id", id_visit),
+            ));
+        }
+        Ok(visit)
+    }
+
+    pub fn assign(&mut self, id_visit: u32, id_sampler: u32) -> Result<SamplingVisit> {
+        self.session.require(Permission::EditSites)?;
+        let mut visit = self.pending_visit(id_visit)?;
+        self.valid_sampler(id_sampler)?;
+
+        visit.id_sampler = Some(id_sampler);
+        self.visits
+            .in_transaction(|conn| SamplingVisitRepo::modify_in(conn, &visit))?;
+        self.visit(id_visit)
+    }
+
+    // Muestra con los datos del programa y la visita, lista para completarse
+    // con la hora y registrarse
+    pub fn prefill(&self, id_visit: u32) -> Result<Sample> {
+        let visit = self.pending_visit(id_visit)?;
+        let schedule = self
+            .schedules
+            .search_by_id(visit.id_schedule)?
+            .ok_or(Error::NotFound {
+                entity: "SamplingSchedule",
+                id: Some(visit.id_schedule),
+            })?;
+
+        Ok(Sample {
+            sample_active: true,
+            project: schedule.project,
+            sampling_date: visit.planned_date,
+            reception: visit.planned_date,
+            id_sampler: visit.id_sampler,
+            id_site: Some(schedule.id_site),
+            ..Sample::default()
+        })
+    }
+
+    // Registra la muestra tomada en la visita con los analisis del programa y
+    // marca la visita como completa, en la misma transaccion
+    pub fn complete(&mut self, id_visit: u32, sample: &Sample) -> Result<Sample> {
+        self.session.require(Permission::RegisterSamples)?;
+        let mut visit = self.pending_visit(id_visit)?;
+        self.samples.valid_item(sample)?;
+        let analyses: Vec<u32> = self
+            .analyses_of(visit.id_schedule)?
+            .into_iter()
+            .filter_map(|analysis| analysis.id_analysis)
+            .collect();
+
+        let id_sample = self.visits.in_transaction(|conn| {
+            let id_sample = self.samples.insert_in(conn, sample)?;
+            SampleManager::expect_in(conn, id_sample, &analyses)?;
+            visit.id_sample = Some(id_sample);
+            if visit.id_sampler.is_none() {
+                visit.id_sampler = sample.id_sampler;
+            }
+            SamplingVisitRepo::modify_in(conn, &visit)?;
+            Ok(id_sample)
+        })?;
+
+        self.samples
+            .search_by_id(id_sample)?
+            .ok_or(Error::NotFound {
+                entity: "Sample",
+                id: Some(id_sample),
+            })
+    }
+}
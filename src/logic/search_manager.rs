@@ -0,0 +1,38 @@
+use crate::data::model::search_hit::SearchHit;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::search_repo::{fts_query, SearchRepo};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+pub struct SearchManager {
+    repository: SearchRepo,
+    last_search: Option<(String, Vec<SearchHit>)>,
+}
+
+#[allow(unused)]
+impl SearchManager {
+    pub fn new(connector: Connector, limit: u64) -> Self {
+        Self {
+            repository: SearchRepo::new(connector, limit),
+            last_search: None,
+        }
+    }
+
+    // Busqueda en clientes, sitios y muestras ordenada por relevancia
+    pub fn search(&mut self, text: &str) -> Result<Vec<SearchHit>> {
+        if fts_query(text).is_none() {
+            return Err(Error::InvalidField {
+                entity: "Search",
+                errors: vec![Message::new("search.error.empty")],
+            });
+        }
+
+        let hits = self.repository.search(text)?;
+        self.last_search = Some((text.to_string(), hits.clone()));
+        Ok(hits)
+    }
+
+    pub fn last_search(&self) -> Option<(String, Vec<SearchHit>)> {
+        self.last_search.clone()
+    }
+}
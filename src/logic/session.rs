@@ -0,0 +1,56 @@
+use crate::data::model::role::{Permission, Role};
+use crate::data::model::signatary::Signatary;
+use crate::error::{Error, Result};
+
+// Usuario que inicio sesion; los managers la consultan antes de cada
+// operacion que cambia datos
+#[derive(Clone, PartialEq, Debug)]
+pub struct Session {
+    pub id_signatary: Option<u32>,
+    pub user: String,
+    pub role: Role,
+}
+
+impl Session {
+    // Procesos internos y pruebas; nunca se entrega al iniciar sesion
+    pub fn system() -> Self {
+        Self {
+            id_signatary: None,
+            user: "system".to_string(),
+            role: Role::Admin,
+        }
+    }
+
+    pub fn of(signatary: &Signatary) -> Self {
+        Self {
+            id_signatary: signatary.id_signatary,
+            user: signatary.user.clone(),
+            role: signatary.role,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.allows(permission)
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<()> {
+        if self.can(permission) {
+            return Ok(());
+        }
+        Err(Error::Forbidden {
+            role: self.role,
+            permission,
+        })
+    }
+}
+
+// Sin sesion explicita solo se puede consultar
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            id_signatary: None,
+            user: String::new(),
+            role: Role::ReadOnly,
+        }
+    }
+}
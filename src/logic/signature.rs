@@ -0,0 +1,95 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::data::model::result_signature::ResultSignature;
+use crate::data::model::sample_result::SampleResult;
+use crate::error::Result;
+
+// Datos que cubre la firma, en un orden fijo; los cambios de estado (activo,
+// version, fechas de la fila) no alteran lo firmado
+#[derive(Serialize)]
+struct SignedPayload<'a> {
+    id_sample_result: Option<u32>,
+    id_sample: Option<u32>,
+    id_analysis: Option<u32>,
+    id_standard: Option<u32>,
+    id_signatary: u32,
+    analysis_date: NaiveDate,
+    result: String,
+    result_unit: Option<&'a str>,
+    signed_at: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Valid,
+    // El resultado o la firma cambiaron despues de firmar
+    Tampered,
+    // La firma apunta a un resultado que ya no existe
+    Missing,
+}
+
+impl SignatureStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Tampered => "tampered",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct SignatureCheck {
+    pub signature: ResultSignature,
+    pub status: SignatureStatus,
+}
+
+pub fn payload(
+    result: &SampleResult,
+    id_signatary: u32,
+    signed_at: DateTime<Utc>,
+) -> Result<String> {
+    Ok(serde_json::to_string(&SignedPayload {
+        id_sample_result: result.id_sample_result,
+        id_sample: result.id_sample,
+        id_analysis: result.id_analysis,
+        id_standard: result.id_standard,
+        id_signatary,
+        analysis_date: result.analysis_date,
+        result: result.result.to_string(),
+        result_unit: result.result_unit.as_deref(),
+        signed_at: signed_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+    })?)
+}
+
+pub fn payload_hash(
+    result: &SampleResult,
+    id_signatary: u32,
+    signed_at: DateTime<Utc>,
+) -> Result<String> {
+    let payload = payload(result, id_signatary, signed_at)?;
+    Ok(format!("{:x}", Sha256::digest(payload.as_bytes())))
+}
+
+// Compara el hash guardado con el de los datos actuales; tambien falla si el
+// resultado ya no lleva al firmante
+pub fn check(
+    signature: &ResultSignature,
+    result: Option<&SampleResult>,
+) -> Result<SignatureStatus> {
+    let Some(result) = result else {
+        return Ok(SignatureStatus::Missing);
+    };
+    let hash = payload_hash(result, signature.id_signatary, signature.signed_at)?;
+    if hash == signature.payload_hash
+        && result.id_sample_result == Some(signature.id_sample_result)
+        && result.id_signatary == Some(signature.id_signatary)
+    {
+        Ok(SignatureStatus::Valid)
+    } else {
+        Ok(SignatureStatus::Tampered)
+    }
+}
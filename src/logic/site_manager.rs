@@ -0,0 +1,402 @@
+use serde_json::{json, Value};
+
+use crate::data::model::analysis::Analysis;
+use crate::data::model::coordinate::{parse_degrees, Axis, BoundingBox, Coordinate};
+use crate::data::model::result_value::BelowLimitPolicy;
+use crate::data::model::role::Permission;
+use crate::data::model::site::Site;
+use crate::data::repo::analysis_repo::{AnalysisRepo, AnalysisSearchCriteria};
+use crate::data::repo::compliance_repo::ComplianceRepo;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::sample_repo::SampleRepo;
+use crate::data::repo::site_repo::{SiteRepo, SiteSearchCriteria};
+use crate::data::repo::trend_repo::TrendRepo;
+use crate::data::table::Patch;
+use crate::data_management::{Finder, LastSearch, Manager, Repository};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::compliance;
+use crate::logic::duplicates;
+use crate::logic::session::Session;
+use crate::logic::trend::{self, Trend};
+
+pub struct SiteManager<SiteSearchCriteria> {
+    repository: SiteRepo,
+    compliance: ComplianceRepo,
+    trends: TrendRepo,
+    analyses: AnalysisRepo,
+    below_limit: BelowLimitPolicy,
+    session: Session,
+    last_search: Option<LastSearch<SiteSearchCriteria>>,
+    last_selected: Option<Site>,
+}
+
+#[allow(unused)]
+impl SiteManager<SiteSearchCriteria> {
+    pub fn new(connector: Connector, page_size: u64) -> Self {
+        Self {
+            compliance: ComplianceRepo::new(connector.clone()),
+            trends: TrendRepo::new(connector.clone()),
+            analyses: AnalysisRepo::new(connector.clone(), page_size),
+            repository: SiteRepo::new(connector, page_size),
+            below_limit: BelowLimitPolicy::default(),
+            session: Session::default(),
+            last_search: None,
+            last_selected: None,
+        }
+    }
+
+    // Valor que toman los resultados debajo del limite de deteccion en las
+    // tendencias y el cumplimiento
+    pub fn with_below_limit(mut self, below_limit: BelowLimitPolicy) -> Self {
+        self.below_limit = below_limit;
+        self
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    fn update_last_search(&mut self) -> Result<()> {
+        if self.last_search.is_none() {
+            return Ok(());
+        }
+
+        let last_search = self.last_search.clone().unwrap();
+        let result = self.search_by(&last_search.criteria, last_search.page)?;
+        self.last_search = Some(result);
+        Ok(())
+    }
+
+    // Sitios con la misma clave o un nombre parecido, del mas parecido al menos
+    pub fn likely_duplicates(&self, site: &Site) -> Result<Vec<Site>> {
+        // En las claves tampoco cuentan los espacios: "NL-01" = "nl 01"
+        let compact = |key: &str| duplicates::normalize(key).replace(' ', "");
+        let key = compact(&site.site_key);
+        let mut matches: Vec<(usize, Site)> = self
+            .repository
+            .find_all(&SiteSearchCriteria::default())?
+            .into_iter()
+            .filter(|candidate| site.id_site.is_none() || candidate.id_site != site.id_site)
+            .filter_map(|candidate| {
+                let distance = if !key.is_empty() && compact(&candidate.site_key) == key {
+                    0
+                } else {
+                    duplicates::name_distance(&site.site_name, &candidate.site_name)?
+                };
+                Some((distance, candidate))
+            })
+            .collect();
+        matches.sort_by_key(|(distance, _)| *distance);
+        Ok(matches.into_iter().map(|(_, site)| site).collect())
+    }
+
+    // Une el sitio repetido con el que se conserva: sus muestras pasan al
+    // conservado y el repetido se da de baja. Devuelve cuantas muestras se movieron.
+    pub fn merge(&mut self, id_survivor: u32, id_duplicate: u32) -> Result<usize> {
+        let find = |id| {
+            self.repository.search_by_id(id)?.ok_or(Error::NotFound {
+                entity: "Site",
+                id: Some(id),
+            })
+        };
+        let survivor = find(id_survivor)?;
+        let mut duplicate = find(id_duplicate)?;
+
+        let mut errors = Vec::new();
+        if id_survivor == id_duplicate {
+            errors.push(Message::new("site.error.merge_same"));
+        }
+        if !survivor.site_active {
+            errors.push(Message::new("site.error.merge_inactive"));
+        }
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Site",
+                errors,
+            });
+        }
+
+        let moved = self.repository.in_transaction(|conn| {
+            let moved = SampleRepo::repoint_in(conn, "id_site", id_duplicate, id_survivor)?;
+            SiteRepo::drop_in(conn, &mut duplicate)?;
+            Ok(moved)
+        })?;
+        self.update_last_search()?;
+        Ok(moved)
+    }
+
+    fn coordinate_errors(latitude: &str, longitude: &str) -> Vec<Message> {
+        let mut errors = Vec::new();
+        if parse_degrees(latitude, Axis::Latitude).is_none() {
+            errors.push(Message::new("site.error.latitude").arg("value", latitude));
+        }
+        if parse_degrees(longitude, Axis::Longitude).is_none() {
+            errors.push(Message::new("site.error.longitude").arg("value", longitude));
+        }
+        errors
+    }
+
+    pub fn parse_coordinate(&self, latitude: &str, longitude: &str) -> Result<Coordinate> {
+        Coordinate::parse(latitude, longitude).ok_or_else(|| Error::InvalidField {
+            entity: "Site",
+            errors: Self::coordinate_errors(latitude, longitude),
+        })
+    }
+
+    // Sitios activos a no mas de `radius_km` del punto, del mas cercano al mas lejano
+    pub fn within_km(&self, center: Coordinate, radius_km: f64) -> Result<Vec<(Site, f64)>> {
+        let criteria = SiteSearchCriteria {
+            site_active: Some(true),
+            bounding_box: Some(BoundingBox::around(center, radius_km)),
+            ..SiteSearchCriteria::default()
+        };
+
+        let mut sites: Vec<(Site, f64)> = self
+            .repository
+            .find_all(&criteria)?
+            .into_iter()
+            .filter_map(|site| {
+                let distance =
+                    Coordinate::parse(&site.latitude, &site.longitude)?.distance_km(&center);
+                (distance <= radius_km).then_some((site, distance))
+            })
+            .collect();
+        sites.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(sites)
+    }
+
+    // Busqueda exacta sin distinguir mayusculas, para elegir desde la consola
+    pub fn find_by_key(&self, site_key: &str) -> Result<Option<Site>> {
+        let site_key = site_key.trim();
+        let criteria = SiteSearchCriteria {
+            site_key: Some(site_key.to_string()),
+            ..SiteSearchCriteria::default()
+        };
+        Ok(self
+            .repository
+            .find_all(&criteria)?
+            .into_iter()
+            .find(|site| site.site_key.eq_ignore_ascii_case(site_key)))
+    }
+
+    pub fn find_analysis(&self, analysis_name: &str) -> Result<Option<Analysis>> {
+        let analysis_name = analysis_name.trim();
+        let criteria = AnalysisSearchCriteria {
+            analysis_name: Some(analysis_name.to_string()),
+            ..AnalysisSearchCriteria::default()
+        };
+        Ok(self
+            .analyses
+            .find_all(&criteria)?
+            .into_iter()
+            .find(|analysis| {
+                duplicates::normalize(&analysis.analysis_name)
+                    == duplicates::normalize(analysis_name)
+            }))
+    }
+
+    // Serie de tiempo de un analisis en el sitio con sus estadisticas; marca
+    // los resultados a mas de `deviations` desviaciones o fuera del historial
+    pub fn result_trend(&self, id_site: u32, id_analysis: u32, deviations: f64) -> Result<Trend> {
+        if deviations.is_nan() || deviations <= 0.0 {
+            return Err(Error::InvalidField {
+                entity: "Site",
+                errors: vec![Message::new("site.error.deviations").arg("value", deviations)],
+            });
+        }
+        let points = self.trends.site_analysis_results(id_site, id_analysis)?;
+        Ok(trend::analyze(points, deviations, self.below_limit))
+    }
+
+    // FeatureCollection con un punto por sitio; los sitios con coordenadas
+    // invalidas se exportan sin geometria
+    pub fn geojson(&self, criteria: &SiteSearchCriteria, with_compliance: bool) -> Result<Value> {
+        let mut features = Vec::new();
+        for site in self.repository.find_all(criteria)? {
+            let geometry = Coordinate::parse(&site.latitude, &site.longitude).map(|point| {
+                json!({
+                    "type": "Point",
+                    "coordinates": [point.longitude, point.latitude],
+                })
+            });
+
+            let mut properties = json!({
+                "id_site": site.id_site,
+                "site_active": site.site_active,
+                "site_key": site.site_key,
+                "site_name": site.site_name,
+                "site_state": site.site_state,
+                "site_municipality": site.site_municipality,
+                "body_of_water": site.body_of_water,
+                "id_client": site.id_client,
+            });
+            if with_compliance {
+                let latest = match site.id_site {
+                    Some(id) => {
+                        compliance::latest_site_compliance(&self.compliance, id, self.below_limit)?
+                    }
+                    None => None,
+                };
+                properties["compliance"] = serde_json::to_value(latest)?;
+            }
+
+            features.push(json!({
+                "type": "Feature",
+                "id": site.id_site,
+                "geometry": geometry,
+                "properties": properties,
+            }));
+        }
+
+        Ok(json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }))
+    }
+}
+
+#[allow(unused)]
+impl Manager<Site, SiteSearchCriteria, Error> for SiteManager<SiteSearchCriteria> {
+    fn valid_item(&self, item: &Site) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if item.id_site.is_some() {
+            errors.push(Message::new("site.error.existing"));
+        }
+
+        if !item.site_active {
+            errors.push(Message::new("site.error.inactive"));
+        }
+
+        if item.site_key.trim().is_empty() {
+            errors.push(Message::new("site.error.key_empty"));
+        }
+
+        if item.site_name.trim().is_empty() {
+            errors.push(Message::new("site.error.name_empty"));
+        }
+
+        errors.extend(Self::coordinate_errors(&item.latitude, &item.longitude));
+
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Site",
+                errors,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn last_search(&self) -> Option<LastSearch<SiteSearchCriteria>> {
+        self.last_search.clone()
+    }
+
+    fn last_selected(&self) -> Option<Site> {
+        self.last_selected.clone()
+    }
+
+    fn set_last_search(&mut self, search: LastSearch<SiteSearchCriteria>) {
+        self.last_search = Some(search)
+    }
+
+    fn set_last_selected(&mut self, item: Site) {
+        self.last_selected = Some(item)
+    }
+}
+
+impl Repository<Site, Error> for SiteManager<SiteSearchCriteria> {
+    fn add(&mut self, item: &Site) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        self.valid_item(item)?;
+        self.repository.add(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn drop(&mut self, item: &mut Site) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        self.repository.drop(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, item: &Site) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        self.repository.delete(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn modify(&mut self, item: &Site) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        self.parse_coordinate(&item.latitude, &item.longitude)?;
+        self.repository.modify(item)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn add_many(&mut self, items: &[Site]) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        for item in items {
+            self.valid_item(item)?;
+        }
+        self.repository.add_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn drop_many(&mut self, items: &mut [Site]) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        self.repository.drop_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn delete_many(&mut self, items: &[Site]) -> Result<()> {
+        self.session.require(Permission::DeleteRecords)?;
+        self.repository.delete_many(items)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+
+    fn modify_many(&mut self, items: &[Site], patch: &Patch) -> Result<()> {
+        self.session.require(Permission::EditSites)?;
+        for item in items {
+            let item = patch.apply(item)?;
+            self.parse_coordinate(&item.latitude, &item.longitude)?;
+        }
+        self.repository.modify_many(items, patch)?;
+        self.update_last_search()?;
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+impl Finder<Site, SiteSearchCriteria, Error> for SiteManager<SiteSearchCriteria> {
+    fn from_row(row: &rusqlite::Row) -> Result<Site> {
+        let row = SiteRepo::from_row(row)?;
+        Ok(row)
+    }
+
+    fn page_size(&self) -> u64 {
+        self.repository.page_size()
+    }
+
+    fn search_by(
+        &mut self,
+        criteria: &SiteSearchCriteria,
+        page_number: u64,
+    ) -> Result<LastSearch<SiteSearchCriteria>> {
+        let result = self.repository.search_by(criteria, page_number)?;
+        self.set_last_search(result.clone());
+        Ok(result)
+    }
+
+    fn search_by_id(&self, id: u32) -> Result<Option<Site>> {
+        let search = self.repository.search_by_id(id)?;
+        Ok(search)
+    }
+}
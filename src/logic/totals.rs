@@ -0,0 +1,50 @@
+use crate::data::model::money::Money;
+use crate::i18n::{t, tf};
+
+// Anchos de columna compartidos por los documentos impresos
+pub const DESCRIPTION_WIDTH: usize = 36;
+pub const NUMBER_WIDTH: usize = 14;
+
+// Importes de una cotizacion o factura: el descuento se aplica al subtotal y
+// el IVA a lo que queda despues del descuento
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Totals {
+    pub discount_percent: f64,
+    pub tax_percent: u32,
+    pub subtotal: Money,
+    pub discount: Money,
+    pub tax: Money,
+    pub total: Money,
+}
+
+impl Totals {
+    pub fn new(subtotal: Money, discount_percent: f64, tax_percent: u32) -> Self {
+        let discount = subtotal.percent(discount_percent);
+        let tax = (subtotal - discount).percent(f64::from(tax_percent));
+        Self {
+            discount_percent,
+            tax_percent,
+            subtotal,
+            discount,
+            tax,
+            total: subtotal - discount + tax,
+        }
+    }
+
+    // Renglones de subtotal, descuento (si lo hay), IVA y total; las
+    // etiquetas se alinean a la derecha en `label_width` caracteres
+    pub fn rows(&self, label_width: usize) -> Vec<String> {
+        let row = |label: &str, amount: Money| {
+            format!("{:>label_width$} {:>NUMBER_WIDTH$}", label, amount)
+        };
+        let mut rows = vec![row(&t("totals.subtotal"), self.subtotal)];
+        if self.discount != Money::ZERO {
+            let label = tf("totals.discount", &[("percent", &self.discount_percent)]);
+            rows.push(row(&label, Money::ZERO - self.discount));
+        }
+        let label = tf("totals.tax", &[("percent", &self.tax_percent)]);
+        rows.push(row(&label, self.tax));
+        rows.push(row(&t("totals.total"), self.total));
+        rows
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::model::result_point::ResultPoint;
+use crate::data::model::result_value::BelowLimitPolicy;
+
+// Desviaciones estandar a partir de las que un resultado se marca como atipico
+pub const DEFAULT_DEVIATIONS: f64 = 3.0;
+
+// Resultados previos que hacen falta para comparar contra el historial
+pub const MIN_HISTORY: usize = 3;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultStats {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub p10: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierFlag {
+    // Mas de N desviaciones estandar del promedio de los demas resultados
+    Deviation,
+    // Fuera del minimo y maximo de los resultados anteriores
+    OutsideHistory,
+}
+
+impl OutlierFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deviation => "deviation",
+            Self::OutsideHistory => "outside_history",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub point: ResultPoint,
+    // None cuando el resultado no es numerico (p. ej. "<0.01" o "ND")
+    pub value: Option<f64>,
+    pub z_score: Option<f64>,
+    pub flags: Vec<OutlierFlag>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Trend {
+    pub deviations: f64,
+    pub points: Vec<TrendPoint>,
+    // None si la serie no tiene resultados numericos
+    pub stats: Option<ResultStats>,
+}
+
+impl Trend {
+    pub fn outliers(&self) -> impl Iterator<Item = &TrendPoint> {
+        self.points.iter().filter(|point| !point.flags.is_empty())
+    }
+}
+
+// Percentil con interpolacion lineal entre los valores vecinos; `sorted` debe
+// venir ordenado y no vacio
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+pub fn stats(values: &[f64]) -> Option<ResultStats> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (mean, std_dev) = mean_and_std_dev(values);
+
+    Some(ResultStats {
+        count: values.len(),
+        mean,
+        std_dev,
+        min: sorted[0],
+        p10: percentile(&sorted, 10.0),
+        p25: percentile(&sorted, 25.0),
+        median: percentile(&sorted, 50.0),
+        p75: percentile(&sorted, 75.0),
+        p90: percentile(&sorted, 90.0),
+        max: sorted[sorted.len() - 1],
+    })
+}
+
+// Cada resultado se compara contra los demas sin incluirse, asi un error de
+// captura grande no infla la desviacion que deberia delatarlo. Los resultados
+// debajo del limite de deteccion entran segun `below_limit`.
+pub fn analyze(points: Vec<ResultPoint>, deviations: f64, below_limit: BelowLimitPolicy) -> Trend {
+    let values: Vec<Option<f64>> = points
+        .iter()
+        .map(|point| {
+            point
+                .result
+                .clone()
+                .with_detection_limit(point.detection_limit)
+                .for_statistics(below_limit)
+        })
+        .collect();
+    let numeric: Vec<f64> = values.iter().flatten().copied().collect();
+
+    let points = points
+        .into_iter()
+        .zip(&values)
+        .enumerate()
+        .map(|(index, (point, value))| {
+            let Some(value) = *value else {
+                return TrendPoint {
+                    point,
+                    value: None,
+                    z_score: None,
+                    flags: Vec::new(),
+                };
+            };
+            let others: Vec<f64> = values
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .filter_map(|(_, value)| *value)
+                .collect();
+            let history: Vec<f64> = values[..index].iter().flatten().copied().collect();
+
+            let mut flags = Vec::new();
+            let mut z_score = None;
+            if others.len() >= MIN_HISTORY {
+                let (mean, std_dev) = mean_and_std_dev(&others);
+                if std_dev > 0.0 {
+                    let z = (value - mean) / std_dev;
+                    if z.abs() > deviations {
+                        flags.push(OutlierFlag::Deviation);
+                    }
+                    z_score = Some(z);
+                }
+            }
+            if history.len() >= MIN_HISTORY {
+                let min = history.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = history.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                if value < min || value > max {
+                    flags.push(OutlierFlag::OutsideHistory);
+                }
+            }
+
+            TrendPoint {
+                point,
+                value: Some(value),
+                z_score,
+                flags,
+            }
+        })
+        .collect();
+
+    Trend {
+        deviations,
+        points,
+        stats: stats(&numeric),
+    }
+}
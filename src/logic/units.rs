@@ -0,0 +1,182 @@
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+
+// Magnitud que mide una unidad; solo se convierte entre unidades de la misma
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    MassConcentration,
+    MassFraction,
+    Conductivity,
+    Temperature,
+    Turbidity,
+    // NMP y UFC vienen de metodos distintos y no se convierten entre si
+    MostProbableNumber,
+    ColonyCount,
+    Ph,
+    Ratio,
+}
+
+impl Dimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MassConcentration => "mass_concentration",
+            Self::MassFraction => "mass_fraction",
+            Self::Conductivity => "conductivity",
+            Self::Temperature => "temperature",
+            Self::Turbidity => "turbidity",
+            Self::MostProbableNumber => "most_probable_number",
+            Self::ColonyCount => "colony_count",
+            Self::Ph => "ph",
+            Self::Ratio => "ratio",
+        }
+    }
+}
+
+// valor en la unidad base = valor * factor + offset. La base de cada
+// dimension es la unidad con factor 1 (mg/L, mg/kg, µS/cm, °C, NTU...).
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+pub struct Unit {
+    pub symbol: &'static str,
+    pub aliases: &'static [&'static str],
+    pub dimension: Dimension,
+    pub factor: f64,
+    pub offset: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        aliases,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+pub const UNITS: &[Unit] = &[
+    unit("g/L", &[], Dimension::MassConcentration, 1000.0),
+    unit(
+        "mg/L",
+        &["ppm", "g/m3", "g/m³"],
+        Dimension::MassConcentration,
+        1.0,
+    ),
+    unit(
+        "µg/L",
+        &["ug/L", "μg/L", "ppb", "mg/m3", "mg/m³"],
+        Dimension::MassConcentration,
+        1e-3,
+    ),
+    unit("ng/L", &["ppt"], Dimension::MassConcentration, 1e-6),
+    unit("g/kg", &[], Dimension::MassFraction, 1000.0),
+    unit("mg/kg", &[], Dimension::MassFraction, 1.0),
+    unit("µg/kg", &["ug/kg", "μg/kg"], Dimension::MassFraction, 1e-3),
+    unit("S/m", &[], Dimension::Conductivity, 1e4),
+    unit("mS/cm", &[], Dimension::Conductivity, 1000.0),
+    unit(
+        "µS/cm",
+        &["uS/cm", "μS/cm", "µmho/cm", "umho/cm"],
+        Dimension::Conductivity,
+        1.0,
+    ),
+    unit("°C", &["C", "ºC", "degC"], Dimension::Temperature, 1.0),
+    Unit {
+        symbol: "K",
+        aliases: &[],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: -273.15,
+    },
+    Unit {
+        symbol: "°F",
+        aliases: &["F", "ºF", "degF"],
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: -32.0 * 5.0 / 9.0,
+    },
+    unit("NTU", &["UNT", "FTU", "FNU"], Dimension::Turbidity, 1.0),
+    unit(
+        "NMP/100 mL",
+        &["MPN/100 mL"],
+        Dimension::MostProbableNumber,
+        1.0,
+    ),
+    unit("UFC/100 mL", &["CFU/100 mL"], Dimension::ColonyCount, 1.0),
+    unit("pH", &["unidades de pH", "upH"], Dimension::Ph, 1.0),
+    unit("%", &[], Dimension::Ratio, 1.0),
+];
+
+// Los nombres se comparan sin espacios ("NMP/100mL"); si no hay coincidencia
+// exacta se busca sin distinguir mayusculas ("mg/l")
+fn compact(text: &str) -> String {
+    text.split_whitespace().collect()
+}
+
+pub fn find(text: &str) -> Option<&'static Unit> {
+    let text = compact(text);
+    let names = |unit: &'static Unit| {
+        std::iter::once(unit.symbol)
+            .chain(unit.aliases.iter().copied())
+            .map(compact)
+    };
+    UNITS
+        .iter()
+        .find(|unit| names(unit).any(|name| name == text))
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find(|unit| names(unit).any(|name| name.to_lowercase() == text.to_lowercase()))
+        })
+}
+
+pub fn parse(text: &str) -> Result<&'static Unit> {
+    find(text).ok_or_else(|| Error::InvalidField {
+        entity: "Unit",
+        errors: vec![Message::new("unit.error.unknown").arg("unit", text.trim())],
+    })
+}
+
+// Redondea a 12 cifras significativas para no arrastrar ruido de punto
+// flotante (25 µg/L -> 0.025 mg/L y no 0.025000000000000001)
+fn tidy(value: f64) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let digits = 12 - value.abs().log10().ceil() as i32;
+    let scale = 10f64.powi(digits);
+    (value * scale).round() / scale
+}
+
+pub fn compatible(from: &Unit, to: &Unit) -> Result<()> {
+    if from.dimension != to.dimension {
+        return Err(Error::InvalidField {
+            entity: "Unit",
+            errors: vec![Message::new("unit.error.incompatible")
+                .arg("from", from.symbol)
+                .arg("to", to.symbol)],
+        });
+    }
+    Ok(())
+}
+
+pub fn convert(value: f64, from: &Unit, to: &Unit) -> Result<f64> {
+    compatible(from, to)?;
+    if from == to {
+        return Ok(value);
+    }
+    let base = value * from.factor + from.offset;
+    Ok(tidy((base - to.offset) / to.factor))
+}
+
+// Conversion a partir de los textos capturados
+pub fn convert_text(value: f64, from: &str, to: &str) -> Result<f64> {
+    convert(value, parse(from)?, parse(to)?)
+}
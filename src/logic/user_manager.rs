@@ -0,0 +1,159 @@
+use crate::data::model::role::{Permission, Role};
+use crate::data::model::signatary::Signatary;
+use crate::data::repo::conector::Connector;
+use crate::data::repo::signatary_repo::{SignataryRepo, SignatarySearchCriteria};
+use crate::data_management::{Finder, Repository};
+use crate::error::{Error, Result};
+use crate::i18n::Message;
+use crate::logic::password;
+use crate::logic::session::Session;
+
+// Usuarios del sistema (firmantes), inicio de sesion y asignacion de roles
+pub struct UserManager {
+    repository: SignataryRepo,
+    session: Session,
+}
+
+impl UserManager {
+    pub fn new(connector: Connector, page_size: u64) -> Self {
+        Self {
+            repository: SignataryRepo::new(connector, page_size),
+            session: Session::default(),
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    fn user_error(key: &'static str) -> Error {
+        Error::InvalidField {
+            entity: "Signatary",
+            errors: vec![Message::new(key)],
+        }
+    }
+
+    fn active(&self, criteria: SignatarySearchCriteria) -> Result<Vec<Signatary>> {
+        self.repository.find_all(&SignatarySearchCriteria {
+            signatary_active: Some(true),
+            ..criteria
+        })
+    }
+
+    // Sin administradores activos no hay quien asigne roles: antes de iniciar
+    // sesion hay que dar de alta el primero
+    pub fn needs_setup(&self) -> Result<bool> {
+        Ok(self
+            .active(SignatarySearchCriteria {
+                role: Some(Role::Admin),
+                ..SignatarySearchCriteria::default()
+            })?
+            .is_empty())
+    }
+
+    // El mismo error para usuario inexistente y contraseña incorrecta
+    pub fn login(&self, user: &str, passwd: &str) -> Result<Session> {
+        let found = self.active(SignatarySearchCriteria {
+            user: Some(user.trim().to_string()),
+            ..SignatarySearchCriteria::default()
+        })?;
+        found
+            .iter()
+            .find(|signatary| password::verify(passwd, &signatary.passwd))
+            .map(Session::of)
+            .ok_or_else(|| Self::user_error("user.error.login"))
+    }
+
+    pub fn users(&self) -> Result<Vec<Signatary>> {
+        self.session.require(Permission::ManageUsers)?;
+        self.repository
+            .find_all(&SignatarySearchCriteria::default())
+    }
+
+    pub fn add_user(&mut self, signatary: &Signatary) -> Result<u32> {
+        self.session.require(Permission::ManageUsers)?;
+        self.insert_user(signatary)
+    }
+
+    // Solo mientras no haya ningun administrador activo
+    pub fn add_first_admin(&mut self, signatary: &Signatary) -> Result<u32> {
+        if !self.needs_setup()? {
+            return Err(Self::user_error("user.error.setup_done"));
+        }
+        self.insert_user(&Signatary {
+            role: Role::Admin,
+            ..signatary.clone()
+        })
+    }
+
+    fn insert_user(&mut self, signatary: &Signatary) -> Result<u32> {
+        let mut signatary = signatary.clone();
+        signatary.user = signatary.user.trim().to_string();
+        signatary.first_name = signatary.first_name.trim().to_string();
+        signatary.father_last_name = signatary.father_last_name.trim().to_string();
+
+        let mut errors = Vec::new();
+        if signatary.id_signatary.is_some() {
+            errors.push(Message::new("user.error.existing"));
+        }
+        if signatary.first_name.is_empty() || signatary.father_last_name.is_empty() {
+            errors.push(Message::new("user.error.name_empty"));
+        }
+        if signatary.user.is_empty() {
+            errors.push(Message::new("user.error.user_empty"));
+        } else if !self
+            .repository
+            .find_all(&SignatarySearchCriteria {
+                user: Some(signatary.user.clone()),
+                ..SignatarySearchCriteria::default()
+            })?
+            .is_empty()
+        {
+            errors.push(Message::new("user.error.user_taken").arg("user", &signatary.user));
+        }
+        if signatary.passwd.is_empty() {
+            errors.push(Message::new("user.error.passwd_empty"));
+        }
+        if !errors.is_empty() {
+            return Err(Error::InvalidField {
+                entity: "Signatary",
+                errors,
+            });
+        }
+
+        signatary.passwd = password::hash(&signatary.passwd)?;
+        signatary.signatary_active = true;
+        self.repository.insert(&signatary)
+    }
+
+    // No se puede quitar el rol al ultimo administrador activo
+    pub fn assign_role(&mut self, id_signatary: u32, role: Role) -> Result<Signatary> {
+        self.session.require(Permission::ManageUsers)?;
+        let mut signatary = self
+            .repository
+            .search_by_id(id_signatary)?
+            .ok_or(Error::NotFound {
+                entity: "Signatary",
+                id: Some(id_signatary),
+            })?;
+        if signatary.role == Role::Admin && role != Role::Admin && signatary.signatary_active {
+            let admins = self.active(SignatarySearchCriteria {
+                role: Some(Role::Admin),
+                ..SignatarySearchCriteria::default()
+            })?;
+            if admins.len() <= 1 {
+                return Err(Self::user_error("user.error.last_admin"));
+            }
+        }
+
+        signatary.role = role;
+        self.repository.modify(&signatary)?;
+        self.repository
+            .search_by_id(id_signatary)?
+            .ok_or(Error::NotFound {
+                entity: "Signatary",
+                id: Some(id_signatary),
+            })
+    }
+}
@@ -1,14 +1,99 @@
-use data::repo::conector::Connector;
-use view::client_console_view::ClientConsoleView;
-use view::console_view::ConsoleView;
+use std::io::{stdin, stdout, ErrorKind, IsTerminal};
 
-mod data;
-mod data_management;
-mod logic;
-mod view;
+use rust_project::cli::{self, Command, LabelsCommand};
+use rust_project::config::{Config, Interface};
+use rust_project::data::repo::conector::Connector;
+use rust_project::error::{Error, Result};
+use rust_project::i18n::{self, tf};
+use rust_project::logic::label_manager::LabelManager;
+use rust_project::logic::session::Session;
+#[cfg(feature = "tui")]
+use rust_project::view::client_tui_view::ClientTuiView;
+use rust_project::view::console_view::{self, ConsoleView};
+use rust_project::view::login_console_view::LoginConsoleView;
+use rust_project::view::main_console_view::MainConsoleView;
 
 fn main() {
-    Connector::db_init().unwrap();
-    let mut client_view = ClientConsoleView::new(50);
-    client_view.menu();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", cli::usage());
+        return;
+    }
+
+    let (command, args) = match cli::parse(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with(Error::from(e), 2),
+    };
+
+    let config = match Config::load(args) {
+        Ok(config) => config,
+        Err(e) => exit_with(Error::from(e), 2),
+    };
+
+    i18n::set_locale(config.locale);
+
+    if let Err(e) = Connector::new(&config).db_init() {
+        exit_with(e, 1);
+    }
+
+    console_view::hide_passwords(stdin().is_terminal());
+    let session = match LoginConsoleView::new(&config, stdin().lock(), stdout()).login() {
+        Ok(session) => session,
+        Err(Error::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => return,
+        Err(e) => exit_with(e, 1),
+    };
+
+    if let Command::Labels(command) = command {
+        if let Err(e) = print_labels(&config, session, &command) {
+            exit_with(e, 1);
+        }
+        return;
+    }
+
+    let result = match config.interface {
+        Interface::Console => MainConsoleView::new(&config, stdin().lock(), stdout())
+            .with_session(session)
+            .menu(),
+        #[cfg(feature = "tui")]
+        Interface::Tui => ClientTuiView::new(&config).with_session(session).run(),
+        // Config::validate rechaza la TUI cuando no se compilo la caracteristica
+        #[cfg(not(feature = "tui"))]
+        Interface::Tui => unreachable!("tui interface requires the tui feature"),
+    };
+
+    match result {
+        Ok(()) => {}
+        // Fin de la entrada (Ctrl+D): se sale igual que con la opcion de salida
+        Err(Error::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => {}
+        Err(e) => exit_with(e, 1),
+    }
+}
+
+// Las etiquetas escriben datos de las muestras en disco: piden iniciar sesion
+// con un rol que registre muestras
+fn print_labels(config: &Config, session: Session, command: &LabelsCommand) -> Result<()> {
+    let manager = LabelManager::new(Connector::new(config), config.page_size).with_session(session);
+    let labels = match command.reception {
+        Some(reception) => manager.labels_received_on(reception)?,
+        None => manager.labels_for(&command.control_numbers)?,
+    };
+    let paths = manager.export(&labels, command.format, &command.output)?;
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    println!(
+        "{}",
+        tf(
+            "label.written",
+            &[("total", &labels.len()), ("path", &paths.join(", "))]
+        )
+    );
+    Ok(())
+}
+
+fn exit_with(e: Error, status: i32) -> ! {
+    eprintln!("{}", e);
+    eprintln!("{}", e.diagnostic());
+    std::process::exit(status);
 }
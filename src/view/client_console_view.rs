@@ -1,199 +1,494 @@
+use std::io::{BufRead, Write};
+
 use crate::{
-    data::{model::client::Client, repo::client_repo::SearchCriteria},
+    config::Config,
+    data::{
+        model::{client::Client, client_contact::ClientContact, money::Money, role::Permission},
+        repo::{client_repo::SearchCriteria, conector::Connector},
+        table::Patch,
+    },
     data_management::{Finder, Repository},
+    error::{Error, Result},
+    i18n::{t, tf},
     logic::client_manager::ClientManager,
+    logic::session::Session,
 };
 
-use super::console_view::ConsoleView;
+use super::console_view::{visible_options, ConsoleView};
 
 #[allow(unused)]
-pub struct ClientConsoleView {
+pub struct ClientConsoleView<R, W> {
     manager: ClientManager<SearchCriteria>,
+    session: Session,
+    input: R,
+    output: W,
 }
 
 #[allow(unused)]
-impl ClientConsoleView {
-    pub fn new(page_size: u64) -> Self {
+impl<R, W> ClientConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
         Self {
-            manager: ClientManager::new(page_size),
+            manager: ClientManager::new(Connector::new(config), config.page_size),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        match option {
+            2..=4 | 8 => self.session.can(Permission::EditClients),
+            5 => self.session.can(Permission::DeleteRecords),
+            _ => true,
+        }
+    }
+
+    fn detail_allows(&self, option: u8) -> bool {
+        option > 3 || self.session.can(Permission::EditClients)
+    }
+
+    fn bulk_allows(&self, option: u8) -> bool {
+        match option {
+            1 | 2 => self.session.can(Permission::EditClients),
+            3 => self.session.can(Permission::DeleteRecords),
+            _ => true,
         }
     }
 
-    fn add_client(&mut self) {
-        Self::clear_linux_console();
+    fn add_client(&mut self) -> Result<()> {
+        self.clear_linux_console();
         let mut client = Client::default();
-        println!("Add a client");
-        client.client_name = Self::capture_string("Type the client name");
+        self.print(&t("client.add_title"))?;
+        client.client_name = self.capture_string(&t("client.add_name"))?;
         client.client_active = true;
-        self.manager.add(&client).map_err(|e| println!("{}", e));
+
+        // Advertencia de posibles repetidos; el usuario decide si lo agrega
+        let matches = match self.manager.likely_duplicates(&client) {
+            Ok(matches) => matches,
+            Err(e) => return self.show_error(&e),
+        };
+        if !matches.is_empty() {
+            let warning = tf("client.duplicates", &[("name", &client.client_name)]);
+            self.print(&warning)?;
+            self.print_clients(&matches)?;
+            self.print(&t("client.add_anyway"))?;
+            if self.capture_atributte::<u8>(&t("common.yes_no"), "u8")? != 1 {
+                return self.print(&t("client.not_added"));
+            }
+        }
+
+        if let Err(e) = self.manager.add(&client) {
+            self.show_error(&e)?;
+        }
+        Ok(())
+    }
+
+    fn print_clients(&mut self, clients: &[Client]) -> Result<()> {
+        for (client_number, client) in (1..).zip(clients) {
+            let row = tf(
+                "client.row",
+                &[
+                    ("number", &client_number),
+                    ("id", &client.id_client.unwrap_or_default()),
+                    ("name", &client.client_name),
+                    ("active", &client.client_active),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn client_detail(&mut self) -> Result<()> {
+        let id: u32 = self.capture_atributte(&t("client.detail_id"), "u32")?;
+        loop {
+            let loaded = self
+                .manager
+                .search_by_id(id)
+                .and_then(|client| match client {
+                    Some(client) => Ok((
+                        client,
+                        self.manager.contacts_of(id)?,
+                        self.manager.unpaid_balance(id)?,
+                    )),
+                    None => Err(Error::NotFound {
+                        entity: "Client",
+                        id: Some(id),
+                    }),
+                });
+            let (client, contacts, balance) = match loaded {
+                Ok(loaded) => loaded,
+                Err(e) => return self.show_error(&e),
+            };
+
+            self.clear_linux_console();
+            self.print_detail(&client, &contacts, balance)?;
+            let options = visible_options(&t("client.detail_options"), |o| self.detail_allows(o));
+            match self.capture_atributte::<u8>(&options, "u8")? {
+                option if !self.detail_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.add_contact(id)?,
+                2 => self.edit_fiscal_data(client)?,
+                3 => self.drop_contact(contacts)?,
+                4 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn print_detail(
+        &mut self,
+        client: &Client,
+        contacts: &[ClientContact],
+        balance: Money,
+    ) -> Result<()> {
+        let none = t("common.none");
+        let detail = tf(
+            "client.detail",
+            &[
+                ("id", &client.id_client.unwrap_or_default()),
+                ("name", &client.client_name),
+                ("active", &client.client_active),
+                ("rfc", client.client_rfc.as_ref().unwrap_or(&none)),
+                ("address", client.billing_address.as_ref().unwrap_or(&none)),
+                ("balance", &balance),
+            ],
+        );
+        self.print(&detail)?;
+
+        if contacts.is_empty() {
+            return self.print(&t("client.no_contacts"));
+        }
+        self.print(&t("client.contacts"))?;
+        for (number, contact) in (1..).zip(contacts) {
+            let row = tf(
+                "client.contact_row",
+                &[
+                    ("number", &number),
+                    ("name", &contact.contact_name),
+                    ("role", contact.contact_role.as_ref().unwrap_or(&none)),
+                    ("email", contact.email.as_ref().unwrap_or(&none)),
+                    ("phone", contact.phone.as_ref().unwrap_or(&none)),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn add_contact(&mut self, id_client: u32) -> Result<()> {
+        let contact = ClientContact {
+            id_client,
+            contact_name: self.capture_string(&t("client.contact_name"))?,
+            contact_role: Some(self.capture_string(&t("client.contact_role"))?),
+            email: Some(self.capture_string(&t("client.contact_email"))?),
+            phone: Some(self.capture_string(&t("client.contact_phone"))?),
+            ..ClientContact::default()
+        };
+        if let Err(e) = self.manager.add_contact(&contact) {
+            self.show_error(&e)?;
+        }
+        Ok(())
+    }
+
+    // Un dato vacio borra el RFC o el domicilio
+    fn edit_fiscal_data(&mut self, mut client: Client) -> Result<()> {
+        client.client_rfc = Some(self.capture_string(&t("client.rfc"))?);
+        client.billing_address = Some(self.capture_string(&t("client.billing_address"))?);
+        if let Err(e) = self.manager.modify(&client) {
+            self.show_error(&e)?;
+        }
+        Ok(())
+    }
+
+    fn drop_contact(&mut self, mut contacts: Vec<ClientContact>) -> Result<()> {
+        let number: usize = self.capture_atributte(&t("client.contact_number"), "usize")?;
+        let Some(contact) = number.checked_sub(1).and_then(|i| contacts.get_mut(i)) else {
+            return self.print(&t("common.invalid_option"));
+        };
+        if let Err(e) = self.manager.drop_contact(contact) {
+            self.show_error(&e)?;
+        }
+        Ok(())
+    }
+
+    fn merge_clients(&mut self) -> Result<()> {
+        let id_survivor: u32 = self.capture_atributte(&t("client.merge_survivor"), "u32")?;
+        let id_duplicate: u32 = self.capture_atributte(&t("client.merge_duplicate"), "u32")?;
+
+        let preview = self.manager.search_by_id(id_survivor).and_then(|survivor| {
+            let duplicate = self.manager.search_by_id(id_duplicate)?;
+            let counts = self.manager.merge_preview(id_duplicate)?;
+            Ok((survivor, duplicate, counts))
+        });
+        let (survivor, duplicate, counts) = match preview {
+            Ok((Some(survivor), Some(duplicate), counts)) => (survivor, duplicate, counts),
+            Ok(_) => return self.print(&t("common.no_results")),
+            Err(e) => return self.show_error(&e),
+        };
+
+        let confirm = tf(
+            "client.merge_preview",
+            &[
+                ("sites", &counts.sites),
+                ("contacts", &counts.contacts),
+                ("quotations", &counts.quotations),
+                ("invoices", &counts.invoices),
+                ("duplicate", &duplicate.client_name),
+                ("survivor", &survivor.client_name),
+            ],
+        );
+        self.print(&confirm)?;
+        if self.capture_atributte::<u8>(&t("common.yes_no"), "u8")? != 1 {
+            return self.print(&t("client.bulk.cancelled"));
+        }
+
+        match self.manager.merge(id_survivor, id_duplicate) {
+            Ok(moved) => {
+                let merged = tf(
+                    "client.merged",
+                    &[
+                        ("sites", &moved.sites),
+                        ("contacts", &moved.contacts),
+                        ("quotations", &moved.quotations),
+                        ("invoices", &moved.invoices),
+                        ("name", &survivor.client_name),
+                    ],
+                );
+                self.print(&merged)
+            }
+            Err(e) => self.show_error(&e),
+        }
     }
 
     fn get_clients_from_criteria(
         &mut self,
         criteria: &SearchCriteria,
         page_number: u64,
-    ) -> Option<u64> {
-        Self::clear_linux_console();
+    ) -> Result<Option<u64>> {
+        self.clear_linux_console();
         let search = match self.manager.search_by(criteria, page_number) {
             Ok(result) => result,
-            Err(_) => return None,
+            Err(e) => {
+                self.show_error(&e)?;
+                return Ok(None);
+            }
         };
 
         let clients: Vec<Client> = match serde_json::from_str(&search.result) {
             Ok(clients) => clients,
             Err(e) => {
-                println!("{}", e);
-                return None;
+                self.show_error(&e.into())?;
+                return Ok(None);
             }
         };
 
-        let mut client_number = (page_number - 1) * self.manager.page_size() + 1;
-        for client in clients {
-            println!(
-                "{}) ID: {}, Name: {}, Active: {}",
-                client_number,
-                client.id_client.unwrap(),
-                client.client_name,
-                client.client_active
+        let first_number = (page_number - 1) * self.manager.page_size() + 1;
+        for (client_number, client) in (first_number..).zip(clients) {
+            let row = tf(
+                "client.row",
+                &[
+                    ("number", &client_number),
+                    ("id", &client.id_client.unwrap()),
+                    ("name", &client.client_name),
+                    ("active", &client.client_active),
+                ],
             );
-            client_number += 1;
+            self.print(&row)?;
         }
         if search.total_pages > 0 {
-            println!("page {} of {}", page_number, search.total_pages);
-            return Some(search.total_pages);
+            let page_of = tf(
+                "common.page_of",
+                &[("page", &page_number), ("total", &search.total_pages)],
+            );
+            self.print(&page_of)?;
+            return Ok(Some(search.total_pages));
         } else {
-            println!("No hay resultados");
+            self.print(&t("common.no_results"))?;
         }
 
-        Some(0)
+        Ok(Some(0))
     }
 
-    fn list_clients(&mut self) {
+    fn list_clients(&mut self) -> Result<()> {
         let criteria = SearchCriteria::default();
-        let mut page = 1;
-        loop {
-            let total_pages = self.get_clients_from_criteria(&criteria, page);
-
-            if total_pages.is_none() {
-                return;
-            }
-
-            let total_pages = total_pages.unwrap();
-            let opc: u8 = Self::capture_atributte("1) prev page\n2) next page\n3) exit", "u8");
-            match opc {
-                1 => {
-                    if page > 1 {
-                        page -= 1;
-                    }
-                }
-                2 => {
-                    if page < total_pages {
-                        page += 1;
-                    }
-                }
-                3 => break,
-                _ => println!("Invalid option"),
-            }
-        }
+        self.browse_clients(&criteria, false)
     }
 
-    fn get_criteria() -> SearchCriteria {
+    fn get_criteria(&mut self) -> Result<SearchCriteria> {
         let mut curr_criteria = SearchCriteria::default();
         loop {
-            println!(
-                "Current criteria:\nID: {}\nActive: {}\nName: {}",
-                curr_criteria
-                    .id_client
-                    .map_or("None".to_string(), |value| value.to_string()),
-                curr_criteria
-                    .client_active
-                    .map_or("None".to_string(), |value| value.to_string()),
-                curr_criteria
-                    .client_name
-                    .clone()
-                    .map_or("None".to_string(), |value| value)
+            let none = t("common.none");
+            let current = tf(
+                "client.current_criteria",
+                &[
+                    (
+                        "id",
+                        &curr_criteria
+                            .id_client
+                            .map_or(none.clone(), |value| value.to_string()),
+                    ),
+                    (
+                        "active",
+                        &curr_criteria
+                            .client_active
+                            .map_or(none.clone(), |value| value.to_string()),
+                    ),
+                    (
+                        "name",
+                        &curr_criteria.client_name.clone().unwrap_or(none.clone()),
+                    ),
+                ],
             );
+            self.print(&current)?;
 
-            let mut options = String::new();
-            options.push_str("1) Set id criteria\n");
-            options.push_str("2) Set active criteria\n");
-            options.push_str("3) Set name criteria\n");
-            options.push_str("4) Continue");
-
-            let opc = Self::capture_atributte::<u8>(&options, "u8");
+            let opc = self.capture_atributte::<u8>(&t("client.criteria_options"), "u8")?;
             match opc {
                 1 => {
-                    curr_criteria.id_client = Self::capture_option_attribute("Add criteria?", "u32")
+                    curr_criteria.id_client =
+                        self.capture_option_attribute(&t("common.add_criteria"), "u32")?
                 }
                 2 => {
                     curr_criteria.client_active =
-                        Self::capture_option_attribute("Add criteria?", "bool")
+                        self.capture_option_attribute(&t("common.add_criteria"), "bool")?
                 }
                 3 => {
                     curr_criteria.client_name =
-                        Self::capture_option_attribute("Add criteria?", "String")
+                        self.capture_option_attribute(&t("common.add_criteria"), "String")?
                 }
-                4 => return curr_criteria,
-                _ => println!("Invalid option"),
+                4 => return Ok(curr_criteria),
+                _ => self.print(&t("common.invalid_option"))?,
             }
         }
     }
 
-    fn search_client(&mut self) {
-        let criteria = Self::get_criteria();
+    fn search_client(&mut self) -> Result<()> {
+        let criteria = self.get_criteria()?;
+        self.browse_clients(&criteria, true)
+    }
+
+    // Con `bulk` el paginador ofrece las acciones en lote sobre el criterio
+    fn browse_clients(&mut self, criteria: &SearchCriteria, bulk: bool) -> Result<()> {
+        let bulk = bulk && self.session.can(Permission::EditClients);
+        let pager = if bulk {
+            t("client.search_pager")
+        } else {
+            t("common.pager")
+        };
+
         let mut page = 1;
         loop {
-            let total_pages = self.get_clients_from_criteria(&criteria, page);
-
-            if total_pages.is_none() {
-                println!("No hay resultados");
-            } else {
-                let total_pages = total_pages.unwrap();
-
-                let mut title = String::new();
-                title.push_str("1) prev page\n");
-                title.push_str("2) next page\n");
-                title.push_str("3) exit");
-
-                let opc: u8 = Self::capture_atributte(&title, "u8");
-                match opc {
-                    1 => {
-                        if page > 1 {
-                            page -= 1;
-                        }
+            let total_pages = match self.get_clients_from_criteria(criteria, page)? {
+                Some(total_pages) => total_pages,
+                None => return Ok(()),
+            };
+
+            let opc: u8 = self.capture_atributte(&pager, "u8")?;
+            match opc {
+                1 => {
+                    if page > 1 {
+                        page -= 1;
                     }
-                    2 => {
-                        if page < total_pages {
-                            page += 1;
-                        }
+                }
+                2 => {
+                    if page < total_pages {
+                        page += 1;
                     }
-                    3 => break,
-                    _ => println!("Invalid option"),
                 }
+                3 => return Ok(()),
+                4 if bulk => self.bulk_action(criteria)?,
+                _ => self.print(&t("common.invalid_option"))?,
             }
         }
     }
+
+    fn bulk_action(&mut self, criteria: &SearchCriteria) -> Result<()> {
+        let options = visible_options(&t("client.bulk.options"), |o| self.bulk_allows(o));
+        let opc: u8 = self.capture_atributte(&options, "u8")?;
+        let (action, keep): (_, fn(&Client) -> bool) = match opc {
+            opc if !self.bulk_allows(opc) => return self.print(&t("common.invalid_option")),
+            1 => ("client.bulk.deactivate", |client| client.client_active),
+            2 => ("client.bulk.reactivate", |client| !client.client_active),
+            3 => ("client.bulk.delete", |_| true),
+            4 => return Ok(()),
+            _ => return self.print(&t("common.invalid_option")),
+        };
+
+        let mut clients = match self.manager.find_all(criteria) {
+            Ok(clients) => clients,
+            Err(e) => return self.show_error(&e),
+        };
+        clients.retain(keep);
+        if clients.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+
+        // Vista previa de los registros afectados antes de confirmar
+        let count = clients.len();
+        self.print(&tf("client.bulk.preview", &[("count", &count)]))?;
+        self.print_clients(&clients)?;
+
+        let confirm = tf(
+            "client.bulk.confirm",
+            &[("action", &t(action)), ("count", &count)],
+        );
+        self.print(&confirm)?;
+        if self.capture_atributte::<u8>(&t("common.yes_no"), "u8")? != 1 {
+            return self.print(&t("client.bulk.cancelled"));
+        }
+
+        let result = match opc {
+            1 => self.manager.drop_many(&mut clients),
+            2 => Patch::new()
+                .set("client_active", true)
+                .and_then(|patch| self.manager.modify_many(&clients, &patch)),
+            _ => self.manager.delete_many(&clients),
+        };
+        match result {
+            Ok(()) => self.print(&tf("client.bulk.done", &[("count", &count)])),
+            Err(e) => self.show_error(&e),
+        }
+    }
 }
 
-impl ConsoleView for ClientConsoleView {
-    fn menu(&mut self) {
+impl<R, W> ConsoleView<R, W> for ClientConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
         loop {
-            Self::clear_linux_console();
-            println!("Client Management");
-            println!("1) List clients");
-            println!("2) Add client");
-            println!("3) Modify client");
-            println!("4) Logic client deletion");
-            println!("5) Complete client deletion");
-            println!("6) Search client");
-            println!("7) Exit");
-            match Self::capture_atributte::<u8>("Select an option: ", "u8") {
-                1 => self.list_clients(),
-                2 => self.add_client(),
-                6 => self.search_client(),
-                7 => return,
-                _ => println!("Invalid option"),
+            self.clear_linux_console();
+            self.print(&t("client.menu.title"))?;
+            let options = visible_options(&t("client.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.list_clients()?,
+                2 => self.add_client()?,
+                6 => self.search_client()?,
+                7 => return Ok(()),
+                8 => self.merge_clients()?,
+                9 => self.client_detail()?,
+                _ => self.print(&t("common.invalid_option"))?,
             }
         }
     }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
 }
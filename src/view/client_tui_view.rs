@@ -0,0 +1,619 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::{
+    config::Config,
+    data::{
+        model::{client::Client, role::Permission},
+        repo::{client_repo::SearchCriteria, conector::Connector},
+    },
+    data_management::{Finder, Repository},
+    error::Result,
+    i18n::{t, tf},
+    logic::client_manager::ClientManager,
+    logic::session::Session,
+};
+
+// Campo de texto de un formulario; los valores se convierten al confirmar
+#[derive(Debug, Clone, PartialEq)]
+struct FormField {
+    label: String,
+    value: String,
+}
+
+impl FormField {
+    fn new(label_key: &str, value: String) -> Self {
+        Self {
+            label: t(label_key),
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FormKind {
+    Filter,
+    Add,
+    Edit(Client),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Form {
+    kind: FormKind,
+    fields: Vec<FormField>,
+    focus: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Mode {
+    Browse,
+    Form(Form),
+    ConfirmDelete(Client),
+}
+
+pub struct ClientTuiView {
+    manager: ClientManager<SearchCriteria>,
+    session: Session,
+    criteria: SearchCriteria,
+    page: u64,
+    total_pages: u64,
+    clients: Vec<Client>,
+    table: TableState,
+    mode: Mode,
+    status: String,
+    exit: bool,
+}
+
+impl ClientTuiView {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            manager: ClientManager::new(Connector::new(config), config.page_size),
+            session: Session::default(),
+            criteria: SearchCriteria::default(),
+            page: 1,
+            total_pages: 0,
+            clients: Vec::new(),
+            table: TableState::default(),
+            mode: Mode::Browse,
+            status: t("tui.help"),
+            exit: false,
+        }
+    }
+
+    // Sin permiso para editar, la ayuda solo muestra las teclas de consulta
+    pub fn with_session(mut self, session: Session) -> Self {
+        if !session.can(Permission::EditClients) {
+            self.status = t("tui.help_read_only");
+        }
+        self.manager = self.manager.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        self.refresh();
+        while !self.exit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn refresh(&mut self) {
+        let search = match self.manager.search_by(&self.criteria, self.page) {
+            Ok(search) => search,
+            Err(e) => {
+                self.status = e.to_string();
+                return;
+            }
+        };
+
+        match serde_json::from_str::<Vec<Client>>(&search.result) {
+            Ok(clients) => self.clients = clients,
+            Err(e) => {
+                self.status = crate::error::Error::from(e).to_string();
+                return;
+            }
+        }
+
+        self.total_pages = search.total_pages;
+        // Si la pagina actual quedo vacia (p. ej. tras eliminar) se retrocede
+        if self.clients.is_empty() && self.page > 1 {
+            self.page = self.total_pages.max(1);
+            return self.refresh();
+        }
+
+        let selected = self.table.selected().unwrap_or(0);
+        if self.clients.is_empty() {
+            self.table.select(None);
+        } else {
+            self.table
+                .select(Some(selected.min(self.clients.len() - 1)));
+        }
+    }
+
+    fn selected(&self) -> Option<Client> {
+        self.table
+            .selected()
+            .and_then(|index| self.clients.get(index).cloned())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match self.mode.clone() {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::Form(form) => self.handle_form_key(form, key),
+            Mode::ConfirmDelete(client) => self.handle_confirm_key(client, key),
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) {
+        let permission = match key.code {
+            KeyCode::Char('a' | 'e' | 'x') | KeyCode::Enter => Some(Permission::EditClients),
+            KeyCode::Char('D') | KeyCode::Delete => Some(Permission::DeleteRecords),
+            _ => None,
+        };
+        if let Some(Err(e)) = permission.map(|permission| self.session.require(permission)) {
+            self.status = e.to_string();
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.exit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::Right | KeyCode::PageDown | KeyCode::Char('n')
+                if self.page < self.total_pages =>
+            {
+                self.page += 1;
+                self.table.select(Some(0));
+                self.refresh();
+            }
+            KeyCode::Left | KeyCode::PageUp | KeyCode::Char('p') if self.page > 1 => {
+                self.page -= 1;
+                self.table.select(Some(0));
+                self.refresh();
+            }
+            KeyCode::Char('/') | KeyCode::Char('f') => {
+                let none = String::new();
+                self.mode = Mode::Form(Form {
+                    kind: FormKind::Filter,
+                    fields: vec![
+                        FormField::new(
+                            "tui.field.id",
+                            self.criteria
+                                .id_client
+                                .map_or(none.clone(), |id| id.to_string()),
+                        ),
+                        FormField::new(
+                            "tui.field.active",
+                            self.criteria
+                                .client_active
+                                .map_or(none.clone(), |active| active.to_string()),
+                        ),
+                        FormField::new(
+                            "tui.field.name",
+                            self.criteria.client_name.clone().unwrap_or(none),
+                        ),
+                    ],
+                    focus: 0,
+                });
+            }
+            KeyCode::Char('c') => {
+                self.criteria = SearchCriteria::default();
+                self.page = 1;
+                self.refresh();
+            }
+            KeyCode::Char('a') => {
+                self.mode = Mode::Form(Form {
+                    kind: FormKind::Add,
+                    fields: vec![FormField::new("tui.field.name", String::new())],
+                    focus: 0,
+                });
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(client) = self.selected() {
+                    self.mode = Mode::Form(Form {
+                        fields: vec![
+                            FormField::new("tui.field.name", client.client_name.clone()),
+                            FormField::new("tui.field.active", client.client_active.to_string()),
+                        ],
+                        kind: FormKind::Edit(client),
+                        focus: 0,
+                    });
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(mut client) = self.selected() {
+                    match self.manager.drop(&mut client) {
+                        Ok(()) => self.status = tf("tui.dropped", &[("name", &client.client_name)]),
+                        Err(e) => self.status = e.to_string(),
+                    }
+                    self.refresh();
+                }
+            }
+            KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(client) = self.selected() {
+                    self.mode = Mode::ConfirmDelete(client);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_form_key(&mut self, mut form: Form, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                return;
+            }
+            KeyCode::Enter => {
+                self.submit(form);
+                return;
+            }
+            KeyCode::Tab | KeyCode::Down => form.focus = (form.focus + 1) % form.fields.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focus = (form.focus + form.fields.len() - 1) % form.fields.len()
+            }
+            KeyCode::Backspace => {
+                form.fields[form.focus].value.pop();
+            }
+            KeyCode::Char(c) => form.fields[form.focus].value.push(c),
+            _ => {}
+        }
+        self.mode = Mode::Form(form);
+    }
+
+    fn submit(&mut self, form: Form) {
+        let value = |index: usize| form.fields[index].value.trim().to_string();
+
+        // Posibles repetidos del cliente agregado, se avisan en la barra de estado
+        let mut duplicates = Vec::new();
+        let result = match &form.kind {
+            FormKind::Filter => {
+                let criteria = parse_criteria(&value(0), &value(1), &value(2));
+                match criteria {
+                    Some(criteria) => {
+                        self.criteria = criteria;
+                        self.page = 1;
+                        self.table.select(Some(0));
+                        Ok(())
+                    }
+                    None => {
+                        self.status = t("tui.invalid_filter");
+                        self.mode = Mode::Form(form);
+                        return;
+                    }
+                }
+            }
+            FormKind::Add => {
+                let client = Client {
+                    id_client: None,
+                    client_active: true,
+                    client_name: value(0),
+                    ..Client::default()
+                };
+                duplicates = self.manager.likely_duplicates(&client).unwrap_or_default();
+                self.manager.add(&client)
+            }
+            FormKind::Edit(original) => match parse_bool(&value(1)) {
+                Some(active) => {
+                    let mut client = original.clone();
+                    client.client_name = value(0);
+                    client.client_active = active;
+                    self.manager.modify(&client)
+                }
+                None => {
+                    self.status = t("tui.invalid_active");
+                    self.mode = Mode::Form(form);
+                    return;
+                }
+            },
+        };
+
+        match result {
+            Ok(()) => {
+                self.status = t("tui.saved");
+                if !duplicates.is_empty() {
+                    let name = value(0);
+                    self.status.push('\n');
+                    self.status
+                        .push_str(&tf("client.duplicates", &[("name", &name)]));
+                    let names: Vec<&str> =
+                        duplicates.iter().map(|c| c.client_name.as_str()).collect();
+                    self.status.push_str(&format!(" {}", names.join(", ")));
+                }
+                self.mode = Mode::Browse;
+                self.refresh();
+            }
+            Err(e) => {
+                // El formulario se conserva para que el usuario corrija los datos
+                self.status = tf("common.error", &[("code", &e.code()), ("message", &e)]);
+                self.mode = Mode::Form(form);
+            }
+        }
+    }
+
+    fn handle_confirm_key(&mut self, client: Client, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('s') => {
+                match self.manager.delete(&client) {
+                    Ok(()) => self.status = tf("tui.deleted", &[("name", &client.client_name)]),
+                    Err(e) => self.status = e.to_string(),
+                }
+                self.mode = Mode::Browse;
+                self.refresh();
+            }
+            _ => self.mode = Mode::Browse,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        // Los conflictos de edicion muestran una linea por columna
+        let status_lines = self.status.lines().count().max(1) as u16;
+        let [main, status] =
+            Layout::vertical([Constraint::Min(5), Constraint::Length(status_lines + 2)])
+                .areas(frame.area());
+        let [table_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)])
+                .areas(main);
+
+        self.draw_table(frame, table_area);
+        self.draw_detail(frame, detail_area);
+
+        let status_bar =
+            Paragraph::new(self.status.as_str()).block(Block::default().borders(Borders::ALL));
+        frame.render_widget(status_bar, status);
+
+        match &self.mode {
+            Mode::Browse => {}
+            Mode::Form(form) => draw_form(frame, form),
+            Mode::ConfirmDelete(client) => {
+                let area = centered(frame.area(), 50, 5);
+                let text = tf("tui.confirm_delete", &[("name", &client.client_name)]);
+                let dialog = Paragraph::new(text).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(t("tui.confirm_title")),
+                );
+                frame.render_widget(Clear, area);
+                frame.render_widget(dialog, area);
+            }
+        }
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(vec![
+            Cell::from(t("tui.field.id")),
+            Cell::from(t("tui.field.name")),
+            Cell::from(t("tui.field.active")),
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows = self.clients.iter().map(|client| {
+            Row::new(vec![
+                Cell::from(client.id_client.map_or(String::new(), |id| id.to_string())),
+                Cell::from(client.client_name.clone()),
+                Cell::from(client.client_active.to_string()),
+            ])
+        });
+
+        let title = if self.total_pages > 0 {
+            tf(
+                "tui.table_title",
+                &[("page", &self.page), ("total", &self.total_pages)],
+            )
+        } else {
+            t("common.no_results")
+        };
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Min(20),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let none = t("common.none");
+        let mut lines = vec![tf(
+            "tui.filters",
+            &[
+                (
+                    "id",
+                    &self
+                        .criteria
+                        .id_client
+                        .map_or(none.clone(), |id| id.to_string()),
+                ),
+                (
+                    "active",
+                    &self
+                        .criteria
+                        .client_active
+                        .map_or(none.clone(), |active| active.to_string()),
+                ),
+                (
+                    "name",
+                    &self.criteria.client_name.clone().unwrap_or(none.clone()),
+                ),
+            ],
+        )];
+
+        if let Some(client) = self.selected() {
+            lines.push(String::new());
+            lines.push(format!(
+                "{}: {}",
+                t("tui.field.id"),
+                client.id_client.map_or(none.clone(), |id| id.to_string())
+            ));
+            lines.push(format!("{}: {}", t("tui.field.name"), client.client_name));
+            lines.push(format!(
+                "{}: {}",
+                t("tui.field.active"),
+                client.client_active
+            ));
+            lines.push(format!(
+                "{}: {}",
+                t("tui.field.rfc"),
+                client.client_rfc.as_ref().unwrap_or(&none)
+            ));
+            lines.push(format!(
+                "{}: {}",
+                t("tui.field.billing_address"),
+                client.billing_address.as_ref().unwrap_or(&none)
+            ));
+
+            let contacts = client
+                .id_client
+                .map(|id| self.manager.contacts_of(id).unwrap_or_default())
+                .unwrap_or_default();
+            lines.push(format!("{}:", t("tui.field.contacts")));
+            for contact in contacts {
+                let reach: Vec<&str> = [&contact.email, &contact.phone]
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                lines.push(format!("- {} {}", contact.contact_name, reach.join(" ")));
+            }
+        }
+
+        let detail = Paragraph::new(lines.join("\n")).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("tui.detail")),
+        );
+        frame.render_widget(detail, area);
+    }
+}
+
+fn draw_form(frame: &mut Frame, form: &Form) {
+    let title = match form.kind {
+        FormKind::Filter => t("tui.filter_title"),
+        FormKind::Add => t("tui.add_title"),
+        FormKind::Edit(_) => t("tui.edit_title"),
+    };
+
+    let lines: Vec<String> = form
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let cursor = if index == form.focus { ">" } else { " " };
+            format!("{} {}: {}", cursor, field.label, field.value)
+        })
+        .collect();
+
+    let area = centered(frame.area(), 60, form.fields.len() as u16 + 4);
+    let body = format!("{}\n\n{}", lines.join("\n"), t("tui.form_help"));
+    let dialog = Paragraph::new(body).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(Clear, area);
+    frame.render_widget(dialog, area);
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "y" | "si" | "sí" | "s" => Some(true),
+        "false" | "0" | "no" | "n" => Some(false),
+        _ => None,
+    }
+}
+
+// Los campos vacios no filtran; devuelve None si algun valor no se puede convertir
+fn parse_criteria(id: &str, active: &str, name: &str) -> Option<SearchCriteria> {
+    let id_client = match id {
+        "" => None,
+        id => Some(id.parse().ok()?),
+    };
+
+    let client_active = match active {
+        "" => None,
+        active => Some(parse_bool(active)?),
+    };
+
+    let client_name = match name {
+        "" => None,
+        name => Some(name.to_string()),
+    };
+
+    Some(SearchCriteria {
+        id_client,
+        client_active,
+        client_name,
+        ..SearchCriteria::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bool_accepts_spanish_and_english_answers() {
+        for value in ["true", "1", "yes", "Y", "si", "Sí", "S"] {
+            assert_eq!(parse_bool(value), Some(true), "{}", value);
+        }
+        for value in ["false", "0", "NO", "n"] {
+            assert_eq!(parse_bool(value), Some(false), "{}", value);
+        }
+        for value in ["", "2", "verdadero", " yes"] {
+            assert_eq!(parse_bool(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn empty_fields_do_not_filter() {
+        assert_eq!(parse_criteria("", "", ""), Some(SearchCriteria::default()));
+        assert_eq!(
+            parse_criteria("7", "no", "Agua"),
+            Some(SearchCriteria {
+                id_client: Some(7),
+                client_active: Some(false),
+                client_name: Some("Agua".to_string()),
+                ..SearchCriteria::default()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_values_reject_the_whole_criteria() {
+        assert_eq!(parse_criteria("abc", "", ""), None);
+        assert_eq!(parse_criteria("-1", "", ""), None);
+        assert_eq!(parse_criteria("1.5", "", "Agua"), None);
+        assert_eq!(parse_criteria("", "quizas", ""), None);
+        assert_eq!(parse_criteria("3", "tal vez", "Agua"), None);
+    }
+}
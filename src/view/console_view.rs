@@ -1,73 +1,124 @@
-use std::process::Command;
-use std::{io::stdin, str::FromStr};
+use std::io::{BufRead, ErrorKind, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::error::{Error, Result};
+use crate::i18n::{t, tf};
+
+static HIDE_PASSWORDS: AtomicBool = AtomicBool::new(false);
+
+// Con la entrada en una terminal las contraseñas se leen sin eco; con un
+// guion (pruebas, entrada redirigida) se leen como cualquier otra linea
+pub fn hide_passwords(enabled: bool) {
+    HIDE_PASSWORDS.store(enabled, Ordering::Relaxed);
+}
+
+// Quita del menu las opciones que el usuario no puede usar; cada linea
+// empieza con su numero ("2) Agregar cliente")
+pub fn visible_options(options: &str, allowed: impl Fn(u8) -> bool) -> String {
+    options
+        .lines()
+        .filter(|line| {
+            line.split_once(')')
+                .and_then(|(number, _)| number.trim().parse::<u8>().ok())
+                .is_none_or(&allowed)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Las vistas leen y escriben sobre los flujos que reciben, asi las sesiones
+// pueden venir de stdin/stdout o de un guion en las pruebas
 #[allow(dead_code)]
-pub trait ConsoleView {
-    fn menu(&mut self);
+pub trait ConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()>;
+    fn input(&mut self) -> &mut R;
+    fn output(&mut self) -> &mut W;
 
-    fn capture_string(title: &str) -> String {
-        loop {
-            let mut input = String::new();
-            println!("{}", title);
+    fn print(&mut self, text: &str) -> Result<()> {
+        writeln!(self.output(), "{}", text)?;
+        Ok(())
+    }
 
-            if let Err(e) = stdin().read_line(&mut input) {
-                println!("Error: {}", e);
-                continue;
-            }
+    fn read_line(&mut self) -> Result<String> {
+        self.output().flush()?;
+        let mut input = String::new();
+        if self.input().read_line(&mut input)? == 0 {
+            return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(input.trim().to_string())
+    }
+
+    fn capture_string(&mut self, title: &str) -> Result<String> {
+        self.print(title)?;
+        self.read_line()
+    }
 
-            return input.trim().to_string();
+    fn capture_password(&mut self, title: &str) -> Result<String> {
+        if !HIDE_PASSWORDS.load(Ordering::Relaxed) {
+            return self.capture_string(title);
         }
+        self.print(title)?;
+        self.output().flush()?;
+        Ok(rpassword::read_password()?.trim().to_string())
     }
 
-    fn capture_atributte<T>(title: &str, type_expect: &str) -> T
+    fn capture_atributte<T>(&mut self, title: &str, type_expect: &str) -> Result<T>
     where
         T: FromStr,
     {
         loop {
-            let mut input = String::new();
-            println!("{}", title);
+            self.print(title)?;
 
-            if let Err(e) = stdin().read_line(&mut input) {
-                println!("Error: {}", e);
-                continue;
-            }
-
-            let input = input.trim();
+            let input = self.read_line()?;
             match input.parse::<T>() {
-                Ok(input) => return input,
+                Ok(input) => return Ok(input),
                 Err(_) => {
-                    println!("No se pudo convertir \"{}\" en \"{}\"", input, type_expect);
+                    let message = tf(
+                        "common.parse_error",
+                        &[("input", &input), ("type", &type_expect)],
+                    );
+                    self.print(&message)?;
                     continue;
                 }
             };
         }
     }
 
-    fn capture_option_attribute<T>(title: &str, type_expected: &str) -> Option<T>
+    fn capture_option_attribute<T>(&mut self, title: &str, type_expected: &str) -> Result<Option<T>>
     where
         T: FromStr,
     {
         loop {
-            println!("{title}");
-            match Self::capture_atributte::<u8>("1) Yes\n2) No", "u8") {
+            self.print(title)?;
+            match self.capture_atributte::<u8>(&t("common.yes_no"), "u8")? {
                 1 => {
-                    return Some(Self::capture_atributte::<T>(
-                        "Ingresa el dato:",
-                        &type_expected,
-                    ))
+                    return Ok(Some(self.capture_atributte::<T>(
+                        &t("common.enter_value"),
+                        type_expected,
+                    )?))
                 }
-                2 => return None,
+                2 => return Ok(None),
                 _ => {
-                    println!("Invalid option");
+                    self.print(&t("common.invalid_option"))?;
                     continue;
                 }
             };
         }
     }
 
-    fn clear_linux_console() {
+    fn show_error(&mut self, e: &Error) -> Result<()> {
+        let message = tf("common.error", &[("code", &e.code()), ("message", e)]);
+        self.print(&message)
+    }
+
+    fn clear_linux_console(&mut self) {
         /*
-        if let Err(e) = Command::new("clear").status() {
+        if let Err(e) = std::process::Command::new("clear").status() {
             println!("Error al limpiar consola: {}\n\n\n\n\n", e);
         }
         */
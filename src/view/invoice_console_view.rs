@@ -0,0 +1,249 @@
+use std::io::{BufRead, Write};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    config::Config,
+    data::model::{invoice::Invoice, role::Permission, sample::Sample},
+    data::repo::{conector::Connector, sample_repo::SampleSearchCriteria},
+    error::{Error, Result},
+    i18n::{t, tf, Message},
+    logic::invoice::status_label,
+    logic::invoice_manager::InvoiceManager,
+    logic::sample_manager::SampleManager,
+    logic::session::Session,
+};
+
+use super::console_view::{visible_options, ConsoleView};
+
+pub struct InvoiceConsoleView<R, W> {
+    manager: InvoiceManager,
+    samples: SampleManager<SampleSearchCriteria>,
+    session: Session,
+    input: R,
+    output: W,
+}
+
+impl<R, W> InvoiceConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: InvoiceManager::new(
+                Connector::new(config),
+                config.page_size,
+                config.tax_percent,
+            ),
+            samples: SampleManager::new(
+                Connector::new(config),
+                config.page_size,
+                config.control_number_pattern.clone(),
+            ),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session.clone());
+        self.samples = self.samples.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        match option {
+            2 | 4..=6 => self.session.can(Permission::ManageInvoices),
+            _ => true,
+        }
+    }
+
+    fn print_samples(&mut self, samples: &[Sample]) -> Result<()> {
+        if samples.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for sample in samples {
+            let names: Vec<String> = match self
+                .manager
+                .analyses_of(sample.id_sample.unwrap_or_default())
+            {
+                Ok(analyses) => analyses
+                    .into_iter()
+                    .map(|analysis| analysis.analysis_name)
+                    .collect(),
+                Err(e) => return self.show_error(&e),
+            };
+            let row = tf(
+                "invoice.sample_row",
+                &[
+                    ("number", &sample.control_number),
+                    ("reception", &sample.reception),
+                    ("analyses", &names.join(", ")),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn billable(&mut self) -> Result<()> {
+        let id_client = self.capture_atributte::<u32>(&t("invoice.client_id"), "u32")?;
+        match self.manager.billable(id_client) {
+            Ok(samples) => self.print_samples(&samples),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    // Sin numeros de control se facturan todas las muestras pendientes
+    fn draft(&mut self) -> Result<()> {
+        let id_client = self.capture_atributte::<u32>(&t("invoice.client_id"), "u32")?;
+        let numbers = self.capture_string(&t("invoice.control_numbers"))?;
+        let found: Result<Vec<u32>> = if numbers.trim().is_empty() {
+            self.manager.billable(id_client).map(|samples| {
+                samples
+                    .into_iter()
+                    .filter_map(|sample| sample.id_sample)
+                    .collect()
+            })
+        } else {
+            numbers
+                .split(',')
+                .map(str::trim)
+                .filter(|number| !number.is_empty())
+                .map(
+                    |number| match self.samples.find_by_control_number(number)? {
+                        Some(sample) => Ok(sample.id_sample.unwrap_or_default()),
+                        None => Err(Error::InvalidField {
+                            entity: "Invoice",
+                            errors: vec![
+                                Message::new("invoice.error.control_number").arg("number", number)
+                            ],
+                        }),
+                    },
+                )
+                .collect()
+        };
+        let today = Local::now().date_naive();
+        let document = found
+            .and_then(|id_samples| self.manager.draft(id_client, &id_samples, today))
+            .and_then(|id| self.manager.document(id));
+        match document {
+            Ok(document) => {
+                let id = document.invoice.id_invoice.unwrap_or_default();
+                self.print(&tf("invoice.added", &[("id", &id)]))?;
+                self.print(&document.render())
+            }
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn show(&mut self) -> Result<()> {
+        let id = self.capture_atributte::<u32>(&t("invoice.invoice_id"), "u32")?;
+        match self.manager.document(id) {
+            Ok(document) => self.print(&document.render()),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn change_status<F>(&mut self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut InvoiceManager, u32, NaiveDate) -> Result<Invoice>,
+    {
+        let id = self.capture_atributte::<u32>(&t("invoice.invoice_id"), "u32")?;
+        let date = self.capture_atributte::<NaiveDate>(&t("invoice.date"), "YYYY-MM-DD")?;
+        match change(&mut self.manager, id, date) {
+            Ok(invoice) => self.print(&tf(
+                "invoice.status_changed",
+                &[
+                    ("id", &id),
+                    ("number", &Self::number_label(&invoice)),
+                    ("status", &status_label(invoice.invoice_status)),
+                ],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn number_label(invoice: &Invoice) -> String {
+        invoice
+            .invoice_number
+            .clone()
+            .unwrap_or_else(|| t("invoice.no_number"))
+    }
+
+    fn list(&mut self) -> Result<()> {
+        let id_client = self.capture_option_attribute::<u32>(&t("invoice.client_filter"), "u32")?;
+        let invoices = match self.manager.invoices(id_client) {
+            Ok(invoices) => invoices,
+            Err(e) => return self.show_error(&e),
+        };
+        if invoices.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for invoice in invoices {
+            let row = tf(
+                "invoice.row",
+                &[
+                    ("id", &invoice.id_invoice.unwrap_or_default()),
+                    ("number", &Self::number_label(&invoice)),
+                    ("client", &invoice.id_client),
+                    ("status", &status_label(invoice.invoice_status)),
+                    ("total", &invoice.total),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn export(&mut self) -> Result<()> {
+        let id_client = self.capture_option_attribute::<u32>(&t("invoice.client_filter"), "u32")?;
+        let path = self.capture_string(&t("invoice.export_path"))?;
+        match self.manager.export(path.trim(), id_client) {
+            Ok(total) => self.print(&tf(
+                "invoice.exported",
+                &[("total", &total), ("path", &path.trim())],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for InvoiceConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("invoice.menu.title"))?;
+            let options = visible_options(&t("invoice.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.billable()?,
+                2 => self.draft()?,
+                3 => self.show()?,
+                4 => self.change_status(InvoiceManager::issue)?,
+                5 => self.change_status(InvoiceManager::pay)?,
+                6 => self.change_status(InvoiceManager::cancel)?,
+                7 => self.list()?,
+                8 => self.export()?,
+                9 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    config::Config,
+    data::model::signatary::Signatary,
+    data::repo::conector::Connector,
+    error::Result,
+    i18n::{t, tf},
+    logic::session::Session,
+    logic::user_manager::UserManager,
+};
+
+use super::console_view::ConsoleView;
+
+// Intentos de inicio de sesion antes de salir
+const ATTEMPTS: usize = 3;
+
+pub struct LoginConsoleView<R, W> {
+    manager: UserManager,
+    input: R,
+    output: W,
+}
+
+impl<R, W> LoginConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: UserManager::new(Connector::new(config), config.page_size),
+            input,
+            output,
+        }
+    }
+
+    // Cuenta del primer administrador, con la que despues se inicia sesion
+    fn setup(&mut self) -> Result<()> {
+        self.print(&t("login.setup"))?;
+        let mut attempt = 1;
+        loop {
+            let signatary = Signatary {
+                first_name: self.capture_string(&t("user.first_name"))?,
+                father_last_name: self.capture_string(&t("user.father_last_name"))?,
+                mother_last_name: self.capture_string(&t("user.mother_last_name"))?,
+                user: self.capture_string(&t("user.user"))?,
+                passwd: self.capture_password(&t("user.passwd"))?,
+                ..Signatary::default()
+            };
+            match self.manager.add_first_admin(&signatary) {
+                Ok(_) => return self.print(&tf("user.added", &[("user", &signatary.user.trim())])),
+                Err(e) if attempt >= ATTEMPTS => return Err(e),
+                Err(e) => self.show_error(&e)?,
+            }
+            attempt += 1;
+        }
+    }
+
+    // Sesion del usuario; mientras no haya administradores primero se da de
+    // alta uno
+    pub fn login(&mut self) -> Result<Session> {
+        if self.manager.needs_setup()? {
+            self.setup()?;
+        }
+
+        self.print(&t("login.title"))?;
+        let mut attempt = 1;
+        loop {
+            let user = self.capture_string(&t("login.user"))?;
+            let passwd = self.capture_password(&t("login.passwd"))?;
+            match self.manager.login(&user, &passwd) {
+                Ok(session) => {
+                    let role = t(&format!("role.{}", session.role.as_str()));
+                    self.print(&tf(
+                        "login.welcome",
+                        &[("user", &session.user), ("role", &role)],
+                    ))?;
+                    return Ok(session);
+                }
+                Err(e) if attempt >= ATTEMPTS => return Err(e),
+                Err(e) => self.show_error(&e)?,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for LoginConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        self.login().map(|_| ())
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    config::Config, data::model::role::Permission, error::Result, i18n::t, logic::session::Session,
+};
+
+use super::client_console_view::ClientConsoleView;
+use super::console_view::{visible_options, ConsoleView};
+use super::invoice_console_view::InvoiceConsoleView;
+use super::metrics_console_view::MetricsConsoleView;
+use super::quotation_console_view::QuotationConsoleView;
+use super::sample_console_view::SampleConsoleView;
+use super::schedule_console_view::ScheduleConsoleView;
+use super::search_console_view::SearchConsoleView;
+use super::site_console_view::SiteConsoleView;
+use super::user_console_view::UserConsoleView;
+
+// Menu principal: cada submenu recibe prestados los mismos flujos
+pub struct MainConsoleView<R, W> {
+    config: Config,
+    session: Session,
+    input: R,
+    output: W,
+}
+
+impl<R, W> MainConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            config: config.clone(),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    // Cada submenu recibe la sesion para ocultar lo que el usuario no puede hacer
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        option != 7 || self.session.can(Permission::ManageUsers)
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for MainConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("main.menu.title"))?;
+            let options = visible_options(&t("main.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            let session = self.session.clone();
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => ClientConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                2 => SiteConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                // Busqueda y metricas solo consultan, no dependen del rol
+                3 => SearchConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .menu()?,
+                4 => return Ok(()),
+                5 => SampleConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                6 => MetricsConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .menu()?,
+                7 => UserConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                8 => ScheduleConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                9 => QuotationConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                10 => InvoiceConsoleView::new(&self.config, &mut self.input, &mut self.output)
+                    .with_session(session)
+                    .menu()?,
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
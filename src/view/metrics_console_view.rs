@@ -0,0 +1,135 @@
+use std::io::{BufRead, Write};
+
+use chrono::Local;
+
+use crate::{
+    config::Config,
+    data::repo::conector::Connector,
+    error::Result,
+    i18n::{t, tf},
+    logic::metrics::{MetricsManager, TurnaroundStats},
+};
+
+use super::console_view::ConsoleView;
+
+pub struct MetricsConsoleView<R, W> {
+    manager: MetricsManager,
+    input: R,
+    output: W,
+}
+
+impl<R, W> MetricsConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: MetricsManager::new(Connector::new(config), config.page_size, config.sla_days),
+            input,
+            output,
+        }
+    }
+
+    fn print_turnaround(&mut self, title: &str, stats: &[TurnaroundStats]) -> Result<()> {
+        self.print(title)?;
+        if stats.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for group in stats {
+            let name = group
+                .name
+                .clone()
+                .unwrap_or_else(|| t("metrics.unassigned"));
+            let row = tf(
+                "metrics.turnaround_row",
+                &[
+                    ("name", &name),
+                    ("results", &group.results),
+                    ("mean", &format!("{:.1}", group.mean_days)),
+                    ("min", &group.min_days),
+                    ("max", &group.max_days),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn dashboard(&mut self) -> Result<()> {
+        let dashboard = match self.manager.dashboard(Local::now().date_naive()) {
+            Ok(dashboard) => dashboard,
+            Err(e) => return self.show_error(&e),
+        };
+
+        self.print_turnaround(&t("metrics.per_analysis"), &dashboard.per_analysis)?;
+        self.print_turnaround(&t("metrics.per_signatary"), &dashboard.per_signatary)?;
+        self.print_turnaround(&t("metrics.per_client"), &dashboard.per_client)?;
+
+        self.print(&t("metrics.backlog"))?;
+        for count in &dashboard.backlog {
+            let status = t(&format!("sample.status.{}", count.status.as_str()));
+            let row = tf(
+                "metrics.backlog_row",
+                &[("status", &status), ("samples", &count.samples)],
+            );
+            self.print(&row)?;
+        }
+
+        self.print(&tf(
+            "metrics.overdue",
+            &[
+                ("total", &dashboard.overdue.len()),
+                ("days", &dashboard.sla_days),
+            ],
+        ))?;
+        for sample in &dashboard.overdue {
+            let row = tf(
+                "metrics.overdue_row",
+                &[
+                    ("number", &sample.control_number),
+                    ("reception", &sample.reception),
+                    ("late", &sample.days_late),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn export_json(&mut self) -> Result<()> {
+        let path = self.capture_string(&t("metrics.export_path"))?;
+        match self.manager.export_json(Local::now().date_naive(), &path) {
+            Ok(_) => self.print(&tf("metrics.exported", &[("path", &path)])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for MetricsConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("metrics.menu.title"))?;
+            self.print(&t("metrics.menu.options"))?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                1 => self.dashboard()?,
+                2 => self.export_json()?,
+                3 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
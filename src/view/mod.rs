@@ -1,2 +1,14 @@
 pub mod client_console_view;
+#[cfg(feature = "tui")]
+pub mod client_tui_view;
 pub mod console_view;
+pub mod invoice_console_view;
+pub mod login_console_view;
+pub mod main_console_view;
+pub mod metrics_console_view;
+pub mod quotation_console_view;
+pub mod sample_console_view;
+pub mod schedule_console_view;
+pub mod search_console_view;
+pub mod site_console_view;
+pub mod user_console_view;
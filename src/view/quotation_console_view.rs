@@ -0,0 +1,361 @@
+use std::io::{BufRead, Write};
+
+use chrono::{Days, Local, NaiveDate};
+
+use crate::{
+    config::Config,
+    data::model::{
+        analysis_package::AnalysisPackage, money::Money, quotation::Quotation, role::Permission,
+    },
+    data::repo::{conector::Connector, site_repo::SiteSearchCriteria},
+    error::Result,
+    i18n::{t, tf},
+    logic::quotation::{QuoteLine, QuotedItem, VALIDITY_DAYS},
+    logic::quotation_manager::QuotationManager,
+    logic::session::Session,
+    logic::site_manager::SiteManager,
+};
+
+use super::console_view::{visible_options, ConsoleView};
+
+pub struct QuotationConsoleView<R, W> {
+    manager: QuotationManager,
+    sites: SiteManager<SiteSearchCriteria>,
+    session: Session,
+    input: R,
+    output: W,
+}
+
+impl<R, W> QuotationConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: QuotationManager::new(
+                Connector::new(config),
+                config.page_size,
+                config.control_number_pattern.clone(),
+                config.tax_percent,
+            ),
+            sites: SiteManager::new(Connector::new(config), config.page_size),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session.clone());
+        self.sites = self.sites.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        match option {
+            2 | 3 | 5 | 6 => self.session.can(Permission::ManageQuotations),
+            8 => self.session.can(Permission::RegisterSamples),
+            _ => true,
+        }
+    }
+
+    fn price_label(price: Option<Money>) -> String {
+        price.map_or_else(|| t("quotation.no_price"), |price| price.to_string())
+    }
+
+    fn catalog(&mut self) -> Result<()> {
+        let analyses = match self.manager.price_catalog() {
+            Ok(analyses) => analyses,
+            Err(e) => return self.show_error(&e),
+        };
+        if analyses.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for analysis in analyses {
+            let row = tf(
+                "quotation.catalog_row",
+                &[
+                    ("id", &analysis.id_analysis.unwrap_or_default()),
+                    ("name", &analysis.analysis_name),
+                    ("price", &Self::price_label(analysis.price)),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn set_price(&mut self) -> Result<()> {
+        let name = self.capture_string(&t("quotation.analysis"))?;
+        let analysis = match self.sites.find_analysis(&name) {
+            Ok(Some(analysis)) => analysis,
+            Ok(None) => {
+                return self.print(&tf("quotation.unknown_analysis", &[("name", &name.trim())]))
+            }
+            Err(e) => return self.show_error(&e),
+        };
+        let price = self.capture_option_attribute::<Money>(&t("quotation.price"), "$0.00")?;
+        match self
+            .manager
+            .set_price(analysis.id_analysis.unwrap_or_default(), price)
+        {
+            Ok(analysis) => self.print(&tf(
+                "quotation.price_set",
+                &[
+                    ("name", &analysis.analysis_name),
+                    ("price", &Self::price_label(analysis.price)),
+                ],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn add_package(&mut self) -> Result<()> {
+        let package_name = self.capture_string(&t("quotation.package_name"))?;
+        let package_price =
+            self.capture_option_attribute::<Money>(&t("quotation.package_price"), "$0.00")?;
+        let names = self.capture_string(&t("quotation.package_analyses"))?;
+        let mut analyses = Vec::new();
+        for name in names.split(',').filter(|name| !name.trim().is_empty()) {
+            match self.sites.find_analysis(name) {
+                Ok(Some(analysis)) => analyses.extend(analysis.id_analysis),
+                Ok(None) => {
+                    let message = tf("quotation.unknown_analysis", &[("name", &name.trim())]);
+                    return self.print(&message);
+                }
+                Err(e) => return self.show_error(&e),
+            }
+        }
+
+        let package = AnalysisPackage {
+            package_active: true,
+            package_name: package_name.trim().to_string(),
+            package_price,
+            ..AnalysisPackage::default()
+        };
+        match self.manager.add_package(&package, &analyses) {
+            Ok(id) => self.print(&tf("quotation.package_added", &[("id", &id)])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn packages(&mut self) -> Result<()> {
+        let packages = match self.manager.packages() {
+            Ok(packages) => packages,
+            Err(e) => return self.show_error(&e),
+        };
+        if packages.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for package in packages {
+            let id_package = package.id_package.unwrap_or_default();
+            let found = self.manager.package_analyses(id_package).map(|analyses| {
+                let names: Vec<String> = analyses
+                    .into_iter()
+                    .map(|analysis| analysis.analysis_name)
+                    .collect();
+                (self.manager.package_price(&package).ok(), names)
+            });
+            let (price, names) = match found {
+                Ok(found) => found,
+                Err(e) => return self.show_error(&e),
+            };
+            let row = tf(
+                "quotation.package_row",
+                &[
+                    ("id", &id_package),
+                    ("name", &package.package_name),
+                    ("price", &Self::price_label(price)),
+                    ("analyses", &names.join(", ")),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn set_discount(&mut self) -> Result<()> {
+        let id_client = self.capture_atributte::<u32>(&t("quotation.client_id"), "u32")?;
+        let discount = self.capture_atributte::<f64>(&t("quotation.discount"), "f64")?;
+        match self.manager.set_discount(id_client, discount) {
+            Ok(client) => self.print(&tf(
+                "quotation.discount_set",
+                &[
+                    ("name", &client.client_name),
+                    ("percent", &client.discount_percent),
+                ],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    // Partidas hasta que se deja el nombre vacio; primero se busca un paquete
+    // y despues un analisis con ese nombre
+    fn capture_lines(&mut self) -> Result<Option<Vec<QuoteLine>>> {
+        let mut lines = Vec::new();
+        loop {
+            let name = self.capture_string(&t("quotation.item"))?;
+            if name.trim().is_empty() {
+                return Ok(Some(lines));
+            }
+            let found = self.manager.find_package(&name).and_then(|package| {
+                match package.and_then(|package| package.id_package) {
+                    Some(id_package) => Ok(Some(QuotedItem::Package(id_package))),
+                    None => Ok(self
+                        .sites
+                        .find_analysis(&name)?
+                        .and_then(|analysis| analysis.id_analysis)
+                        .map(QuotedItem::Analysis)),
+                }
+            });
+            let item = match found {
+                Ok(Some(item)) => item,
+                Ok(None) => {
+                    self.print(&tf("quotation.unknown_item", &[("name", &name.trim())]))?;
+                    continue;
+                }
+                Err(e) => {
+                    self.show_error(&e)?;
+                    return Ok(None);
+                }
+            };
+            let samples = self.capture_atributte::<u32>(&t("quotation.samples"), "u32")?;
+            lines.push(QuoteLine { item, samples });
+        }
+    }
+
+    fn quote(&mut self) -> Result<()> {
+        let id_client = self.capture_atributte::<u32>(&t("quotation.client_id"), "u32")?;
+        let site_key = self.capture_string(&t("quotation.site"))?;
+        let id_site = if site_key.trim().is_empty() {
+            None
+        } else {
+            match self.sites.find_by_key(&site_key) {
+                Ok(Some(site)) => site.id_site,
+                Ok(None) => return self.print(&t("common.no_results")),
+                Err(e) => return self.show_error(&e),
+            }
+        };
+        let project = self.capture_string(&t("quotation.project"))?;
+        let Some(lines) = self.capture_lines()? else {
+            return Ok(());
+        };
+
+        let today = Local::now().date_naive();
+        let quotation = Quotation {
+            quotation_active: true,
+            id_client,
+            id_site,
+            project: project.trim().to_string(),
+            issued_on: today,
+            valid_until: today
+                .checked_add_days(Days::new(VALIDITY_DAYS))
+                .unwrap_or(today),
+            ..Quotation::default()
+        };
+        let document = self
+            .manager
+            .quote(&quotation, &lines)
+            .and_then(|id| self.manager.document(id));
+        match document {
+            Ok(document) => {
+                let id = document.quotation.id_quotation.unwrap_or_default();
+                self.print(&tf("quotation.added", &[("id", &id)]))?;
+                self.print(&document.render())
+            }
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn show(&mut self) -> Result<()> {
+        let id = self.capture_atributte::<u32>(&t("quotation.quotation_id"), "u32")?;
+        let document = match self.manager.document(id) {
+            Ok(document) => document,
+            Err(e) => return self.show_error(&e),
+        };
+        let text = document.render();
+        self.print(&text)?;
+
+        let path = self.capture_string(&t("quotation.path"))?;
+        if path.trim().is_empty() {
+            return Ok(());
+        }
+        match std::fs::write(path.trim(), text) {
+            Ok(()) => self.print(&tf("quotation.saved", &[("path", &path.trim())])),
+            Err(e) => self.show_error(&e.into()),
+        }
+    }
+
+    fn convert(&mut self) -> Result<()> {
+        let id = self.capture_atributte::<u32>(&t("quotation.quotation_id"), "u32")?;
+        let reception =
+            self.capture_atributte::<NaiveDate>(&t("quotation.reception"), "YYYY-MM-DD")?;
+        let samples = match self.manager.convert(id, reception) {
+            Ok(samples) => samples,
+            Err(e) => return self.show_error(&e),
+        };
+        self.print(&tf(
+            "quotation.converted",
+            &[("id", &id), ("total", &samples.len())],
+        ))?;
+        for sample in samples {
+            let names: Vec<String> = match self
+                .manager
+                .expected_analyses(sample.id_sample.unwrap_or_default())
+            {
+                Ok(analyses) => analyses
+                    .into_iter()
+                    .map(|analysis| analysis.analysis_name)
+                    .collect(),
+                Err(e) => return self.show_error(&e),
+            };
+            let row = tf(
+                "quotation.sample_row",
+                &[
+                    ("number", &sample.control_number),
+                    ("analyses", &names.join(", ")),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for QuotationConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("quotation.menu.title"))?;
+            let options = visible_options(&t("quotation.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.catalog()?,
+                2 => self.set_price()?,
+                3 => self.add_package()?,
+                4 => self.packages()?,
+                5 => self.set_discount()?,
+                6 => self.quote()?,
+                7 => self.show()?,
+                8 => self.convert()?,
+                9 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
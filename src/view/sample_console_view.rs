@@ -0,0 +1,306 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+use crate::{
+    config::Config,
+    data::model::{role::Permission, sample_status::SampleStatus},
+    data::repo::{conector::Connector, sample_repo::SampleSearchCriteria},
+    error::{Error, Result},
+    i18n::{t, tf},
+    logic::label_manager::LabelManager,
+    logic::labels::LabelFormat,
+    logic::result_manager::ResultManager,
+    logic::sample_manager::SampleManager,
+    logic::session::Session,
+    logic::signature::SignatureStatus,
+};
+
+use super::console_view::{visible_options, ConsoleView};
+
+pub struct SampleConsoleView<R, W> {
+    manager: SampleManager<SampleSearchCriteria>,
+    results: ResultManager,
+    labels: LabelManager,
+    session: Session,
+    input: R,
+    output: W,
+}
+
+impl<R, W> SampleConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: SampleManager::new(
+                Connector::new(config),
+                config.page_size,
+                config.control_number_pattern.clone(),
+            ),
+            results: ResultManager::new(Connector::new(config), config.page_size),
+            labels: LabelManager::new(Connector::new(config), config.page_size),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session.clone());
+        self.results = self.results.with_session(session.clone());
+        self.labels = self.labels.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        match option {
+            2 => {
+                self.session.can(Permission::RecordResults)
+                    || self.session.can(Permission::ReviewResults)
+            }
+            5 => self.session.can(Permission::ReviewResults),
+            7 => self.session.can(Permission::RegisterSamples),
+            _ => true,
+        }
+    }
+
+    fn status_label(status: SampleStatus) -> String {
+        t(&format!("sample.status.{}", status.as_str()))
+    }
+
+    fn board(&mut self) -> Result<()> {
+        let board = match self.manager.board() {
+            Ok(board) => board,
+            Err(e) => return self.show_error(&e),
+        };
+
+        for (status, samples) in board {
+            let header = tf(
+                "sample.board_group",
+                &[
+                    ("status", &Self::status_label(status)),
+                    ("total", &samples.len()),
+                ],
+            );
+            self.print(&header)?;
+            for sample in samples {
+                let row = tf(
+                    "sample.board_row",
+                    &[
+                        ("number", &sample.control_number),
+                        ("project", &sample.project),
+                        ("reception", &sample.reception),
+                    ],
+                );
+                self.print(&row)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn change_status(&mut self) -> Result<()> {
+        let control_number = self.capture_string(&t("sample.control_number"))?;
+        let sample = match self.manager.find_by_control_number(&control_number) {
+            Ok(Some(sample)) => sample,
+            Ok(None) => return self.print(&t("common.no_results")),
+            Err(e) => return self.show_error(&e),
+        };
+
+        let current = tf(
+            "sample.current_status",
+            &[("status", &Self::status_label(sample.sample_status))],
+        );
+        self.print(&current)?;
+        let allowed: Vec<SampleStatus> = SampleStatus::ALL
+            .into_iter()
+            .filter(|to| sample.sample_status.can_change_to(*to))
+            .collect();
+        if allowed.is_empty() {
+            return self.print(&t("sample.no_transitions"));
+        }
+        for (number, status) in (1..).zip(&allowed) {
+            self.print(&format!("{}) {}", number, Self::status_label(*status)))?;
+        }
+
+        let option = self.capture_atributte::<usize>(&t("common.select_option"), "usize")?;
+        let Some(to) = option.checked_sub(1).and_then(|i| allowed.get(i)).copied() else {
+            return self.print(&t("common.invalid_option"));
+        };
+
+        let id_sample = sample.id_sample.ok_or(Error::NotFound {
+            entity: "Sample",
+            id: None,
+        })?;
+        match self.manager.transition(id_sample, to) {
+            Ok(sample) => self.print(&tf(
+                "sample.status_changed",
+                &[
+                    ("number", &sample.control_number),
+                    ("status", &Self::status_label(sample.sample_status)),
+                ],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    // Se vuelve a pedir usuario y contraseña aunque ya haya sesion
+    fn sign_result(&mut self) -> Result<()> {
+        let id: u32 = self.capture_atributte(&t("sample.sign_result_id"), "u32")?;
+        let user = self.capture_string(&t("login.user"))?;
+        let passwd = self.capture_password(&t("login.passwd"))?;
+        match self.results.sign(id, &user, &passwd) {
+            Ok(signature) => self.print(&tf(
+                "sample.signed",
+                &[
+                    ("id", &signature.id_sample_result),
+                    ("date", &signature.signed_at.format("%Y-%m-%d %H:%M:%S UTC")),
+                    ("hash", &signature.payload_hash),
+                ],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn verify_signatures(&mut self) -> Result<()> {
+        let checks = match self.results.verify_all() {
+            Ok(checks) => checks,
+            Err(e) => return self.show_error(&e),
+        };
+        let problems: Vec<_> = checks
+            .iter()
+            .filter(|check| check.status != SignatureStatus::Valid)
+            .collect();
+        self.print(&tf(
+            "sample.signatures_checked",
+            &[("total", &checks.len()), ("problems", &problems.len())],
+        ))?;
+        for check in problems {
+            let row = tf(
+                "sample.signature_problem",
+                &[
+                    ("id", &check.signature.id_sample_result),
+                    ("date", &check.signature.signed_at.format("%Y-%m-%d")),
+                    (
+                        "status",
+                        &t(&format!("signature.status.{}", check.status.as_str())),
+                    ),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    // Sin numeros de control se imprime el lote recibido en una fecha
+    fn print_labels(&mut self) -> Result<()> {
+        let numbers = self.capture_string(&t("label.control_numbers"))?;
+        let numbers: Vec<String> = numbers
+            .split(',')
+            .map(str::trim)
+            .filter(|number| !number.is_empty())
+            .map(str::to_string)
+            .collect();
+        let labels = if numbers.is_empty() {
+            let reception: NaiveDate =
+                self.capture_atributte(&t("label.reception"), "YYYY-MM-DD")?;
+            self.labels.labels_received_on(reception)
+        } else {
+            self.labels.labels_for(&numbers)
+        };
+        let labels = match labels {
+            Ok(labels) => labels,
+            Err(e) => return self.show_error(&e),
+        };
+
+        let path = PathBuf::from(self.capture_string(&t("label.path"))?);
+        let Some(format) = LabelFormat::from_path(&path) else {
+            return self.print(&t("label.unknown_format"));
+        };
+        match self.labels.export(&labels, format, &path) {
+            Ok(paths) => {
+                let paths: Vec<String> = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                self.print(&tf(
+                    "label.written",
+                    &[("total", &labels.len()), ("path", &paths.join(", "))],
+                ))
+            }
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn history(&mut self) -> Result<()> {
+        let control_number = self.capture_string(&t("sample.control_number"))?;
+        let history = self
+            .manager
+            .find_by_control_number(&control_number)
+            .and_then(|sample| match sample.and_then(|sample| sample.id_sample) {
+                Some(id_sample) => self.manager.history(id_sample),
+                None => Ok(Vec::new()),
+            });
+        let history = match history {
+            Ok(history) => history,
+            Err(e) => return self.show_error(&e),
+        };
+        if history.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+
+        for transition in history {
+            let row = tf(
+                "sample.history_row",
+                &[
+                    (
+                        "when",
+                        &transition.transitioned_at.format("%Y-%m-%d %H:%M:%S"),
+                    ),
+                    ("from", &Self::status_label(transition.from_status)),
+                    ("to", &Self::status_label(transition.to_status)),
+                    ("actor", &transition.actor),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for SampleConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("sample.menu.title"))?;
+            let options = visible_options(&t("sample.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.board()?,
+                2 => self.change_status()?,
+                3 => self.history()?,
+                4 => return Ok(()),
+                5 => self.sign_result()?,
+                6 => self.verify_signatures()?,
+                7 => self.print_labels()?,
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
@@ -0,0 +1,303 @@
+use std::io::{BufRead, Write};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    config::Config,
+    data::model::{
+        frequency::Frequency, planned_visit::PlannedVisit, role::Permission,
+        sampling_schedule::SamplingSchedule,
+    },
+    data::repo::{conector::Connector, site_repo::SiteSearchCriteria},
+    data_management::Finder,
+    error::Result,
+    i18n::{t, tf},
+    logic::schedule_manager::ScheduleManager,
+    logic::session::Session,
+    logic::site_manager::SiteManager,
+};
+
+use super::console_view::{visible_options, ConsoleView};
+
+pub struct ScheduleConsoleView<R, W> {
+    manager: ScheduleManager,
+    sites: SiteManager<SiteSearchCriteria>,
+    session: Session,
+    input: R,
+    output: W,
+}
+
+impl<R, W> ScheduleConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: ScheduleManager::new(
+                Connector::new(config),
+                config.page_size,
+                config.control_number_pattern.clone(),
+            ),
+            sites: SiteManager::new(Connector::new(config), config.page_size),
+            session: Session::default(),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session.clone());
+        self.sites = self.sites.with_session(session.clone());
+        self.session = session;
+        self
+    }
+
+    fn menu_allows(&self, option: u8) -> bool {
+        match option {
+            1 | 2 | 5 => self.session.can(Permission::EditSites),
+            6 => self.session.can(Permission::RegisterSamples),
+            _ => true,
+        }
+    }
+
+    fn capture_date(&mut self, title: &str) -> Result<NaiveDate> {
+        self.capture_atributte::<NaiveDate>(title, "YYYY-MM-DD")
+    }
+
+    fn print_visits(&mut self, title: &str, visits: &[PlannedVisit]) -> Result<()> {
+        self.print(title)?;
+        for visit in visits {
+            let client = visit
+                .client_name
+                .clone()
+                .unwrap_or_else(|| t("schedule.no_client"));
+            let sampler = visit
+                .sampler_user
+                .clone()
+                .unwrap_or_else(|| t("schedule.no_sampler"));
+            let row = tf(
+                "schedule.visit_row",
+                &[
+                    ("id", &visit.id_visit),
+                    ("date", &visit.planned_date),
+                    ("key", &visit.site_key),
+                    ("name", &visit.site_name),
+                    ("client", &client),
+                    ("sampler", &sampler),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn add_schedule(&mut self) -> Result<()> {
+        let site_key = self.capture_string(&t("schedule.site"))?;
+        let site = match self.sites.find_by_key(&site_key) {
+            Ok(Some(site)) => site,
+            Ok(None) => return self.print(&t("common.no_results")),
+            Err(e) => return self.show_error(&e),
+        };
+
+        self.print(&t("schedule.frequency"))?;
+        for (number, frequency) in (1..).zip(Frequency::ALL) {
+            let label = t(&format!("frequency.{}", frequency.as_str()));
+            self.print(&format!("{}) {}", number, label))?;
+        }
+        let option = self.capture_atributte::<usize>(&t("common.select_option"), "usize")?;
+        let Some(frequency) = option.checked_sub(1).and_then(|i| Frequency::ALL.get(i)) else {
+            return self.print(&t("common.invalid_option"));
+        };
+
+        let starts_on = self.capture_date(&t("schedule.starts_on"))?;
+        let ends_on =
+            self.capture_option_attribute::<NaiveDate>(&t("schedule.ends_on"), "YYYY-MM-DD")?;
+        let id_sampler = self.capture_option_attribute::<u32>(&t("schedule.sampler"), "u32")?;
+        let project = self.capture_string(&t("schedule.project"))?;
+
+        let names = self.capture_string(&t("schedule.analyses"))?;
+        let mut analyses = Vec::new();
+        for name in names.split(',').filter(|name| !name.trim().is_empty()) {
+            match self.sites.find_analysis(name) {
+                Ok(Some(analysis)) => analyses.extend(analysis.id_analysis),
+                Ok(None) => {
+                    let message = tf("schedule.unknown_analysis", &[("name", &name.trim())]);
+                    return self.print(&message);
+                }
+                Err(e) => return self.show_error(&e),
+            }
+        }
+
+        let schedule = SamplingSchedule {
+            schedule_active: true,
+            id_site: site.id_site.unwrap_or_default(),
+            frequency: *frequency,
+            starts_on,
+            ends_on,
+            id_sampler,
+            project,
+            ..SamplingSchedule::default()
+        };
+        match self.manager.add_schedule(&schedule, &analyses) {
+            Ok(id) => self.print(&tf("schedule.added", &[("id", &id)])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn plan(&mut self) -> Result<()> {
+        let until = self.capture_date(&t("schedule.plan_until"))?;
+        match self.manager.plan(until) {
+            Ok(visits) => self.print(&tf("schedule.planned", &[("total", &visits.len())])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn upcoming(&mut self) -> Result<()> {
+        let days = self.capture_atributte::<u64>(&t("schedule.days"), "u64")?;
+        let today = Local::now().date_naive();
+        match self.manager.upcoming(today, days) {
+            Ok(visits) => {
+                let title = tf(
+                    "schedule.upcoming",
+                    &[("days", &days), ("total", &visits.len())],
+                );
+                self.print_visits(&title, &visits)
+            }
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn pending(&mut self) -> Result<()> {
+        let days = self.capture_atributte::<u64>(&t("schedule.days"), "u64")?;
+        let today = Local::now().date_naive();
+        let found = self
+            .manager
+            .overdue(today)
+            .and_then(|overdue| Ok((overdue, self.manager.unassigned(today, days)?)));
+        let (overdue, unassigned) = match found {
+            Ok(found) => found,
+            Err(e) => return self.show_error(&e),
+        };
+
+        let title = tf("schedule.overdue", &[("total", &overdue.len())]);
+        self.print_visits(&title, &overdue)?;
+        let title = tf(
+            "schedule.unassigned",
+            &[("days", &days), ("total", &unassigned.len())],
+        );
+        self.print_visits(&title, &unassigned)
+    }
+
+    fn assign(&mut self) -> Result<()> {
+        let id_visit = self.capture_atributte::<u32>(&t("schedule.visit_id"), "u32")?;
+        let id_sampler = self.capture_atributte::<u32>(&t("schedule.sampler_id"), "u32")?;
+        match self.manager.assign(id_visit, id_sampler) {
+            Ok(_) => self.print(&tf(
+                "schedule.assigned",
+                &[("id", &id_visit), ("sampler", &id_sampler)],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    // La muestra sale del programa y la visita; solo se piden la fecha real
+    // y la hora del muestreo
+    fn complete(&mut self) -> Result<()> {
+        let id_visit = self.capture_atributte::<u32>(&t("schedule.visit_id"), "u32")?;
+        let found = self.manager.prefill(id_visit).and_then(|sample| {
+            let site = match sample.id_site {
+                Some(id_site) => self.sites.search_by_id(id_site)?,
+                None => None,
+            };
+            Ok((sample, site))
+        });
+        let (mut sample, site) = match found {
+            Ok(found) => found,
+            Err(e) => return self.show_error(&e),
+        };
+
+        let site_key = site.map(|site| site.site_key).unwrap_or_default();
+        self.print(&tf(
+            "schedule.prefilled",
+            &[
+                ("key", &site_key),
+                ("project", &sample.project),
+                ("date", &sample.sampling_date),
+            ],
+        ))?;
+        let title = tf("schedule.sampling_date", &[("date", &sample.sampling_date)]);
+        let date = self.capture_string(&title)?;
+        if !date.is_empty() {
+            match date.parse::<NaiveDate>() {
+                Ok(date) => {
+                    sample.sampling_date = date;
+                    sample.reception = date;
+                }
+                Err(_) => {
+                    let message = tf(
+                        "common.parse_error",
+                        &[("input", &date), ("type", &"YYYY-MM-DD")],
+                    );
+                    return self.print(&message);
+                }
+            }
+        }
+        sample.sampling_hour = self.capture_string(&t("schedule.sampling_hour"))?;
+
+        let completed = self.manager.complete(id_visit, &sample).and_then(|sample| {
+            let id_schedule = self.manager.visit(id_visit)?.id_schedule;
+            Ok((sample, self.manager.analyses_of(id_schedule)?))
+        });
+        let (sample, analyses) = match completed {
+            Ok(completed) => completed,
+            Err(e) => return self.show_error(&e),
+        };
+        self.print(&tf(
+            "schedule.completed",
+            &[("number", &sample.control_number), ("id", &id_visit)],
+        ))?;
+        let names: Vec<String> = analyses
+            .into_iter()
+            .map(|analysis| analysis.analysis_name)
+            .collect();
+        self.print(&tf(
+            "schedule.analyses_to_run",
+            &[("analyses", &names.join(", "))],
+        ))
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for ScheduleConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("schedule.menu.title"))?;
+            let options = visible_options(&t("schedule.menu.options"), |o| self.menu_allows(o));
+            self.print(&options)?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                option if !self.menu_allows(option) => self.print(&t("common.invalid_option"))?,
+                1 => self.add_schedule()?,
+                2 => self.plan()?,
+                3 => self.upcoming()?,
+                4 => self.pending()?,
+                5 => self.assign()?,
+                6 => self.complete()?,
+                7 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
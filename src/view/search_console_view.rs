@@ -0,0 +1,96 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    config::Config,
+    data::{model::search_hit::SearchHit, repo::conector::Connector},
+    error::Result,
+    i18n::{t, tf},
+    logic::search_manager::SearchManager,
+};
+
+use super::console_view::ConsoleView;
+
+pub struct SearchConsoleView<R, W> {
+    manager: SearchManager,
+    input: R,
+    output: W,
+}
+
+impl<R, W> SearchConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: SearchManager::new(Connector::new(config), config.page_size),
+            input,
+            output,
+        }
+    }
+
+    fn search(&mut self) -> Result<()> {
+        let text = self.capture_string(&t("search.prompt"))?;
+        let hits = match self.manager.search(&text) {
+            Ok(hits) => hits,
+            Err(e) => return self.show_error(&e),
+        };
+
+        if hits.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+
+        for (number, hit) in (1..).zip(&hits) {
+            let line = self.format_hit(number, hit);
+            self.print(&line)?;
+        }
+        Ok(())
+    }
+
+    fn format_hit(&self, number: u64, hit: &SearchHit) -> String {
+        let entity = t(&format!("search.entity.{}", hit.entity.as_str()));
+        let inactive = if hit.active {
+            String::new()
+        } else {
+            format!(" ({})", t("search.inactive"))
+        };
+        tf(
+            "search.hit",
+            &[
+                ("number", &number),
+                ("entity", &entity),
+                ("id", &hit.id),
+                ("title", &hit.title),
+                ("inactive", &inactive),
+                ("snippet", &hit.snippet),
+            ],
+        )
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for SearchConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("search.menu.title"))?;
+            self.print(&t("search.menu.options"))?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                1 => self.search()?,
+                2 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
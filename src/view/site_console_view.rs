@@ -0,0 +1,190 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    config::Config,
+    data::repo::{conector::Connector, site_repo::SiteSearchCriteria},
+    error::Result,
+    i18n::{t, tf},
+    logic::session::Session,
+    logic::site_manager::SiteManager,
+    logic::trend::DEFAULT_DEVIATIONS,
+};
+
+use super::console_view::ConsoleView;
+
+pub struct SiteConsoleView<R, W> {
+    manager: SiteManager<SiteSearchCriteria>,
+    input: R,
+    output: W,
+}
+
+impl<R, W> SiteConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: SiteManager::new(Connector::new(config), config.page_size)
+                .with_below_limit(config.below_limit),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session);
+        self
+    }
+
+    fn sites_near(&mut self) -> Result<()> {
+        let latitude = self.capture_string(&t("site.latitude"))?;
+        let longitude = self.capture_string(&t("site.longitude"))?;
+        let center = match self.manager.parse_coordinate(&latitude, &longitude) {
+            Ok(center) => center,
+            Err(e) => return self.show_error(&e),
+        };
+        let radius_km = self.capture_atributte::<f64>(&t("site.radius"), "f64")?;
+
+        let sites = match self.manager.within_km(center, radius_km) {
+            Ok(sites) => sites,
+            Err(e) => return self.show_error(&e),
+        };
+        if sites.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+
+        for (number, (site, distance)) in (1..).zip(sites) {
+            let row = tf(
+                "site.near_row",
+                &[
+                    ("number", &number),
+                    ("key", &site.site_key),
+                    ("name", &site.site_name),
+                    ("distance", &format!("{:.2}", distance)),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn export_geojson(&mut self) -> Result<()> {
+        let path = self.capture_string(&t("site.export_path"))?;
+        self.print(&t("site.export_compliance"))?;
+        let with_compliance = self.capture_atributte::<u8>(&t("common.yes_no"), "u8")? == 1;
+
+        let criteria = SiteSearchCriteria {
+            site_active: Some(true),
+            ..SiteSearchCriteria::default()
+        };
+        let exported = self
+            .manager
+            .geojson(&criteria, with_compliance)
+            .and_then(|geojson| {
+                let total = geojson["features"].as_array().map_or(0, Vec::len);
+                std::fs::write(&path, serde_json::to_string_pretty(&geojson)?)?;
+                Ok(total)
+            });
+
+        match exported {
+            Ok(total) => self.print(&tf("site.exported", &[("total", &total), ("path", &path)])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn result_trend(&mut self) -> Result<()> {
+        let site_key = self.capture_string(&t("site.trend_site"))?;
+        let analysis_name = self.capture_string(&t("site.trend_analysis"))?;
+        let found = self
+            .manager
+            .find_by_key(&site_key)
+            .and_then(|site| Ok((site, self.manager.find_analysis(&analysis_name)?)));
+        let (site, analysis) = match found {
+            Ok((Some(site), Some(analysis))) => (site, analysis),
+            Ok(_) => return self.print(&t("common.no_results")),
+            Err(e) => return self.show_error(&e),
+        };
+
+        let trend = self.manager.result_trend(
+            site.id_site.unwrap_or_default(),
+            analysis.id_analysis.unwrap_or_default(),
+            DEFAULT_DEVIATIONS,
+        );
+        let trend = match trend {
+            Ok(trend) => trend,
+            Err(e) => return self.show_error(&e),
+        };
+        if trend.points.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+
+        for point in &trend.points {
+            let flags: Vec<String> = point
+                .flags
+                .iter()
+                .map(|flag| t(&format!("site.trend_flag.{}", flag.as_str())))
+                .collect();
+            let row = tf(
+                "site.trend_row",
+                &[
+                    ("date", &point.point.sampling_date),
+                    ("number", &point.point.control_number),
+                    ("result", &point.point.result),
+                    ("flags", &flags.join(", ")),
+                ],
+            );
+            self.print(row.trim_end())?;
+        }
+
+        if let Some(stats) = &trend.stats {
+            let summary = tf(
+                "site.trend_stats",
+                &[
+                    ("count", &stats.count),
+                    ("mean", &format!("{:.4}", stats.mean)),
+                    ("median", &format!("{:.4}", stats.median)),
+                    ("p10", &format!("{:.4}", stats.p10)),
+                    ("p90", &format!("{:.4}", stats.p90)),
+                    ("min", &stats.min),
+                    ("max", &stats.max),
+                ],
+            );
+            self.print(&summary)?;
+        }
+        let total = trend.outliers().count();
+        self.print(&tf(
+            "site.trend_outliers",
+            &[("total", &total), ("deviations", &trend.deviations)],
+        ))
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for SiteConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("site.menu.title"))?;
+            self.print(&t("site.menu.options"))?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                1 => self.sites_near()?,
+                2 => self.export_geojson()?,
+                3 => return Ok(()),
+                4 => self.result_trend()?,
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    config::Config,
+    data::model::{role::Role, signatary::Signatary},
+    data::repo::conector::Connector,
+    error::Result,
+    i18n::{t, tf},
+    logic::session::Session,
+    logic::user_manager::UserManager,
+};
+
+use super::console_view::ConsoleView;
+
+// Alta de usuarios y asignacion de roles; solo para administradores
+pub struct UserConsoleView<R, W> {
+    manager: UserManager,
+    input: R,
+    output: W,
+}
+
+impl<R, W> UserConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(config: &Config, input: R, output: W) -> Self {
+        Self {
+            manager: UserManager::new(Connector::new(config), config.page_size),
+            input,
+            output,
+        }
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.manager = self.manager.with_session(session);
+        self
+    }
+
+    fn role_label(role: Role) -> String {
+        t(&format!("role.{}", role.as_str()))
+    }
+
+    fn list_users(&mut self) -> Result<()> {
+        let users = match self.manager.users() {
+            Ok(users) => users,
+            Err(e) => return self.show_error(&e),
+        };
+        if users.is_empty() {
+            return self.print(&t("common.no_results"));
+        }
+        for user in users {
+            let name = format!(
+                "{} {} {}",
+                user.first_name, user.father_last_name, user.mother_last_name
+            );
+            let row = tf(
+                "user.row",
+                &[
+                    ("id", &user.id_signatary.unwrap_or_default()),
+                    ("user", &user.user),
+                    ("name", &name.trim()),
+                    ("role", &Self::role_label(user.role)),
+                ],
+            );
+            self.print(&row)?;
+        }
+        Ok(())
+    }
+
+    fn capture_role(&mut self) -> Result<Option<Role>> {
+        for (number, role) in (1..).zip(Role::ALL) {
+            self.print(&format!("{}) {}", number, Self::role_label(role)))?;
+        }
+        let option = self.capture_atributte::<usize>(&t("common.select_option"), "usize")?;
+        Ok(option
+            .checked_sub(1)
+            .and_then(|i| Role::ALL.get(i))
+            .copied())
+    }
+
+    fn add_user(&mut self) -> Result<()> {
+        let mut signatary = Signatary {
+            first_name: self.capture_string(&t("user.first_name"))?,
+            father_last_name: self.capture_string(&t("user.father_last_name"))?,
+            mother_last_name: self.capture_string(&t("user.mother_last_name"))?,
+            user: self.capture_string(&t("user.user"))?,
+            passwd: self.capture_password(&t("user.passwd"))?,
+            ..Signatary::default()
+        };
+        let Some(role) = self.capture_role()? else {
+            return self.print(&t("common.invalid_option"));
+        };
+        signatary.role = role;
+
+        match self.manager.add_user(&signatary) {
+            Ok(_) => self.print(&tf("user.added", &[("user", &signatary.user.trim())])),
+            Err(e) => self.show_error(&e),
+        }
+    }
+
+    fn assign_role(&mut self) -> Result<()> {
+        let id: u32 = self.capture_atributte(&t("user.id"), "u32")?;
+        let Some(role) = self.capture_role()? else {
+            return self.print(&t("common.invalid_option"));
+        };
+        match self.manager.assign_role(id, role) {
+            Ok(user) => self.print(&tf(
+                "user.role_assigned",
+                &[("user", &user.user), ("role", &Self::role_label(user.role))],
+            )),
+            Err(e) => self.show_error(&e),
+        }
+    }
+}
+
+impl<R, W> ConsoleView<R, W> for UserConsoleView<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    fn menu(&mut self) -> Result<()> {
+        loop {
+            self.clear_linux_console();
+            self.print(&t("user.menu.title"))?;
+            self.print(&t("user.menu.options"))?;
+            match self.capture_atributte::<u8>(&t("common.select_option"), "u8")? {
+                1 => self.list_users()?,
+                2 => self.add_user()?,
+                3 => self.assign_role()?,
+                4 => return Ok(()),
+                _ => self.print(&t("common.invalid_option"))?,
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut R {
+        &mut self.input
+    }
+
+    fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
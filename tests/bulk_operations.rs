@@ -0,0 +1,200 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::repo::client_repo::{ClientRepo, SearchCriteria};
+use rust_project::data::table::Patch;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::client_manager::ClientManager;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::client_console_view::ClientConsoleView;
+use rust_project::view::console_view::ConsoleView;
+
+fn client(name: &str) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+fn all_clients(db: &TestDb) -> Vec<Client> {
+    ClientRepo::new(db.connector(), 10)
+        .find_all(&SearchCriteria::default())
+        .unwrap()
+}
+
+#[test]
+fn add_many_is_all_or_nothing() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+
+    let error = manager
+        .add_many(&[client("Alfa"), client(""), client("Charlie")])
+        .unwrap_err();
+    assert!(matches!(error, Error::InvalidField { .. }));
+    assert!(all_clients(&db).is_empty());
+
+    manager
+        .add_many(&[client("Alfa"), client("Bravo")])
+        .unwrap();
+    let names: Vec<String> = all_clients(&db)
+        .into_iter()
+        .map(|client| client.client_name)
+        .collect();
+    assert_eq!(names, ["Alfa", "Bravo"]);
+}
+
+#[test]
+fn add_many_samples_rolls_back_numbers_on_duplicates() {
+    let db = TestDb::new(10);
+    let mut manager = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    let sample = |control_number: &str| Sample {
+        sample_active: true,
+        control_number: control_number.to_string(),
+        sampling_date: date,
+        reception: date,
+        ..Sample::default()
+    };
+
+    let error = manager
+        .add_many(&[sample(""), sample("A-1"), sample("A-1")])
+        .unwrap_err();
+    assert!(error.to_string().contains("A-1 is already in use"));
+
+    // El consecutivo tomado por la primera muestra tambien se deshizo
+    manager.add_many(&[sample(""), sample("A-1")]).unwrap();
+    let first = manager.search_by_id(1).unwrap().unwrap();
+    assert_eq!(first.control_number, "2024-0-00001");
+}
+
+#[test]
+fn drop_and_modify_many_update_versions() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager
+        .add_many(&[client("Alfa"), client("Bravo")])
+        .unwrap();
+
+    let mut clients = all_clients(&db);
+    manager.drop_many(&mut clients).unwrap();
+    assert!(clients
+        .iter()
+        .all(|c| !c.client_active && c.row_version == 2));
+    assert_eq!(all_clients(&db), clients);
+
+    let patch = Patch::new().set("client_active", true).unwrap();
+    manager.modify_many(&clients, &patch).unwrap();
+    assert!(all_clients(&db)
+        .iter()
+        .all(|c| c.client_active && c.row_version == 3));
+
+    manager.delete_many(&all_clients(&db)).unwrap();
+    assert!(all_clients(&db).is_empty());
+}
+
+#[test]
+fn stale_row_aborts_the_whole_batch() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager
+        .add_many(&[client("Alfa"), client("Bravo")])
+        .unwrap();
+    let loaded = all_clients(&db);
+
+    let mut edited = loaded[1].clone();
+    edited.client_name = "Bravo Sur".to_string();
+    manager.modify(&edited).unwrap();
+
+    let patch = Patch::new().set("client_name", "Renombrado").unwrap();
+    let error = manager.modify_many(&loaded, &patch).unwrap_err();
+    assert!(matches!(error, Error::Conflict { .. }));
+
+    let names: Vec<String> = all_clients(&db)
+        .into_iter()
+        .map(|client| client.client_name)
+        .collect();
+    assert_eq!(names, ["Alfa", "Bravo Sur"]);
+}
+
+#[test]
+fn modify_and_modify_many_reject_a_blank_name() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager
+        .add_many(&[client("Alfa"), client("Bravo")])
+        .unwrap();
+    let clients = all_clients(&db);
+
+    let mut edited = clients[0].clone();
+    edited.client_name = "  ".to_string();
+    let error = manager.modify(&edited).unwrap_err();
+    assert!(matches!(error, Error::InvalidField { .. }));
+    assert!(error
+        .to_string()
+        .contains("The client name must not be empty"));
+
+    let patch = Patch::new().set("client_name", "").unwrap();
+    assert!(matches!(
+        manager.modify_many(&clients, &patch).unwrap_err(),
+        Error::InvalidField { .. }
+    ));
+    assert_eq!(all_clients(&db), clients);
+}
+
+#[test]
+fn patch_rejects_bookkeeping_and_unknown_columns() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager.add(&client("Alfa")).unwrap();
+    let clients = all_clients(&db);
+
+    for column in ["row_version", "id_client", "client_color"] {
+        let patch = Patch::new().set(column, 7).unwrap();
+        let error = manager.modify_many(&clients, &patch).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains(&format!("The field {} cannot be changed", column)));
+    }
+    assert!(manager.modify_many(&clients, &Patch::new()).is_err());
+    assert_eq!(all_clients(&db), clients);
+}
+
+#[test]
+fn console_previews_and_deactivates_matching_clients() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add_many(&[client("Agua Norte"), client("Agua Sur"), client("Minera")])
+        .unwrap();
+
+    // buscar por nombre "Agua", acciones en lote, dar de baja, no confirmar;
+    // repetir confirmando y salir
+    let script = "6\n3\n1\nAgua\n4\n4\n1\n2\n4\n1\n1\n3\n7\n";
+    let mut output = Vec::new();
+    ClientConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("2 clients will be affected:"));
+    assert!(output.contains("1) ID: 1, Name: Agua Norte, Active: true"));
+    assert!(output.contains("Confirm: deactivate 2 clients?"));
+    assert!(output.contains("No changes were made"));
+    assert!(output.contains("2 clients processed"));
+
+    let active: Vec<bool> = all_clients(&db)
+        .into_iter()
+        .map(|client| client.client_active)
+        .collect();
+    assert_eq!(active, [false, false, true]);
+}
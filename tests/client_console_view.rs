@@ -0,0 +1,120 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::repo::client_repo::{ClientRepo, SearchCriteria};
+use rust_project::data_management::{Finder, LastSearch, Repository};
+use rust_project::logic::session::Session;
+use rust_project::view::client_console_view::ClientConsoleView;
+use rust_project::view::console_view::ConsoleView;
+
+fn run_session(db: &TestDb, script: &str) -> String {
+    let mut output = Vec::new();
+    let mut view = ClientConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system());
+    view.menu().expect("session finishes with the exit option");
+    String::from_utf8(output).expect("utf-8 output")
+}
+
+fn add_clients(db: &TestDb, names: &[&str]) {
+    let mut repo = ClientRepo::new(db.connector(), db.config.page_size);
+    for name in names {
+        let client = Client {
+            id_client: None,
+            client_active: true,
+            client_name: name.to_string(),
+            ..Client::default()
+        };
+        repo.add(&client).unwrap();
+    }
+}
+
+fn search(db: &TestDb, criteria: &SearchCriteria) -> Vec<Client> {
+    let mut repo = ClientRepo::new(db.connector(), db.config.page_size);
+    let search: LastSearch<SearchCriteria> = repo.search_by(criteria, 1).unwrap();
+    serde_json::from_str(&search.result).unwrap()
+}
+
+#[test]
+fn list_pages_through_clients() {
+    let db = TestDb::new(2);
+    add_clients(&db, &["Alfa", "Bravo", "Charlie"]);
+
+    // listar, siguiente pagina, salir del paginador, salir del menu
+    let output = run_session(&db, "1\n2\n3\n7\n");
+
+    assert!(output.contains("1) ID: 1, Name: Alfa, Active: true"));
+    assert!(output.contains("2) ID: 2, Name: Bravo, Active: true"));
+    assert!(output.contains("Page 1 of 2"));
+    assert!(output.contains("3) ID: 3, Name: Charlie, Active: true"));
+    assert!(output.contains("Page 2 of 2"));
+}
+
+#[test]
+fn list_without_clients_reports_no_results() {
+    let db = TestDb::new(10);
+
+    let output = run_session(&db, "1\n3\n7\n");
+
+    assert!(output.contains("No results"));
+}
+
+#[test]
+fn add_stores_the_client() {
+    let db = TestDb::new(10);
+
+    let output = run_session(&db, "2\nAgua Potable SA\n7\n");
+
+    assert!(output.contains("Type the client name"));
+    let clients = search(&db, &SearchCriteria::default());
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].client_name, "Agua Potable SA");
+    assert!(clients[0].client_active);
+}
+
+#[test]
+fn add_with_empty_name_shows_validation_error() {
+    let db = TestDb::new(10);
+
+    let output = run_session(&db, "2\n\n7\n");
+
+    assert!(output.contains("[E006_INVALID_FIELD] The client name must not be empty"));
+    assert!(search(&db, &SearchCriteria::default()).is_empty());
+}
+
+#[test]
+fn search_filters_by_name() {
+    let db = TestDb::new(10);
+    add_clients(&db, &["Agua Norte", "Laboratorio Sur", "Agua Sur"]);
+
+    // buscar, criterio por nombre, si, "Sur", continuar, salir del paginador, salir
+    let output = run_session(&db, "6\n3\n1\nSur\n4\n3\n7\n");
+
+    assert!(output.contains("Name: Sur"));
+    assert!(output.contains("Name: Agua Sur"));
+    assert!(output.contains("Name: Laboratorio Sur"));
+    assert!(!output.contains("Name: Agua Norte"));
+    assert!(output.contains("Page 1 of 1"));
+}
+
+#[test]
+fn invalid_input_is_reported_and_retried() {
+    let db = TestDb::new(10);
+
+    let output = run_session(&db, "abc\n10\n7\n");
+
+    assert!(output.contains("Could not convert \"abc\" into \"u8\""));
+    assert!(output.contains("Invalid option"));
+}
+
+#[test]
+fn session_without_exit_fails_at_end_of_input() {
+    let db = TestDb::new(10);
+    let mut output = Vec::new();
+    let mut view = ClientConsoleView::new(&db.config, Cursor::new("1\n"), &mut output)
+        .with_session(Session::system());
+
+    assert!(view.menu().is_err());
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::client_contact::ClientContact;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::client_manager::ClientManager;
+use rust_project::logic::contact::{valid_email, valid_rfc};
+use rust_project::logic::session::Session;
+use rust_project::view::client_console_view::ClientConsoleView;
+use rust_project::view::console_view::ConsoleView;
+
+fn client(name: &str, rfc: Option<&str>) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        client_rfc: rfc.map(str::to_string),
+        ..Client::default()
+    }
+}
+
+fn contact(id_client: u32, name: &str, email: &str) -> ClientContact {
+    ClientContact {
+        id_client,
+        contact_name: name.to_string(),
+        email: Some(email.to_string()),
+        ..ClientContact::default()
+    }
+}
+
+#[test]
+fn email_and_rfc_formats() {
+    for email in ["ana@agua.com.mx", "a.b+c@lab-1.mx"] {
+        assert!(valid_email(email), "{}", email);
+    }
+    for email in [
+        "ana",
+        "@agua.mx",
+        "ana@agua",
+        "ana@@agua.mx",
+        "ana @agua.mx",
+        "ana@.mx",
+    ] {
+        assert!(!valid_email(email), "{}", email);
+    }
+
+    for rfc in ["APS850101AB1", "GOMA960229H12", "&ÑA010101XY9"] {
+        assert!(valid_rfc(rfc), "{}", rfc);
+    }
+    for rfc in [
+        "APS851301AB1",
+        "AP850101AB1",
+        "aps850101ab1",
+        "APS850101AB",
+        "GOMA900230H12",
+    ] {
+        assert!(!valid_rfc(rfc), "{}", rfc);
+    }
+}
+
+#[test]
+fn rfc_is_normalized_and_validated() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+
+    manager
+        .add(&client("Agua Potable", Some(" aps 850101ab1 ")))
+        .unwrap();
+    let mut stored = manager.search_by_id(1).unwrap().unwrap();
+    assert_eq!(stored.client_rfc.as_deref(), Some("APS850101AB1"));
+
+    let error = manager.add(&client("Minera", Some("XYZ"))).unwrap_err();
+    assert!(error.to_string().contains("The RFC \"XYZ\" is not valid"));
+
+    stored.client_rfc = Some("123".to_string());
+    assert!(matches!(
+        manager.modify(&stored).unwrap_err(),
+        Error::InvalidField { .. }
+    ));
+
+    stored.client_rfc = Some(String::new());
+    stored.billing_address = Some("Av. Juárez 10, Monterrey".to_string());
+    manager.modify(&stored).unwrap();
+    let stored = manager.search_by_id(1).unwrap().unwrap();
+    assert_eq!(stored.client_rfc, None);
+    assert_eq!(
+        stored.billing_address.as_deref(),
+        Some("Av. Juárez 10, Monterrey")
+    );
+}
+
+#[test]
+fn contacts_belong_to_a_client() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager.add(&client("Agua Potable", None)).unwrap();
+
+    let id = manager
+        .add_contact(&contact(1, " Ana López ", "ana@agua.mx"))
+        .unwrap();
+    manager
+        .add_contact(&ClientContact {
+            phone: Some("81 1234 5678".to_string()),
+            email: Some(" ".to_string()),
+            ..contact(1, "Beto", "")
+        })
+        .unwrap();
+
+    let error = manager.add_contact(&contact(1, "", "ana@")).unwrap_err();
+    let message = error.to_string();
+    assert!(message.contains("The contact name must not be empty"));
+    assert!(message.contains("the email \"ana@\" is not valid"));
+    assert!(matches!(
+        manager.add_contact(&contact(9, "Ana", "ana@agua.mx")),
+        Err(Error::NotFound { id: Some(9), .. })
+    ));
+
+    let mut contacts = manager.contacts_of(1).unwrap();
+    assert_eq!(contacts.len(), 2);
+    assert_eq!(contacts[0].id_contact, Some(id));
+    assert_eq!(contacts[0].contact_name, "Ana López");
+    assert_eq!(contacts[1].email, None);
+
+    manager.drop_contact(&mut contacts[1]).unwrap();
+    assert_eq!(manager.contacts_of(1).unwrap().len(), 1);
+}
+
+#[test]
+fn deleting_a_client_deletes_its_contacts() {
+    let db = TestDb::new(10);
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    manager.add(&client("Agua Potable", None)).unwrap();
+    manager
+        .add_contact(&contact(1, "Ana", "ana@agua.mx"))
+        .unwrap();
+
+    let stored = manager.search_by_id(1).unwrap().unwrap();
+    manager.delete(&stored).unwrap();
+    assert!(manager.search_by_id(1).unwrap().is_none());
+    assert!(manager.contacts_of(1).unwrap().is_empty());
+}
+
+#[test]
+fn detail_screen_shows_and_edits_contacts() {
+    let db = TestDb::new(10);
+    ClientManager::new(db.connector(), 10)
+        .with_session(Session::system())
+        .add(&client("Agua Potable", None))
+        .unwrap();
+
+    // detalle del 1, agregar contacto, RFC invalido, RFC valido, regresar
+    let script = "9\n1\n1\nAna\nCompras\nana@agua.mx\n8112345678\n\
+                  2\nXYZ\n\n2\nAPS850101AB1\nAv. Juárez 10\n4\n7\n";
+    let mut output = Vec::new();
+    ClientConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("No contacts"));
+    assert!(output.contains("1) Ana (Compras) - ana@agua.mx - 8112345678"));
+    assert!(output.contains("The RFC \"XYZ\" is not valid"));
+    assert!(output.contains("RFC: APS850101AB1\nBilling address: Av. Juárez 10"));
+    assert!(output.contains("Unpaid balance: $0.00"));
+}
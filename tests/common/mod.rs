@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use rust_project::config::{Config, LogLevel, Profile};
+use rust_project::data::repo::conector::Connector;
+use rust_project::i18n::{self, Locale};
+use tempfile::TempDir;
+
+// Base de datos temporal creada con db/script.sql; se borra al salir de alcance
+pub struct TestDb {
+    pub config: Config,
+    _dir: TempDir,
+}
+
+impl TestDb {
+    pub fn new(page_size: u64) -> Self {
+        // Todas las pruebas comparan contra los textos en ingles
+        i18n::set_locale(Locale::En);
+
+        let dir = tempfile::tempdir().expect("temporary directory");
+        let mut config = Config::defaults(Profile::Test);
+        config.database_url = dir.path().join("test.db").display().to_string();
+        config.database_init_script = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("db/script.sql")
+            .display()
+            .to_string();
+        config.page_size = page_size;
+        config.locale = Locale::En;
+        config.log_level = LogLevel::Error;
+
+        Connector::new(&config).db_init().expect("database created");
+        Self { config, _dir: dir }
+    }
+
+    pub fn connector(&self) -> Connector {
+        Connector::new(&self.config)
+    }
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use std::thread;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rusqlite::Connection;
+use rust_project::config::{Config, Profile};
+use rust_project::data::model::client::Client;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data::repo::conector::Connector;
+use rust_project::data::repo::control_number_repo::ControlNumberRepo;
+use rust_project::data::repo::migrations;
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+
+fn sample(year: i32, id_site: Option<u32>) -> Sample {
+    let date = NaiveDate::from_ymd_opt(year, 3, 2).unwrap();
+    Sample {
+        sample_active: true,
+        project: "Pozos".to_string(),
+        sampling_date: date,
+        reception: date,
+        id_site,
+        ..Sample::default()
+    }
+}
+
+#[test]
+fn pattern_formats_and_validates() {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    let pattern = ControlNumberPattern::default();
+    assert_eq!(pattern.format(date, Some(7), 42), "2024-7-00042");
+    assert_eq!(pattern.format(date, None, 123456), "2024-0-123456");
+
+    let pattern: ControlNumberPattern = "LAB/{YY}{MM}/{client:03}/{seq}".parse().unwrap();
+    assert_eq!(pattern.format(date, Some(7), 9), "LAB/2403/007/9");
+
+    for invalid in [
+        "{YYYY}-{client}",
+        "{client}-{seq}",
+        "{YYYY}-{seq",
+        "{YYYY}}-{seq}",
+        "{YYYY}-{seq:5}",
+        "{YYYY}-{day}-{seq}",
+    ] {
+        assert!(
+            invalid.parse::<ControlNumberPattern>().is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn generates_per_year_sequences_with_the_site_client() {
+    let db = TestDb::new(10);
+    let id_client = ClientRepo::new(db.connector(), 10)
+        .insert(&Client {
+            id_client: None,
+            client_active: true,
+            client_name: "Agua".to_string(),
+            ..Client::default()
+        })
+        .unwrap();
+    let id_site = SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            id_client: Some(id_client),
+            ..Site::default()
+        })
+        .unwrap();
+
+    let mut manager = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    let numbers: Vec<String> = [
+        sample(2024, Some(id_site)),
+        sample(2024, None),
+        sample(2025, Some(id_site)),
+    ]
+    .iter()
+    .map(|item| manager.register(item).unwrap().control_number)
+    .collect();
+
+    let expected = format!("2024-{}-00001", id_client);
+    assert_eq!(numbers[0], expected);
+    assert_eq!(numbers[1], "2024-0-00002");
+    assert_eq!(numbers[2], format!("2025-{}-00001", id_client));
+
+    let repo = ControlNumberRepo::new(db.connector());
+    assert_eq!(repo.last_value(2024).unwrap(), 2);
+    assert_eq!(repo.last_value(2023).unwrap(), 0);
+}
+
+#[test]
+fn skips_numbers_taken_by_hand_and_rejects_duplicates() {
+    let db = TestDb::new(10);
+    let mut manager = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+
+    let mut manual = sample(2024, None);
+    manual.control_number = " 2024-0-00001 ".to_string();
+    let stored = manager.register(&manual).unwrap();
+    assert_eq!(stored.control_number, "2024-0-00001");
+
+    let generated = manager.register(&sample(2024, None)).unwrap();
+    assert_eq!(generated.control_number, "2024-0-00002");
+
+    let error = manager.register(&manual).unwrap_err();
+    assert!(matches!(error, Error::InvalidField { .. }));
+    assert!(error
+        .to_string()
+        .contains("The control number 2024-0-00001 is already in use"));
+
+    let mut changed = manager
+        .search_by_id(generated.id_sample.unwrap())
+        .unwrap()
+        .unwrap();
+    changed.control_number = stored.control_number;
+    assert!(matches!(
+        manager.modify(&changed).unwrap_err(),
+        Error::InvalidField { .. }
+    ));
+}
+
+#[test]
+fn concurrent_inserts_get_distinct_numbers() {
+    let db = TestDb::new(10);
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let connector = db.connector();
+            thread::spawn(move || {
+                let mut manager =
+                    SampleManager::new(connector, 10, ControlNumberPattern::default())
+                        .with_session(Session::system());
+                (0..5)
+                    .map(|_| {
+                        manager
+                            .register(&sample(2024, None))
+                            .unwrap()
+                            .control_number
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let mut numbers: Vec<String> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    numbers.sort();
+
+    let expected: Vec<String> = (1..=40).map(|seq| format!("2024-0-{:05}", seq)).collect();
+    assert_eq!(numbers, expected);
+}
+
+#[test]
+fn migration_renames_existing_duplicates() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.db");
+    let script = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/db/script.sql"));
+    let mut conn = Connection::open(&path).unwrap();
+    conn.execute_batch(&script.unwrap()).unwrap();
+    conn.execute_batch(
+        "INSERT INTO Sample (sample_active, control_number, project, sampling_date,
+                             sampling_hour, reception)
+         VALUES (1, 'A-1', 'p', '2024-01-01', '', '2024-01-01'),
+                (1, 'A-1', 'p', '2024-01-01', '', '2024-01-01'),
+                (1, 'A-2', 'p', '2024-01-01', '', '2024-01-01');",
+    )
+    .unwrap();
+
+    migrations::migrate(&mut conn).unwrap();
+
+    let mut stmt = conn
+        .prepare("SELECT control_number FROM Sample ORDER BY id_sample")
+        .unwrap();
+    let numbers: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    assert_eq!(numbers, ["A-1", "A-1-2", "A-2"]);
+
+    let mut config = Config::defaults(Profile::Test);
+    config.database_url = path.display().to_string();
+    let mut repo = SampleRepo::new(Connector::new(&config), 10);
+    let mut duplicate = sample(2024, None);
+    duplicate.control_number = "A-2".to_string();
+    assert!(repo.add(&duplicate).unwrap_err().is_unique_violation());
+}
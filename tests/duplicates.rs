@@ -0,0 +1,274 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::invoice::Invoice;
+use rust_project::data::model::invoice_status::InvoiceStatus;
+use rust_project::data::model::money::Money;
+use rust_project::data::model::quotation::Quotation;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::client_repo::{ClientRepo, SearchCriteria};
+use rust_project::data::repo::invoice_repo::InvoiceRepo;
+use rust_project::data::repo::quotation_repo::QuotationRepo;
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::client_manager::{ClientManager, MergeCounts};
+use rust_project::logic::duplicates::{levenshtein, name_distance, normalize};
+use rust_project::logic::session::Session;
+use rust_project::logic::site_manager::SiteManager;
+use rust_project::view::client_console_view::ClientConsoleView;
+use rust_project::view::console_view::ConsoleView;
+
+fn client(name: &str) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+fn site(key: &str, name: &str, id_client: Option<u32>) -> Site {
+    Site {
+        site_active: true,
+        site_key: key.to_string(),
+        site_name: name.to_string(),
+        latitude: "19.4326".to_string(),
+        longitude: "-99.1332".to_string(),
+        id_client,
+        ..Site::default()
+    }
+}
+
+#[test]
+fn names_are_compared_without_case_accents_or_punctuation() {
+    assert_eq!(normalize("  AGUA Potable,  S.A. "), "agua potable sa");
+    assert_eq!(normalize("Químicos de León"), "quimicos de leon");
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+
+    assert_eq!(
+        name_distance("Agua Potable SA", "AGUA POTABLE S.A."),
+        Some(0)
+    );
+    assert_eq!(name_distance("Agua Potable SA", "Agua Potabel SA"), Some(2));
+    assert_eq!(name_distance("Alfa", "Beta"), None);
+    assert_eq!(name_distance("", "."), None);
+}
+
+#[test]
+fn likely_duplicates_are_sorted_by_distance() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    repo.add_many(&[
+        client("Agua Potabel SA"),
+        client("AGUA POTABLE S.A."),
+        client("Minera del Norte"),
+    ])
+    .unwrap();
+
+    let manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    let names: Vec<String> = manager
+        .likely_duplicates(&client("Agua Potable SA"))
+        .unwrap()
+        .into_iter()
+        .map(|client| client.client_name)
+        .collect();
+    assert_eq!(names, ["AGUA POTABLE S.A.", "Agua Potabel SA"]);
+
+    // Al editar no se compara consigo mismo
+    let stored = repo.search_by_id(2).unwrap().unwrap();
+    assert_eq!(manager.likely_duplicates(&stored).unwrap().len(), 1);
+}
+
+#[test]
+fn sites_match_by_key_or_name() {
+    let db = TestDb::new(10);
+    SiteRepo::new(db.connector(), 10)
+        .add_many(&[
+            site("NL-01", "Pozo Norte", None),
+            site("NL-02", "Pozo  norte.", None),
+            site("nl 01", "Presa", None),
+            site("NL-03", "Manantial", None),
+        ])
+        .unwrap();
+
+    let manager = SiteManager::new(db.connector(), 10).with_session(Session::system());
+    let mut keys: Vec<String> = manager
+        .likely_duplicates(&site("NL-01", "Pozo Norte", None))
+        .unwrap()
+        .into_iter()
+        .map(|site| site.site_key)
+        .collect();
+    keys.sort();
+    assert_eq!(keys, ["NL-01", "NL-02", "nl 01"]);
+}
+
+#[test]
+fn merge_moves_sites_and_deactivates_the_duplicate() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add_many(&[client("Agua Potable SA"), client("AGUA POTABLE S.A.")])
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .add_many(&[
+            site("NL-01", "Pozo Norte", Some(1)),
+            site("NL-02", "Pozo Sur", Some(2)),
+            site("NL-03", "Presa", Some(2)),
+        ])
+        .unwrap();
+
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    assert_eq!(manager.merge(1, 2).unwrap().sites, 2);
+
+    assert_eq!(manager.sites_of(1).unwrap().len(), 3);
+    assert!(manager.sites_of(2).unwrap().is_empty());
+    let moved = SiteRepo::new(db.connector(), 10)
+        .search_by_id(2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(moved.row_version, 2);
+
+    let duplicate = manager.search_by_id(2).unwrap().unwrap();
+    assert!(!duplicate.client_active);
+
+    let error = manager.merge(2, 1).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("The client to keep must be active"));
+    assert!(matches!(
+        manager.merge(1, 1).unwrap_err(),
+        Error::InvalidField { .. }
+    ));
+    assert!(matches!(
+        manager.merge(1, 99).unwrap_err(),
+        Error::NotFound { id: Some(99), .. }
+    ));
+}
+
+#[test]
+fn merge_moves_quotations_and_invoices() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add_many(&[client("Agua Potable SA"), client("AGUA POTABLE S.A.")])
+        .unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let id_quotation = QuotationRepo::new(db.connector(), 10)
+        .insert(&Quotation {
+            quotation_active: true,
+            id_client: 2,
+            project: "Pozos".to_string(),
+            issued_on: date,
+            valid_until: date,
+            ..Quotation::default()
+        })
+        .unwrap();
+    let id_invoice = InvoiceRepo::new(db.connector(), 10)
+        .insert(&Invoice {
+            invoice_active: true,
+            id_client: 2,
+            invoice_status: InvoiceStatus::Issued,
+            drafted_on: date,
+            total: "439".parse().unwrap(),
+            ..Invoice::default()
+        })
+        .unwrap();
+
+    let mut manager = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    let counts = MergeCounts {
+        sites: 0,
+        contacts: 0,
+        quotations: 1,
+        invoices: 1,
+    };
+    assert_eq!(manager.merge_preview(2).unwrap(), counts);
+    assert_eq!(manager.merge(1, 2).unwrap(), counts);
+    assert_eq!(manager.merge_preview(2).unwrap(), MergeCounts::default());
+
+    let quotation = QuotationRepo::new(db.connector(), 10)
+        .search_by_id(id_quotation)
+        .unwrap()
+        .unwrap();
+    assert_eq!(quotation.id_client, 1);
+    let invoice = InvoiceRepo::new(db.connector(), 10)
+        .search_by_id(id_invoice)
+        .unwrap()
+        .unwrap();
+    assert_eq!(invoice.id_client, 1);
+    assert_eq!(manager.unpaid_balance(1).unwrap(), invoice.total);
+    assert_eq!(manager.unpaid_balance(2).unwrap(), Money::ZERO);
+}
+
+#[test]
+fn merge_sites_moves_samples() {
+    let db = TestDb::new(10);
+    SiteRepo::new(db.connector(), 10)
+        .add_many(&[site("NL-01", "Pozo", None), site("NL 01", "Pozo", None)])
+        .unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    SampleRepo::new(db.connector(), 10)
+        .add(&Sample {
+            sample_active: true,
+            control_number: "A-1".to_string(),
+            sampling_date: date,
+            reception: date,
+            id_site: Some(2),
+            ..Sample::default()
+        })
+        .unwrap();
+
+    let mut manager = SiteManager::new(db.connector(), 10).with_session(Session::system());
+    assert_eq!(manager.merge(1, 2).unwrap(), 1);
+    let sample = SampleRepo::new(db.connector(), 10)
+        .search_by_id(1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.id_site, Some(1));
+    assert!(!manager.search_by_id(2).unwrap().unwrap().site_active);
+}
+
+#[test]
+fn console_warns_before_adding_and_merges() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add(&client("Agua Potable SA"))
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .add(&site("NL-01", "Pozo", Some(1)))
+        .unwrap();
+
+    // agregar repetido y rechazarlo, agregarlo de todos modos, unir 1 <- 2
+    let script = "2\nAGUA POTABLE S.A.\n2\n2\nAGUA POTABLE S.A.\n1\n8\n2\n1\n1\n7\n";
+    let mut output = Vec::new();
+    ClientConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Possible duplicates of \"AGUA POTABLE S.A.\":"));
+    assert!(output.contains("1) ID: 1, Name: Agua Potable SA, Active: true"));
+    assert!(output.contains("The client was not added"));
+    assert!(output.contains(
+        "1 sites, 0 contacts, 0 quotations and 0 invoices of Agua Potable SA will move to AGUA POTABLE S.A. and Agua Potable SA will be deactivated"
+    ));
+    assert!(output
+        .contains("1 sites, 0 contacts, 0 quotations and 0 invoices moved to AGUA POTABLE S.A."));
+
+    let clients = ClientRepo::new(db.connector(), 10)
+        .find_all(&SearchCriteria::default())
+        .unwrap();
+    let active: Vec<(&str, bool)> = clients
+        .iter()
+        .map(|c| (c.client_name.as_str(), c.client_active))
+        .collect();
+    assert_eq!(
+        active,
+        [("AGUA POTABLE S.A.", true), ("Agua Potable SA", false)]
+    );
+}
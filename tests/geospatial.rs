@@ -0,0 +1,250 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::coordinate::{parse_degrees, Axis, BoundingBox, Coordinate};
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::site::Site;
+use rust_project::data::model::standard::Standard;
+use rust_project::data::model::standard_detail::StandardDetail;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::repo::site_repo::{SiteRepo, SiteSearchCriteria};
+use rust_project::data::repo::standard_detail_repo::StandardDetailRepo;
+use rust_project::data::repo::standard_repo::StandardRepo;
+use rust_project::data_management::Repository;
+use rust_project::error::Error;
+use rust_project::logic::session::Session;
+use rust_project::logic::site_manager::SiteManager;
+
+fn site(key: &str, latitude: &str, longitude: &str) -> Site {
+    Site {
+        site_active: true,
+        site_key: key.to_string(),
+        site_name: format!("Sitio {}", key),
+        latitude: latitude.to_string(),
+        longitude: longitude.to_string(),
+        ..Site::default()
+    }
+}
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("valid coordinate");
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn parses_decimal_and_dms() {
+    assert_close(parse_degrees("19.4326", Axis::Latitude), 19.4326);
+    assert_close(parse_degrees(" -99.1332 ", Axis::Longitude), -99.1332);
+    assert_close(parse_degrees("19°25'57\"N", Axis::Latitude), 19.4325);
+    assert_close(
+        parse_degrees("99° 08' 00\" W", Axis::Longitude),
+        -99.133333333,
+    );
+    assert_close(parse_degrees("99 8 0 O", Axis::Longitude), -99.133333333);
+    assert_close(parse_degrees("S 33° 27.6'", Axis::Latitude), -33.46);
+
+    for invalid in [
+        "",
+        "91",
+        "19°61'N",
+        "19.5°30'N",
+        "-19°N",
+        "19 E",
+        "abc",
+        "1 2 3 4",
+    ] {
+        assert_eq!(parse_degrees(invalid, Axis::Latitude), None, "{}", invalid);
+    }
+    assert_eq!(parse_degrees("180.5", Axis::Longitude), None);
+}
+
+#[test]
+fn distances_and_boxes() {
+    let zocalo = Coordinate::new(19.4326, -99.1332).unwrap();
+    let puebla = Coordinate::new(19.0414, -98.2063).unwrap();
+    let distance = zocalo.distance_km(&puebla);
+    assert!((distance - 106.6).abs() < 0.5, "{}", distance);
+    assert!(Coordinate::new(90.1, 0.0).is_none());
+
+    let area = BoundingBox::around(zocalo, 120.0);
+    assert!(area.contains(&puebla));
+    assert!(!BoundingBox::around(zocalo, 50.0).contains(&puebla));
+
+    // Caja que cruza el antimeridiano
+    let pacific = BoundingBox {
+        min_latitude: -10.0,
+        min_longitude: 170.0,
+        max_latitude: 10.0,
+        max_longitude: 190.0,
+    };
+    assert!(pacific.contains(&Coordinate::new(0.0, -175.0).unwrap()));
+    assert!(!pacific.contains(&Coordinate::new(0.0, 160.0).unwrap()));
+}
+
+#[test]
+fn manager_rejects_invalid_coordinates() {
+    let db = TestDb::new(10);
+    let mut manager = SiteManager::new(db.connector(), 10).with_session(Session::system());
+
+    let error = manager.add(&site("NL-01", "95", "100°W")).unwrap_err();
+    let Error::InvalidField { errors, .. } = &error else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(error
+        .to_string()
+        .contains("The latitude \"95\" is not valid"));
+
+    manager.add(&site("NL-01", "25°40'N", "100°18'W")).unwrap();
+}
+
+#[test]
+fn bounding_box_and_radius_search() {
+    let db = TestDb::new(10);
+    let mut repo = SiteRepo::new(db.connector(), 10);
+    repo.add(&site("CDMX", "19.4326", "-99.1332")).unwrap();
+    repo.add(&site("PUE", "19°02'29\"N", "98°12'23\"W"))
+        .unwrap();
+    repo.add(&site("MTY", "25.6866", "-100.3161")).unwrap();
+    repo.add(&site("BAD", "sin dato", "-99.0")).unwrap();
+
+    let criteria = SiteSearchCriteria {
+        bounding_box: Some(BoundingBox::new(
+            Coordinate::new(18.5, -99.5).unwrap(),
+            Coordinate::new(20.0, -98.0).unwrap(),
+        )),
+        ..SiteSearchCriteria::default()
+    };
+    let mut keys: Vec<String> = repo
+        .find_all(&criteria)
+        .unwrap()
+        .into_iter()
+        .map(|site| site.site_key)
+        .collect();
+    keys.sort();
+    assert_eq!(keys, ["CDMX", "PUE"]);
+
+    let manager = SiteManager::new(db.connector(), 10).with_session(Session::system());
+    let center = manager.parse_coordinate("19.4326", "-99.1332").unwrap();
+    let near: Vec<(String, f64)> = manager
+        .within_km(center, 110.0)
+        .unwrap()
+        .into_iter()
+        .map(|(site, distance)| (site.site_key, distance))
+        .collect();
+    assert_eq!(near.len(), 2);
+    assert_eq!(near[0].0, "CDMX");
+    assert_eq!(near[1].0, "PUE");
+    assert!(near[1].1 > 100.0);
+
+    assert_eq!(manager.within_km(center, 50.0).unwrap().len(), 1);
+}
+
+#[test]
+fn geojson_export_with_latest_compliance() {
+    let db = TestDb::new(10);
+    let id_site = SiteRepo::new(db.connector(), 10)
+        .insert(&site("CDMX", "19.4326", "-99.1332"))
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .insert(&site("BAD", "?", "?"))
+        .unwrap();
+    StandardRepo::new(db.connector(), 10)
+        .insert(&Standard {
+            std_active: true,
+            std_name: "NOM-127".to_string(),
+            ..Standard::default()
+        })
+        .unwrap();
+    AnalysisRepo::new(db.connector(), 10)
+        .insert(&Analysis {
+            analysis_active: true,
+            analysis_name: "Nitratos".to_string(),
+            ..Analysis::default()
+        })
+        .unwrap();
+
+    StandardDetailRepo::new(db.connector(), 10)
+        .add(&StandardDetail {
+            id_std_detail: None,
+            id_standard: Some(1),
+            id_analysis: Some(1),
+            min_value: None,
+            max_value: Some(0.5),
+            ..StandardDetail::default()
+        })
+        .unwrap();
+
+    let mut samples = SampleRepo::new(db.connector(), 10);
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    for (day, control_number, result) in [(1, "2024-0001", "0.9"), (8, "2024-0002", "0.2")] {
+        let date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let id_sample = samples
+            .insert(&Sample {
+                sample_active: true,
+                control_number: control_number.to_string(),
+                sampling_date: date,
+                reception: date,
+                id_site: Some(id_site),
+                ..Sample::default()
+            })
+            .unwrap();
+        results
+            .add(&SampleResult {
+                result_active: true,
+                result: result.parse().unwrap(),
+                analysis_date: date,
+                id_analysis: Some(1),
+                id_standard: Some(1),
+                id_sample: Some(id_sample),
+                ..SampleResult::default()
+            })
+            .unwrap();
+    }
+
+    let manager = SiteManager::new(db.connector(), 10).with_session(Session::system());
+    let geojson = manager
+        .geojson(&SiteSearchCriteria::default(), true)
+        .unwrap();
+
+    assert_eq!(geojson["type"], "FeatureCollection");
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2);
+
+    let cdmx = features
+        .iter()
+        .find(|feature| feature["properties"]["site_key"] == "CDMX")
+        .unwrap();
+    assert_eq!(cdmx["geometry"]["type"], "Point");
+    assert_eq!(cdmx["geometry"]["coordinates"][0], -99.1332);
+    assert_eq!(cdmx["geometry"]["coordinates"][1], 19.4326);
+    assert_eq!(
+        cdmx["properties"]["compliance"]["control_number"],
+        "2024-0002"
+    );
+    assert_eq!(cdmx["properties"]["compliance"]["status"], "compliant");
+
+    let bad = features
+        .iter()
+        .find(|feature| feature["properties"]["site_key"] == "BAD")
+        .unwrap();
+    assert!(bad["geometry"].is_null());
+    assert!(bad["properties"]["compliance"].is_null());
+
+    let plain = manager
+        .geojson(&SiteSearchCriteria::default(), false)
+        .unwrap();
+    assert!(plain["features"][0]["properties"]
+        .get("compliance")
+        .is_none());
+}
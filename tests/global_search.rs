@@ -0,0 +1,167 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::search_hit::SearchEntity;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data::repo::migrations::{self, MIGRATIONS};
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::search_repo::{fts_query, SearchRepo};
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::main_console_view::MainConsoleView;
+
+fn client(name: &str) -> Client {
+    Client {
+        id_client: None,
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+fn site(key: &str, name: &str, municipality: &str) -> Site {
+    Site {
+        site_active: true,
+        site_key: key.to_string(),
+        site_name: name.to_string(),
+        site_municipality: Some(municipality.to_string()),
+        ..Site::default()
+    }
+}
+
+fn sample(control_number: &str, project: &str) -> Sample {
+    Sample {
+        sample_active: true,
+        control_number: control_number.to_string(),
+        project: project.to_string(),
+        sampling_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        reception: NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+        ..Sample::default()
+    }
+}
+
+#[test]
+fn migrations_are_applied_once() {
+    let db = TestDb::new(10);
+    let mut conn = db.connector().get_connection().unwrap();
+
+    let last = MIGRATIONS.last().unwrap().version;
+    assert_eq!(migrations::current_version(&conn).unwrap(), last);
+    assert!(migrations::migrate(&mut conn).unwrap().is_empty());
+}
+
+#[test]
+fn finds_mixed_entities_with_highlights() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add(&client("Agua Potable de Monterrey"))
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .add(&site("NL-01", "Pozo Monterrey Norte", "Monterrey"))
+        .unwrap();
+    SampleRepo::new(db.connector(), 10)
+        .add(&sample("2024-0001", "Monitoreo Monterrey"))
+        .unwrap();
+    SampleRepo::new(db.connector(), 10)
+        .add(&sample("2024-0002", "Presas"))
+        .unwrap();
+
+    let hits = SearchRepo::new(db.connector(), 10)
+        .search("monterrey")
+        .unwrap();
+
+    let mut entities: Vec<_> = hits.iter().map(|hit| hit.entity).collect();
+    entities.sort_by_key(|entity| entity.as_str());
+    assert_eq!(
+        entities,
+        [
+            SearchEntity::Client,
+            SearchEntity::Sample,
+            SearchEntity::Site
+        ]
+    );
+    assert!(hits.windows(2).all(|pair| pair[0].rank <= pair[1].rank));
+
+    let client_hit = hits
+        .iter()
+        .find(|hit| hit.entity == SearchEntity::Client)
+        .unwrap();
+    assert_eq!(client_hit.title, "Agua Potable de [Monterrey]");
+
+    let site_hit = hits
+        .iter()
+        .find(|hit| hit.entity == SearchEntity::Site)
+        .unwrap();
+    assert_eq!(site_hit.title, "NL-01 Pozo [Monterrey] Norte");
+}
+
+#[test]
+fn index_follows_updates_and_deletes() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    let search = SearchRepo::new(db.connector(), 10);
+    let id = repo.insert(&client("Laboratorio Sur")).unwrap();
+
+    let mut stored = repo.search_by_id(id).unwrap().unwrap();
+    stored.client_name = "Laboratorio Norte".to_string();
+    repo.modify(&stored).unwrap();
+    assert!(search.search("sur").unwrap().is_empty());
+    assert_eq!(search.search("norte").unwrap()[0].id, id);
+
+    repo.drop(&mut stored).unwrap();
+    assert!(!search.search("norte").unwrap()[0].active);
+
+    repo.delete(&stored).unwrap();
+    assert!(search.search("norte").unwrap().is_empty());
+}
+
+#[test]
+fn matches_prefixes_without_accents() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add(&client("Químicos de León"))
+        .unwrap();
+
+    let search = SearchRepo::new(db.connector(), 10);
+    assert_eq!(search.search("quimic leon").unwrap().len(), 1);
+    assert!(search.search("quimicos norte").unwrap().is_empty());
+}
+
+#[test]
+fn user_input_is_not_fts_syntax() {
+    assert_eq!(fts_query("  "), None);
+    assert_eq!(fts_query("\" \""), None);
+    assert_eq!(
+        fts_query("pozo OR \"norte* NEAR"),
+        Some("\"pozo\"* \"OR\"* \"norte*\"* \"NEAR\"*".to_string())
+    );
+
+    let db = TestDb::new(10);
+    let search = SearchRepo::new(db.connector(), 10);
+    assert!(search.search("a:b (c) -d ^e * OR").unwrap().is_empty());
+}
+
+#[test]
+fn console_lists_hits_and_rejects_empty_text() {
+    let db = TestDb::new(10);
+    ClientRepo::new(db.connector(), 10)
+        .add(&client("Aguas del Norte"))
+        .unwrap();
+
+    let script = "3\n1\nnorte\n1\n \n2\n4\n";
+    let mut output = Vec::new();
+    MainConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("1) [Client 1] Aguas del [Norte]"));
+    assert!(output.contains("[E006_INVALID_FIELD] The search text must not be empty"));
+}
@@ -0,0 +1,555 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::{Local, NaiveDate};
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::analysis_package::AnalysisPackage;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::invoice::Invoice;
+use rust_project::data::model::invoice_line::InvoiceLine;
+use rust_project::data::model::invoice_status::InvoiceStatus;
+use rust_project::data::model::money::Money;
+use rust_project::data::model::quotation::Quotation;
+use rust_project::data::model::role::Role;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data::repo::invoice_line_repo::InvoiceLineRepo;
+use rust_project::data::repo::invoice_repo::InvoiceRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::client_manager::ClientManager;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::invoice_manager::InvoiceManager;
+use rust_project::logic::quotation::{QuoteLine, QuotedItem};
+use rust_project::logic::quotation_manager::QuotationManager;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::client_console_view::ClientConsoleView;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::invoice_console_view::InvoiceConsoleView;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+fn money(text: &str) -> Money {
+    text.parse().unwrap()
+}
+
+fn manager(db: &TestDb) -> InvoiceManager {
+    InvoiceManager::new(db.connector(), 10, 16).with_session(Session::system())
+}
+
+fn balance(db: &TestDb) -> Money {
+    ClientManager::new(db.connector(), 10)
+        .with_session(Session::system())
+        .unpaid_balance(1)
+        .unwrap()
+}
+
+// Cliente 1 con 10% de descuento y su sitio P1; Nitratos (1) a $150 y Dureza
+// (2) a $120.50. La muestra 1 (Nitratos y Dureza) y la 2 (Nitratos) ya se
+// reportaron; la 3 sigue en analisis.
+fn seed(db: &TestDb) {
+    ClientRepo::new(db.connector(), 10)
+        .insert(&Client {
+            client_active: true,
+            client_name: "Agua Clara, S.A.".to_string(),
+            client_rfc: Some("ACL850101AB1".to_string()),
+            discount_percent: 10.0,
+            ..Client::default()
+        })
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            site_key: "P1".to_string(),
+            site_name: "Pozo 1".to_string(),
+            latitude: "19.4".to_string(),
+            longitude: "-99.1".to_string(),
+            id_client: Some(1),
+            ..Site::default()
+        })
+        .unwrap();
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    for (name, price) in [("Nitratos", "150"), ("Dureza", "120.50")] {
+        analyses
+            .insert(&Analysis {
+                analysis_active: true,
+                analysis_name: name.to_string(),
+                price: Some(money(price)),
+                ..Analysis::default()
+            })
+            .unwrap();
+    }
+
+    let mut samples = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    for day in [1, 5, 20] {
+        samples
+            .add(&Sample {
+                sample_active: true,
+                project: "Pozos".to_string(),
+                sampling_date: date(3, day),
+                reception: date(3, day),
+                id_site: Some(1),
+                ..Sample::default()
+            })
+            .unwrap();
+    }
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    for (id_sample, id_analysis) in [(1, 1), (1, 2), (1, 1), (2, 1), (3, 1)] {
+        results
+            .add(&SampleResult {
+                result_active: true,
+                result: "1.0".parse().unwrap(),
+                analysis_date: date(3, 25),
+                id_analysis: Some(id_analysis),
+                id_sample: Some(id_sample),
+                ..SampleResult::default()
+            })
+            .unwrap();
+    }
+    for id_sample in [1, 2, 3] {
+        samples.start_analysis(id_sample).unwrap();
+    }
+    for id_sample in [1, 2] {
+        samples.complete_results(id_sample).unwrap();
+        samples.review(id_sample).unwrap();
+        samples.report(id_sample).unwrap();
+    }
+}
+
+#[test]
+fn reported_samples_become_invoice_lines() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+
+    let billable: Vec<String> = manager
+        .billable(1)
+        .unwrap()
+        .into_iter()
+        .map(|sample| sample.control_number)
+        .collect();
+    assert_eq!(billable, ["2024-1-00001", "2024-1-00002"]);
+
+    let errors = manager
+        .draft(7, &[3, 99], date(4, 1))
+        .unwrap_err()
+        .to_string();
+    assert!(errors.contains("The client 7 does not exist or is inactive"));
+    assert!(errors.contains("the sample 99 does not exist"));
+    assert!(errors.contains("the sample 2024-1-00003 is not from a site of the client"));
+    let errors = manager.draft(1, &[3], date(4, 1)).unwrap_err().to_string();
+    assert!(errors.contains("The sample 2024-1-00003 has not been reported yet"));
+
+    let id = manager.draft(1, &[2, 1], date(4, 1)).unwrap();
+    let document = manager.document(id).unwrap();
+    let lines: Vec<(&str, &str, Money)> = document
+        .lines
+        .iter()
+        .map(|line| {
+            (
+                line.control_number.as_str(),
+                line.line.description.as_str(),
+                line.line.unit_price,
+            )
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            ("2024-1-00001", "Nitratos", money("150")),
+            ("2024-1-00001", "Dureza", money("120.50")),
+            ("2024-1-00002", "Nitratos", money("150")),
+        ]
+    );
+    // 420.50 - 10% = 378.45; +16% IVA = 439.00
+    let invoice = &document.invoice;
+    assert_eq!(invoice.invoice_status, InvoiceStatus::Draft);
+    assert_eq!(invoice.invoice_number, None);
+    assert_eq!(
+        (
+            invoice.subtotal,
+            invoice.discount,
+            invoice.tax,
+            invoice.total
+        ),
+        (
+            money("420.50"),
+            money("42.05"),
+            money("60.55"),
+            money("439")
+        )
+    );
+
+    let text = document.render();
+    assert!(text.starts_with("INVOICE DRAFT 1\nStatus: Draft\nDrafted on 2024-04-01\n"));
+    assert!(text.contains("2024-1-00001   Dureza                                      $120.50"));
+    assert!(text.contains("Discount 10%        -$42.05"));
+
+    assert!(manager.billable(1).unwrap().is_empty());
+    assert!(manager
+        .draft(1, &[1], date(4, 2))
+        .unwrap_err()
+        .to_string()
+        .contains("The sample 2024-1-00001 is already in another invoice"));
+
+    // Aunque se salte el manager, la base no acepta la muestra en otra factura
+    let other = InvoiceRepo::new(db.connector(), 10)
+        .insert(&Invoice {
+            invoice_active: true,
+            id_client: 1,
+            drafted_on: date(4, 2),
+            ..Invoice::default()
+        })
+        .unwrap();
+    let mut lines = InvoiceLineRepo::new(db.connector(), 10);
+    let error = lines
+        .insert(&InvoiceLine {
+            id_invoice: other,
+            ..document.lines[0].line.clone()
+        })
+        .unwrap_err();
+    assert!(matches!(error, Error::InvalidField { .. }));
+    assert!(error
+        .to_string()
+        .contains("The sample is already in another invoice, reload the samples to bill"));
+}
+
+#[test]
+fn quoted_samples_are_billed_at_the_quotation_prices() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut quotations =
+        QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+            .with_session(Session::system());
+    let id_package = quotations
+        .add_package(
+            &AnalysisPackage {
+                package_active: true,
+                package_name: "Basico".to_string(),
+                package_price: Some(money("250")),
+                ..AnalysisPackage::default()
+            },
+            &[1, 2],
+        )
+        .unwrap();
+    quotations.set_discount(1, 20.0).unwrap();
+    let lines = [
+        QuoteLine {
+            item: QuotedItem::Package(id_package),
+            samples: 1,
+        },
+        QuoteLine {
+            item: QuotedItem::Analysis(1),
+            samples: 1,
+        },
+    ];
+    let id_quotation = quotations
+        .quote(
+            &Quotation {
+                quotation_active: true,
+                id_client: 1,
+                id_site: Some(1),
+                project: "Pozos".to_string(),
+                issued_on: date(3, 1),
+                valid_until: date(3, 31),
+                ..Quotation::default()
+            },
+            &lines,
+        )
+        .unwrap();
+    // Los cambios posteriores de precio y descuento no afectan lo cotizado
+    quotations.set_price(1, Some(money("999"))).unwrap();
+    quotations.set_discount(1, 0.0).unwrap();
+    quotations.convert(id_quotation, date(3, 10)).unwrap();
+
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    for (id_sample, id_analysis) in [(4, 1), (4, 2), (5, 1)] {
+        results
+            .add(&SampleResult {
+                result_active: true,
+                result: "1.0".parse().unwrap(),
+                analysis_date: date(3, 25),
+                id_analysis: Some(id_analysis),
+                id_sample: Some(id_sample),
+                ..SampleResult::default()
+            })
+            .unwrap();
+    }
+    let mut samples = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    for id_sample in [4, 5] {
+        samples.start_analysis(id_sample).unwrap();
+        samples.complete_results(id_sample).unwrap();
+        samples.review(id_sample).unwrap();
+        samples.report(id_sample).unwrap();
+    }
+
+    let mut manager = manager(&db);
+    assert!(manager
+        .draft(1, &[1, 4], date(4, 1))
+        .unwrap_err()
+        .to_string()
+        .contains(
+            "Samples from different quotations, or quoted and not quoted, go in separate invoices"
+        ));
+
+    let id = manager.draft(1, &[4, 5], date(4, 1)).unwrap();
+    let document = manager.document(id).unwrap();
+    let lines: Vec<(&str, &str, Money)> = document
+        .lines
+        .iter()
+        .map(|line| {
+            (
+                line.control_number.as_str(),
+                line.line.description.as_str(),
+                line.line.unit_price,
+            )
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            ("2024-1-00004", "Basico", money("250")),
+            ("2024-1-00005", "Nitratos", money("150")),
+        ]
+    );
+    // 400 - 20% de la cotizacion = 320; +16% IVA = 371.20
+    let invoice = &document.invoice;
+    assert_eq!(invoice.discount_percent, 20.0);
+    assert_eq!(
+        (invoice.subtotal, invoice.discount, invoice.total),
+        (money("400"), money("80"), money("371.20"))
+    );
+}
+
+#[test]
+fn quoted_samples_without_a_site_are_billed_to_the_quotation_client() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut quotations =
+        QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+            .with_session(Session::system());
+    let line = QuoteLine {
+        item: QuotedItem::Analysis(2),
+        samples: 1,
+    };
+    let id_quotation = quotations
+        .quote(
+            &Quotation {
+                quotation_active: true,
+                id_client: 1,
+                project: "Pozos".to_string(),
+                issued_on: date(3, 1),
+                valid_until: date(3, 31),
+                ..Quotation::default()
+            },
+            &[line],
+        )
+        .unwrap();
+    let converted = quotations.convert(id_quotation, date(3, 10)).unwrap();
+    let id_sample = converted[0].id_sample.unwrap();
+    assert_eq!(converted[0].id_site, None);
+
+    SampleResultRepo::new(db.connector(), 10)
+        .add(&SampleResult {
+            result_active: true,
+            result: "1.0".parse().unwrap(),
+            analysis_date: date(3, 25),
+            id_analysis: Some(2),
+            id_sample: Some(id_sample),
+            ..SampleResult::default()
+        })
+        .unwrap();
+    let mut samples = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    samples.start_analysis(id_sample).unwrap();
+    samples.complete_results(id_sample).unwrap();
+    samples.review(id_sample).unwrap();
+    samples.report(id_sample).unwrap();
+
+    let mut manager = manager(&db);
+    let billable: Vec<Option<u32>> = manager
+        .billable(1)
+        .unwrap()
+        .iter()
+        .map(|sample| sample.id_sample)
+        .collect();
+    assert!(billable.contains(&Some(id_sample)));
+
+    let id = manager.draft(1, &[id_sample], date(4, 1)).unwrap();
+    let document = manager.document(id).unwrap();
+    assert_eq!(document.lines.len(), 1);
+    assert_eq!(document.lines[0].line.description, "Dureza");
+    assert_eq!(document.lines[0].line.unit_price, money("120.50"));
+    assert!(!manager
+        .billable(1)
+        .unwrap()
+        .iter()
+        .any(|sample| sample.id_sample == Some(id_sample)));
+}
+
+#[test]
+fn issuing_numbers_invoices_and_tracks_the_balance() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+
+    let reviewer = Session {
+        id_signatary: Some(1),
+        user: "eva".to_string(),
+        role: Role::Reviewer,
+    };
+    assert!(matches!(
+        InvoiceManager::new(db.connector(), 10, 16)
+            .with_session(reviewer)
+            .draft(1, &[1], date(4, 1)),
+        Err(Error::Forbidden { .. })
+    ));
+
+    let first = manager.draft(1, &[1], date(4, 1)).unwrap();
+    assert!(manager
+        .pay(first, date(4, 2))
+        .unwrap_err()
+        .to_string()
+        .contains("The invoice 1 cannot go from Draft to Paid"));
+    assert!(manager
+        .issue(first, date(3, 30))
+        .unwrap_err()
+        .to_string()
+        .contains("The date 2024-03-30 is before the invoice was drafted on 2024-04-01"));
+
+    let issued = manager.issue(first, date(4, 2)).unwrap();
+    assert_eq!(issued.invoice_number.as_deref(), Some("F2024-00001"));
+    assert_eq!(issued.issued_on, Some(date(4, 2)));
+    assert_eq!(balance(&db), issued.total);
+
+    // Al cancelarla la muestra se puede volver a facturar con otro folio
+    manager.cancel(first, date(4, 3)).unwrap();
+    assert_eq!(balance(&db), Money::ZERO);
+    let second = manager.draft(1, &[1, 2], date(4, 3)).unwrap();
+    let issued = manager.issue(second, date(4, 3)).unwrap();
+    assert_eq!(issued.invoice_number.as_deref(), Some("F2024-00002"));
+    assert_eq!(balance(&db), money("439"));
+
+    let paid = manager.pay(second, date(4, 20)).unwrap();
+    assert_eq!(paid.invoice_status, InvoiceStatus::Paid);
+    assert_eq!(paid.paid_on, Some(date(4, 20)));
+    assert_eq!(balance(&db), Money::ZERO);
+    assert!(manager
+        .cancel(second, date(4, 21))
+        .unwrap_err()
+        .to_string()
+        .contains("cannot go from Paid to Cancelled"));
+}
+
+#[test]
+fn invoices_are_exported_to_csv_and_json() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    let id = manager.draft(1, &[1, 2], date(4, 1)).unwrap();
+    manager.issue(id, date(4, 2)).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let csv = dir.path().join("facturas.csv");
+    assert_eq!(manager.export(csv.to_str().unwrap(), Some(1)).unwrap(), 1);
+    assert_eq!(
+        std::fs::read_to_string(&csv).unwrap(),
+        "invoice_number,status,client,rfc,drafted_on,issued_on,paid_on,cancelled_on,subtotal,discount,tax,total\n\
+         F2024-00001,issued,\"Agua Clara, S.A.\",ACL850101AB1,2024-04-01,2024-04-02,,,420.50,42.05,60.55,439.00\n"
+    );
+
+    let json = dir.path().join("facturas.JSON");
+    assert_eq!(manager.export(json.to_str().unwrap(), None).unwrap(), 1);
+    let value: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    assert_eq!(value[0]["invoice"]["invoice_number"], "F2024-00001");
+    assert_eq!(value[0]["invoice"]["total"], 43900);
+    assert_eq!(value[0]["client"]["client_name"], "Agua Clara, S.A.");
+    assert_eq!(value[0]["lines"][2]["control_number"], "2024-1-00002");
+    assert_eq!(value[0]["lines"][2]["unit_price"], 15000);
+
+    assert!(manager
+        .export(dir.path().join("facturas.xlsx").to_str().unwrap(), None)
+        .unwrap_err()
+        .to_string()
+        .contains("Unknown export format for"));
+}
+
+#[test]
+fn clients_with_invoices_cannot_be_deleted() {
+    let db = TestDb::new(10);
+    seed(&db);
+    manager(&db).draft(1, &[1], date(4, 1)).unwrap();
+    let mut clients = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    let mut client = clients.search_by_id(1).unwrap().unwrap();
+
+    let error = clients.delete(&client).unwrap_err();
+    assert!(matches!(error, Error::InvalidField { .. }));
+    assert!(error
+        .to_string()
+        .contains("The client \"Agua Clara, S.A.\" has invoices, it can only be deactivated"));
+    assert!(clients.delete_many(&[client.clone()]).is_err());
+
+    // Darlo de baja conserva sus facturas
+    clients.drop(&mut client).unwrap();
+    assert!(!clients.search_by_id(1).unwrap().unwrap().client_active);
+    assert_eq!(
+        InvoiceRepo::new(db.connector(), 10)
+            .search_by_id(1)
+            .unwrap()
+            .unwrap()
+            .id_client,
+        1
+    );
+}
+
+#[test]
+fn console_invoices_and_client_balance() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("facturas.csv");
+    let today = Local::now().date_naive();
+
+    // pendientes, factura de todo lo pendiente, emitirla, listar, exportar
+    let script = format!(
+        "1\n1\n2\n1\n\n4\n1\n{}\n7\n1\n1\n8\n2\n{}\n9\n",
+        today,
+        path.display()
+    );
+    let mut output = Vec::new();
+    InvoiceConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("  2024-1-00001 received 2024-03-01: Nitratos, Dureza"));
+    assert!(output.contains("Invoice 1 added as a draft"));
+    assert!(output.contains("INVOICE DRAFT 1"));
+    let number = format!("F{}-00001", today.format("%Y"));
+    assert!(output.contains(&format!("Invoice 1 ({}) is now Issued", number)));
+    assert!(output.contains(&format!("  1) {} - client 1 - Issued - $439.00", number)));
+    assert!(output.contains(&format!("1 invoices exported to {}", path.display())));
+    assert!(std::fs::read_to_string(&path).unwrap().contains(&number));
+
+    let mut output = Vec::new();
+    ClientConsoleView::new(&db.config, Cursor::new("9\n1\n4\n7\n"), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Billing address: None\nUnpaid balance: $439.00"));
+}
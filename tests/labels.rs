@@ -0,0 +1,264 @@
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::cli::{self, Command, LabelsCommand};
+use rust_project::data::model::role::Role;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::sample_repo::SampleSearchCriteria;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::error::Error;
+use rust_project::i18n::{self, Locale};
+use rust_project::logic::barcode;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::label_manager::LabelManager;
+use rust_project::logic::labels::{self, Label, LabelFormat, Shape};
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::sample_console_view::SampleConsoleView;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn label(number: usize) -> Label {
+    Label {
+        control_number: format!("2024-0-{:05}", number),
+        site_key: Some("P1".to_string()),
+        sampling_date: date(2024, 3, 2),
+    }
+}
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+// Sitio P1 y tres muestras: dos recibidas el 2 de marzo y una el 3
+fn seed(db: &TestDb) {
+    SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            site_key: "P1".to_string(),
+            site_name: "Pozo 1".to_string(),
+            latitude: "19.4".to_string(),
+            longitude: "-99.1".to_string(),
+            ..Site::default()
+        })
+        .unwrap();
+    let mut manager: SampleManager<SampleSearchCriteria> =
+        SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+            .with_session(Session::system());
+    for (id_site, reception) in [(Some(1), date(2024, 3, 2)), (None, date(2024, 3, 2))]
+        .into_iter()
+        .chain([(Some(1), date(2024, 3, 3))])
+    {
+        manager
+            .register(&Sample {
+                sample_active: true,
+                id_site,
+                project: "Pozos".to_string(),
+                sampling_date: date(2024, 3, 1),
+                reception,
+                ..Sample::default()
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn code128_and_qr_encode_the_control_number() {
+    i18n::set_locale(Locale::En);
+    let symbols = barcode::code128_symbols("PJJ123C").unwrap();
+    assert_eq!(symbols.first(), Some(&104));
+    assert_eq!(symbols[symbols.len() - 2], 55);
+    assert_eq!(symbols.last(), Some(&106));
+
+    // 11 modulos por caracter, inicio y verificador, y 13 del paro
+    let modules = barcode::code128_modules("PJJ123C").unwrap();
+    assert_eq!(modules.len(), 11 * (7 + 3) + 2);
+    assert!(modules[0] && modules[modules.len() - 1]);
+
+    assert!(barcode::code128_modules("2024-ñ")
+        .unwrap_err()
+        .to_string()
+        .contains("The character \"ñ\" cannot be encoded in the barcode"));
+
+    let qr = barcode::qr_modules("2024-0-00001").unwrap();
+    assert_eq!(qr.len(), 21);
+    assert!(qr.iter().all(|row| row.len() == 21));
+}
+
+#[test]
+fn labels_fill_sheets_of_twenty_four() {
+    i18n::set_locale(Locale::En);
+    let batch: Vec<Label> = (1..=25).map(label).collect();
+    let pages = labels::layout(&batch).unwrap();
+    assert_eq!(pages.len(), 2);
+
+    let texts: Vec<&str> = pages[1]
+        .shapes
+        .iter()
+        .filter_map(|shape| match shape {
+            Shape::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["2024-0-00025", "P1", "2024-03-02"]);
+
+    assert!(labels::layout(&[])
+        .unwrap_err()
+        .to_string()
+        .contains("There are no samples to label"));
+}
+
+#[test]
+fn sheets_are_written_in_each_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let batch: Vec<Label> = (1..=25).map(label).collect();
+
+    let pdf = dir.path().join("labels.pdf");
+    let written = labels::write(&batch, LabelFormat::Pdf, &pdf).unwrap();
+    assert_eq!(written, [pdf.as_path()]);
+    let bytes = std::fs::read(&pdf).unwrap();
+    let text = String::from_utf8_lossy(&bytes);
+    assert!(text.starts_with("%PDF-"));
+    assert!(text.contains("/Count 2"));
+    assert!(text.trim_end().ends_with("%%EOF"));
+
+    let svg = dir.path().join("labels.svg");
+    let written = labels::write(&batch, LabelFormat::Svg, &svg).unwrap();
+    assert_eq!(written, [svg, dir.path().join("labels-2.svg")]);
+    let sheet = std::fs::read_to_string(&written[1]).unwrap();
+    assert!(sheet.starts_with("<svg"));
+    assert!(sheet.contains("2024-0-00025"));
+
+    let png = labels::render_png(&labels::layout(&batch[..1]).unwrap()[0], 50.0).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+fn cli_parses_the_labels_command() {
+    let (command, config_args) = cli::parse(args(
+        "--locale en labels --output out.pdf 2024-0-00001,2024-0-00002 X",
+    ))
+    .unwrap();
+    assert_eq!(config_args, args("--locale en"));
+    assert_eq!(
+        command,
+        Command::Labels(LabelsCommand {
+            control_numbers: args("2024-0-00001 2024-0-00002 X"),
+            reception: None,
+            format: LabelFormat::Pdf,
+            output: PathBuf::from("out.pdf"),
+        })
+    );
+
+    let (command, _) = cli::parse(args(
+        "labels --reception 2024-03-02 --format png --output hoja",
+    ))
+    .unwrap();
+    let Command::Labels(labels) = command else {
+        panic!("expected the labels command");
+    };
+    assert_eq!(labels.reception, Some(date(2024, 3, 2)));
+    assert_eq!(labels.format, LabelFormat::Png);
+
+    assert_eq!(
+        cli::parse(args("--database labels")).unwrap().0,
+        Command::Menu
+    );
+    assert!(cli::parse(args("labels 2024-0-00001")).is_err());
+    assert!(cli::parse(args("labels --output a.svg --reception 2024-03-02 A")).is_err());
+    assert!(cli::parse(args("labels --output a.svg --format gif A")).is_err());
+}
+
+#[test]
+fn samples_are_labeled_one_by_one_or_by_reception() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let manager = LabelManager::new(db.connector(), 10).with_session(Session::system());
+
+    let batch = manager
+        .labels_for(&args("2024-0-00003 2024-0-00002"))
+        .unwrap();
+    assert_eq!(
+        batch,
+        [
+            Label {
+                control_number: "2024-0-00003".to_string(),
+                site_key: Some("P1".to_string()),
+                sampling_date: date(2024, 3, 1),
+            },
+            Label {
+                control_number: "2024-0-00002".to_string(),
+                site_key: None,
+                sampling_date: date(2024, 3, 1),
+            },
+        ]
+    );
+    let errors = manager
+        .labels_for(&args("2024-0-00009 2024-0-00001 2024-0"))
+        .unwrap_err()
+        .to_string();
+    assert!(errors.contains("The sample 2024-0-00009 does not exist"));
+    assert!(errors.contains("the sample 2024-0 does not exist"));
+
+    let received: Vec<String> = manager
+        .labels_received_on(date(2024, 3, 2))
+        .unwrap()
+        .into_iter()
+        .map(|label| label.control_number)
+        .collect();
+    assert_eq!(received, ["2024-0-00001", "2024-0-00002"]);
+
+    // Escribir las etiquetas pide un rol que registre muestras
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lote.svg");
+    let analyst = Session {
+        id_signatary: None,
+        user: "ana".to_string(),
+        role: Role::Analyst,
+    };
+    assert!(matches!(
+        LabelManager::new(db.connector(), 10)
+            .with_session(analyst)
+            .export(&batch, LabelFormat::Svg, &path),
+        Err(Error::Forbidden { .. })
+    ));
+    assert!(!path.exists());
+    manager.export(&batch, LabelFormat::Svg, &path).unwrap();
+    assert!(path.exists());
+}
+
+#[test]
+fn console_prints_the_labels_of_a_reception() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("recepcion.svg");
+
+    // lote del 2 de marzo en svg, un archivo .gif que no se reconoce, salir
+    let script = format!(
+        "7\n\n2024-03-02\n{}\n7\n2024-0-00003\n{}\n4\n",
+        path.display(),
+        dir.path().join("hoja.gif").display()
+    );
+    let mut output = Vec::new();
+    SampleConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("7) Print labels"));
+    assert!(output.contains(&format!("2 labels written to {}", path.display())));
+    assert!(output.contains("Unknown label format"));
+    let sheet = std::fs::read_to_string(&path).unwrap();
+    assert!(sheet.contains("2024-0-00001") && sheet.contains("2024-0-00002"));
+    assert!(!sheet.contains("2024-0-00003"));
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::sample_status::SampleStatus;
+use rust_project::data::model::signatary::Signatary;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::repo::signatary_repo::SignataryRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::Repository;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::metrics::MetricsManager;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::metrics_console_view::MetricsConsoleView;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+// Cliente "Agua" con un sitio, dos analisis y un signatario; tres muestras
+// recibidas el 1, 5 y 20 de marzo
+fn seed(db: &TestDb) {
+    ClientRepo::new(db.connector(), 10)
+        .insert(&Client {
+            client_active: true,
+            client_name: "Agua".to_string(),
+            ..Client::default()
+        })
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            site_key: "P1".to_string(),
+            site_name: "Pozo 1".to_string(),
+            latitude: "19.4".to_string(),
+            longitude: "-99.1".to_string(),
+            id_client: Some(1),
+            ..Site::default()
+        })
+        .unwrap();
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    for name in ["Nitratos", "Dureza"] {
+        analyses
+            .insert(&Analysis {
+                analysis_active: true,
+                analysis_name: name.to_string(),
+                ..Analysis::default()
+            })
+            .unwrap();
+    }
+    SignataryRepo::new(db.connector(), 10)
+        .insert(&Signatary {
+            signatary_active: true,
+            first_name: "Ana".to_string(),
+            father_last_name: "López".to_string(),
+            ..Signatary::default()
+        })
+        .unwrap();
+
+    let mut samples = SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system());
+    for day in [1, 5, 20] {
+        samples
+            .add(&Sample {
+                sample_active: true,
+                project: "Pozos".to_string(),
+                sampling_date: date(3, day),
+                reception: date(3, day),
+                id_site: Some(1),
+                ..Sample::default()
+            })
+            .unwrap();
+    }
+    samples.start_analysis(1).unwrap();
+    samples.complete_results(1).unwrap();
+    samples.review(1).unwrap();
+    samples.report(1).unwrap();
+    samples.start_analysis(2).unwrap();
+
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    for (id_sample, id_analysis, id_signatary, analysis_date) in [
+        (1, Some(1), Some(1), date(3, 4)),
+        (1, Some(2), Some(1), date(3, 8)),
+        (2, Some(1), None, date(3, 15)),
+    ] {
+        results
+            .add(&SampleResult {
+                result_active: true,
+                result: "1.0".parse().unwrap(),
+                analysis_date,
+                id_analysis,
+                id_signatary,
+                id_sample: Some(id_sample),
+                ..SampleResult::default()
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn turnaround_is_grouped_per_analysis_signatary_and_client() {
+    let db = TestDb::new(10);
+    seed(&db);
+
+    let dashboard = MetricsManager::new(db.connector(), 10, 10)
+        .dashboard(date(3, 25))
+        .unwrap();
+
+    let per_analysis: Vec<(Option<&str>, usize, f64, i64, i64)> = dashboard
+        .per_analysis
+        .iter()
+        .map(|s| {
+            (
+                s.name.as_deref(),
+                s.results,
+                s.mean_days,
+                s.min_days,
+                s.max_days,
+            )
+        })
+        .collect();
+    assert_eq!(
+        per_analysis,
+        vec![
+            (Some("Dureza"), 1, 7.0, 7, 7),
+            (Some("Nitratos"), 2, 6.5, 3, 10),
+        ]
+    );
+
+    // El resultado sin signatario queda en su propio grupo, al final
+    assert_eq!(dashboard.per_signatary.len(), 2);
+    assert_eq!(
+        dashboard.per_signatary[0].name.as_deref(),
+        Some("Ana López")
+    );
+    assert_eq!(dashboard.per_signatary[0].results, 2);
+    assert_eq!(dashboard.per_signatary[1].id, None);
+    assert_eq!(dashboard.per_signatary[1].max_days, 10);
+
+    assert_eq!(dashboard.per_client.len(), 1);
+    assert_eq!(dashboard.per_client[0].results, 3);
+}
+
+#[test]
+fn backlog_and_overdue_use_the_sla() {
+    let db = TestDb::new(10);
+    seed(&db);
+
+    let dashboard = MetricsManager::new(db.connector(), 10, 10)
+        .dashboard(date(3, 25))
+        .unwrap();
+
+    let backlog: Vec<(SampleStatus, usize)> = dashboard
+        .backlog
+        .iter()
+        .map(|count| (count.status, count.samples))
+        .collect();
+    assert_eq!(
+        backlog,
+        vec![
+            (SampleStatus::Received, 1),
+            (SampleStatus::InAnalysis, 1),
+            (SampleStatus::ResultsComplete, 0),
+            (SampleStatus::Reviewed, 0),
+        ]
+    );
+
+    // La muestra 1 ya se reporto; la 2 lleva 20 dias y la 3 apenas 5
+    assert_eq!(dashboard.overdue.len(), 1);
+    assert_eq!(dashboard.overdue[0].id_sample, 2);
+    assert_eq!(dashboard.overdue[0].days_open, 20);
+    assert_eq!(dashboard.overdue[0].days_late, 10);
+
+    let relaxed = MetricsManager::new(db.connector(), 10, 30)
+        .dashboard(date(3, 25))
+        .unwrap();
+    assert!(relaxed.overdue.is_empty());
+}
+
+#[test]
+fn dashboard_is_shown_and_exported_as_json() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("metrics.json");
+
+    let script = format!("1\n2\n{}\n3\n", path.display());
+    let mut output = Vec::new();
+    MetricsConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("  Nitratos: 2 results, mean 6.5, min 3, max 10"));
+    assert!(output.contains("  (not assigned): 1 results"));
+    assert!(output.contains("  In analysis: 1"));
+    assert!(output.contains("Overdue samples: 2"));
+    assert!(output.contains("Dashboard exported to"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["sla_days"], 10);
+    assert_eq!(json["per_client"][0]["name"], "Agua");
+    assert_eq!(json["backlog"][1]["status"], "in_analysis");
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::{Error, ErrorCode};
+
+fn client(name: &str) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+#[test]
+fn versions_start_at_one_and_grow_with_each_change() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    let id = repo.insert(&client("Alfa")).unwrap();
+    let mut stored = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.row_version, 1);
+
+    stored.client_name = "Beta".to_string();
+    repo.modify(&stored).unwrap();
+    let mut stored = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.row_version, 2);
+
+    // Guardar sin cambios no cuenta como cambio
+    repo.modify(&stored).unwrap();
+    assert_eq!(repo.search_by_id(id).unwrap().unwrap().row_version, 2);
+
+    repo.drop(&mut stored).unwrap();
+    assert_eq!(stored.row_version, 3);
+    assert_eq!(repo.search_by_id(id).unwrap().unwrap(), stored);
+}
+
+#[test]
+fn stale_edit_fails_with_both_versions() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    let id = repo.insert(&client("Alfa")).unwrap();
+
+    let mut first = repo.search_by_id(id).unwrap().unwrap();
+    let mut second = first.clone();
+
+    first.client_name = "Alfa Norte".to_string();
+    repo.modify(&first).unwrap();
+
+    second.client_name = "Alfa Sur".to_string();
+    let error = repo.modify(&second).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::Conflict);
+    let Error::Conflict {
+        stored_version,
+        edited_version,
+        differences,
+        ..
+    } = &error
+    else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert_eq!((*stored_version, *edited_version), (2, 1));
+    assert_eq!(
+        differences,
+        &[(
+            "client_name",
+            "\"Alfa Norte\"".to_string(),
+            "\"Alfa Sur\"".to_string()
+        )]
+    );
+
+    let message = error.to_string();
+    assert!(message.contains("Client record 1 was changed by someone else"));
+    assert!(message.contains("client_name: stored \"Alfa Norte\", yours \"Alfa Sur\""));
+
+    // El cambio del primer usuario se conserva
+    assert_eq!(
+        repo.search_by_id(id).unwrap().unwrap().client_name,
+        "Alfa Norte"
+    );
+
+    // Tras recargar, la edicion se aplica
+    let mut reloaded = repo.search_by_id(id).unwrap().unwrap();
+    reloaded.client_name = "Alfa Sur".to_string();
+    repo.modify(&reloaded).unwrap();
+    assert_eq!(repo.search_by_id(id).unwrap().unwrap().row_version, 3);
+}
+
+#[test]
+fn deactivation_by_another_user_is_a_conflict() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    let id = repo.insert(&client("Alfa")).unwrap();
+
+    let mut editing = repo.search_by_id(id).unwrap().unwrap();
+    let mut other = editing.clone();
+    repo.drop(&mut other).unwrap();
+
+    editing.client_name = "Alfa SA".to_string();
+    let Error::Conflict { differences, .. } = repo.modify(&editing).unwrap_err() else {
+        panic!("expected a conflict");
+    };
+    let columns: Vec<&str> = differences.iter().map(|d| d.0).collect();
+    assert_eq!(columns, ["client_active", "client_name"]);
+}
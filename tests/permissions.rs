@@ -0,0 +1,319 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rusqlite::Connection;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::role::{Permission, Role};
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::signatary::Signatary;
+use rust_project::data::repo::client_repo::SearchCriteria;
+use rust_project::data::repo::migrations;
+use rust_project::data_management::Repository;
+use rust_project::error::{Error, ErrorCode};
+use rust_project::logic::client_manager::ClientManager;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::password;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::logic::user_manager::UserManager;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::login_console_view::LoginConsoleView;
+use rust_project::view::main_console_view::MainConsoleView;
+
+fn session(user: &str, role: Role) -> Session {
+    Session {
+        id_signatary: None,
+        user: user.to_string(),
+        role,
+    }
+}
+
+fn signatary(user: &str, role: Role) -> Signatary {
+    Signatary {
+        first_name: "Ana".to_string(),
+        father_last_name: "Lopez".to_string(),
+        user: user.to_string(),
+        passwd: "secreto".to_string(),
+        role,
+        ..Signatary::default()
+    }
+}
+
+fn client(name: &str) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+#[test]
+fn roles_grant_their_permissions() {
+    let allowed = |role: Role| -> Vec<Permission> {
+        Permission::ALL
+            .into_iter()
+            .filter(|permission| role.allows(*permission))
+            .collect()
+    };
+
+    assert_eq!(allowed(Role::Admin), Permission::ALL);
+    assert!(allowed(Role::ReadOnly).is_empty());
+    assert_eq!(allowed(Role::Analyst), vec![Permission::RecordResults]);
+    assert_eq!(
+        allowed(Role::Sampler),
+        vec![Permission::EditSites, Permission::RegisterSamples]
+    );
+    assert!(!Role::Reviewer.allows(Permission::DeleteRecords));
+    assert!(!Role::Reviewer.allows(Permission::ManageQuotations));
+    assert_eq!("Read-only".parse::<Role>().unwrap(), Role::ReadOnly);
+}
+
+#[test]
+fn only_admins_delete_clients() {
+    let db = TestDb::new(10);
+    let mut reviewer =
+        ClientManager::new(db.connector(), 10).with_session(session("beto", Role::Reviewer));
+    reviewer.add(&client("Agua Clara")).unwrap();
+    let stored = reviewer.find_all(&SearchCriteria::default()).unwrap();
+
+    let error = reviewer.delete(&stored[0]).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Forbidden {
+            role: Role::Reviewer,
+            permission: Permission::DeleteRecords,
+        }
+    ));
+    assert_eq!(error.code(), ErrorCode::Forbidden);
+    assert_eq!(
+        error.to_string(),
+        "The Reviewer role is not allowed to permanently delete records"
+    );
+
+    let mut read_only =
+        ClientManager::new(db.connector(), 10).with_session(session("eva", Role::ReadOnly));
+    assert!(matches!(
+        read_only.add(&client("Pozos del Norte")),
+        Err(Error::Forbidden { .. })
+    ));
+    // Sin sesion explicita el manager solo puede consultar
+    assert_eq!(Session::default().role, Role::ReadOnly);
+    assert!(matches!(
+        ClientManager::new(db.connector(), 10).add(&client("Pozos del Norte")),
+        Err(Error::Forbidden { .. })
+    ));
+
+    let mut admin =
+        ClientManager::new(db.connector(), 10).with_session(session("root", Role::Admin));
+    admin.delete(&stored[0]).unwrap();
+    assert!(admin
+        .find_all(&SearchCriteria::default())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn sample_transitions_follow_the_role() {
+    let db = TestDb::new(10);
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    let manager = |role| {
+        SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+            .with_session(session("ana", role))
+    };
+
+    let id = manager(Role::Sampler)
+        .register(&Sample {
+            sample_active: true,
+            sampling_date: date,
+            reception: date,
+            ..Sample::default()
+        })
+        .unwrap()
+        .id_sample
+        .unwrap();
+    assert!(matches!(
+        manager(Role::Sampler).start_analysis(id),
+        Err(Error::Forbidden { .. })
+    ));
+
+    let mut analyst = manager(Role::Analyst);
+    analyst.start_analysis(id).unwrap();
+    analyst.complete_results(id).unwrap();
+    assert!(matches!(
+        analyst.review(id),
+        Err(Error::Forbidden {
+            permission: Permission::ReviewResults,
+            ..
+        })
+    ));
+    manager(Role::Reviewer).review(id).unwrap();
+}
+
+#[test]
+fn login_and_role_assignment() {
+    let db = TestDb::new(10);
+    let mut setup = UserManager::new(db.connector(), 10);
+    assert!(setup.needs_setup().unwrap());
+    assert!(matches!(
+        setup.add_user(&signatary("beto", Role::Analyst)),
+        Err(Error::Forbidden { .. })
+    ));
+
+    // El primer usuario siempre es administrador, aunque pida otro rol
+    let id_admin = setup
+        .add_first_admin(&signatary("ana", Role::ReadOnly))
+        .unwrap();
+    assert!(!setup.needs_setup().unwrap());
+    assert!(setup
+        .add_first_admin(&signatary("otro", Role::Admin))
+        .unwrap_err()
+        .to_string()
+        .contains("There is already an active administrator"));
+    let admin = setup.login("ana", "secreto").unwrap();
+    let mut setup = setup.with_session(admin);
+    let id_analyst = setup.add_user(&signatary("beto", Role::Analyst)).unwrap();
+    assert!(setup
+        .add_user(&signatary(" beto ", Role::Sampler))
+        .unwrap_err()
+        .to_string()
+        .contains("The user name beto is already taken"));
+
+    assert!(matches!(
+        setup.login("ana", "otra"),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(setup.login("nadie", "secreto").is_err());
+    let admin = setup.login(" ana ", "secreto").unwrap();
+    assert_eq!(
+        (admin.id_signatary, admin.role),
+        (Some(id_admin), Role::Admin)
+    );
+
+    let mut analyst =
+        UserManager::new(db.connector(), 10).with_session(setup.login("beto", "secreto").unwrap());
+    assert!(matches!(
+        analyst.assign_role(id_analyst, Role::Admin),
+        Err(Error::Forbidden { .. })
+    ));
+
+    let mut manager = UserManager::new(db.connector(), 10).with_session(admin);
+    // Solo se guarda el hash con sal: dos usuarios con la misma contraseña
+    // quedan con hashes distintos
+    let stored: Vec<String> = manager
+        .users()
+        .unwrap()
+        .into_iter()
+        .map(|user| user.passwd)
+        .collect();
+    assert!(stored.iter().all(|passwd| passwd.starts_with("$argon2")));
+    assert_ne!(stored[0], stored[1]);
+    assert_eq!(
+        manager
+            .assign_role(id_analyst, Role::Reviewer)
+            .unwrap()
+            .role,
+        Role::Reviewer
+    );
+    assert!(manager
+        .assign_role(id_admin, Role::ReadOnly)
+        .unwrap_err()
+        .to_string()
+        .contains("The last active administrator cannot lose the role"));
+}
+
+#[test]
+fn migration_hashes_plain_passwords() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/db/script.sql"));
+    let mut conn = Connection::open(dir.path().join("old.db")).unwrap();
+    conn.execute_batch(&script.unwrap()).unwrap();
+    conn.execute_batch(
+        "INSERT INTO Signatary (signatary_active, first_name, second_name, father_last_name,
+                                mother_last_name, user, passwd)
+         VALUES (1, 'Ana', '', 'Lopez', '', 'ana', 'secreto'),
+                (1, 'Beto', '', 'Ruiz', '', 'beto', '');",
+    )
+    .unwrap();
+
+    migrations::migrate(&mut conn).unwrap();
+
+    let mut stmt = conn
+        .prepare("SELECT passwd FROM Signatary ORDER BY id_signatary")
+        .unwrap();
+    let stored: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    assert!(password::verify("secreto", &stored[0]));
+    assert!(!password::verify("otra", &stored[0]));
+    assert_eq!(stored[1], "");
+    assert!(!password::verify("", &stored[1]));
+}
+
+#[test]
+fn first_sign_in_creates_the_administrator() {
+    let db = TestDb::new(10);
+
+    // nombre sin usuario, luego la cuenta completa y el inicio de sesion
+    let script = "Ana\nLopez\n\n\nsecreto\nAna\nLopez\n\nana\nsecreto\nana\nsecreto\n";
+    let mut output = Vec::new();
+    let session = LoginConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .login()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("There is no administrator yet"));
+    assert!(output.contains("The user name must not be empty"));
+    assert!(output.contains("User ana was added"));
+    assert!(output.contains("Welcome, ana (Administrator)"));
+    assert_eq!((session.user.as_str(), session.role), ("ana", Role::Admin));
+    assert!(!UserManager::new(db.connector(), 10).needs_setup().unwrap());
+}
+
+#[test]
+fn console_hides_what_the_role_cannot_use() {
+    let db = TestDb::new(10);
+    let mut setup = UserManager::new(db.connector(), 10).with_session(Session::system());
+    setup.add_user(&signatary("ana", Role::Admin)).unwrap();
+    setup.add_user(&signatary("eva", Role::ReadOnly)).unwrap();
+
+    let mut output = Vec::new();
+    let read_only = LoginConsoleView::new(
+        &db.config,
+        Cursor::new("eva\nmal\neva\nsecreto\n"),
+        &mut output,
+    )
+    .login()
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("The user or the password is not correct"));
+    assert!(output.contains("Welcome, eva (Read only)"));
+
+    // usuarios (oculto), clientes, salir de clientes, salir
+    let mut output = Vec::new();
+    MainConsoleView::new(&db.config, Cursor::new("7\n1\n7\n4\n"), &mut output)
+        .with_session(read_only)
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains(
+        "6) Metrics\n8) Sampling schedule\n9) Quotations\n10) Invoices\nSelect an option"
+    ));
+    assert!(!output.contains("7) Users"));
+    assert!(output.contains("Invalid option"));
+    assert!(output.contains("1) List clients\n6) Search client\n7) Exit\n9) Client details"));
+
+    // usuarios, listar, salir, salir
+    let mut output = Vec::new();
+    MainConsoleView::new(&db.config, Cursor::new("7\n1\n4\n4\n"), &mut output)
+        .with_session(session("ana", Role::Admin))
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("6) Metrics\n7) Users"));
+    assert!(output.contains("ana: Ana Lopez - Administrator"));
+    assert!(output.contains("eva: Ana Lopez - Read only"));
+}
@@ -0,0 +1,416 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::{Local, NaiveDate};
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::analysis_package::AnalysisPackage;
+use rust_project::data::model::client::Client;
+use rust_project::data::model::money::Money;
+use rust_project::data::model::quotation::Quotation;
+use rust_project::data::model::role::Role;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::client_repo::ClientRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::Error;
+use rust_project::logic::client_manager::ClientManager;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::quotation::{QuoteLine, QuotedItem};
+use rust_project::logic::quotation_manager::QuotationManager;
+use rust_project::logic::session::Session;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::quotation_console_view::QuotationConsoleView;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn money(text: &str) -> Money {
+    text.parse().unwrap()
+}
+
+fn manager(db: &TestDb) -> QuotationManager {
+    QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+        .with_session(Session::system())
+}
+
+fn session(role: Role) -> Session {
+    Session {
+        id_signatary: Some(1),
+        user: "eva".to_string(),
+        role,
+    }
+}
+
+// Cliente 1 con su sitio P1 y los analisis Nitratos (1), Dureza (2) y
+// Coliformes (3), todavia sin precio
+fn seed(db: &TestDb) {
+    ClientRepo::new(db.connector(), 10)
+        .insert(&Client {
+            client_active: true,
+            client_name: "Agua Clara".to_string(),
+            client_rfc: Some("ACL850101AB1".to_string()),
+            ..Client::default()
+        })
+        .unwrap();
+    SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            site_key: "P1".to_string(),
+            site_name: "Pozo 1".to_string(),
+            latitude: "19.4".to_string(),
+            longitude: "-99.1".to_string(),
+            id_client: Some(1),
+            ..Site::default()
+        })
+        .unwrap();
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    for name in ["Nitratos", "Dureza", "Coliformes"] {
+        analyses
+            .insert(&Analysis {
+                analysis_active: true,
+                analysis_name: name.to_string(),
+                ..Analysis::default()
+            })
+            .unwrap();
+    }
+}
+
+// Precios, el paquete "Basico" (Nitratos y Dureza) y 10% de descuento al cliente
+fn seed_prices(manager: &mut QuotationManager) -> u32 {
+    for (id, price) in [(1, "150"), (2, "120.50"), (3, "$300.00")] {
+        manager.set_price(id, Some(money(price))).unwrap();
+    }
+    manager.set_discount(1, 10.0).unwrap();
+    manager
+        .add_package(
+            &AnalysisPackage {
+                package_active: true,
+                package_name: "Basico".to_string(),
+                ..AnalysisPackage::default()
+            },
+            &[2, 1],
+        )
+        .unwrap()
+}
+
+fn quotation() -> Quotation {
+    Quotation {
+        quotation_active: true,
+        id_client: 1,
+        id_site: Some(1),
+        project: "Monitoreo 2024".to_string(),
+        issued_on: date(2024, 3, 1),
+        valid_until: date(2024, 3, 31),
+        ..Quotation::default()
+    }
+}
+
+#[test]
+fn money_is_parsed_and_formatted() {
+    assert_eq!(money("$1,234.5").cents(), 123450);
+    assert_eq!(money(".25").cents(), 25);
+    assert_eq!(money("1200").to_string(), "$1,200.00");
+    assert_eq!(Money::from_cents(-123456789).to_string(), "-$1,234,567.89");
+    for invalid in ["", "$", "-5", "1.234", "12a"] {
+        assert!(invalid.parse::<Money>().is_err(), "{}", invalid);
+    }
+
+    assert_eq!(money("1513.80").percent(16.0), money("242.21"));
+    assert_eq!(money("10").percent(12.5), money("1.25"));
+    let total: Money = [money("1"), money("2.5")].into_iter().sum();
+    assert_eq!(total * 3, money("10.50"));
+}
+
+#[test]
+fn packages_are_priced_from_their_analyses() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+
+    let reviewer = QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+        .with_session(session(Role::Reviewer))
+        .set_price(1, Some(money("150")));
+    assert!(matches!(reviewer, Err(Error::Forbidden { .. })));
+
+    let id_package = seed_prices(&mut manager);
+    let package = manager.find_package("  básico ").unwrap().unwrap();
+    assert_eq!(package.id_package, Some(id_package));
+    assert_eq!(manager.package_price(&package).unwrap(), money("270.50"));
+    let names: Vec<String> = manager
+        .package_analyses(id_package)
+        .unwrap()
+        .into_iter()
+        .map(|analysis| analysis.analysis_name)
+        .collect();
+    assert_eq!(names, ["Nitratos", "Dureza"]);
+
+    let errors = manager
+        .add_package(
+            &AnalysisPackage {
+                package_active: true,
+                package_name: "BASICO".to_string(),
+                ..AnalysisPackage::default()
+            },
+            &[],
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(errors.contains("There is already a package named BASICO"));
+    assert!(errors.contains("the package needs at least one analysis"));
+
+    manager.set_price(2, None).unwrap();
+    assert!(manager
+        .package_price(&package)
+        .unwrap_err()
+        .to_string()
+        .contains("Dureza has no price"));
+    let own_price = AnalysisPackage {
+        package_price: Some(money("250")),
+        ..package
+    };
+    assert_eq!(manager.package_price(&own_price).unwrap(), money("250"));
+
+    assert!(manager
+        .set_discount(1, 120.0)
+        .unwrap_err()
+        .to_string()
+        .contains("The discount 120 must be between 0 and 100"));
+}
+
+#[test]
+fn quotations_freeze_prices_discount_and_tax() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    let id_package = seed_prices(&mut manager);
+
+    let errors = manager
+        .quote(
+            &Quotation {
+                id_client: 7,
+                valid_until: date(2024, 2, 1),
+                ..quotation()
+            },
+            &[QuoteLine {
+                item: QuotedItem::Analysis(3),
+                samples: 0,
+            }],
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(errors.contains("The client 7 does not exist or is inactive"));
+    assert!(errors.contains("valid until 2024-02-01, before its issue date 2024-03-01"));
+    assert!(errors.contains("Coliformes needs at least one sample"));
+
+    let lines = [
+        QuoteLine {
+            item: QuotedItem::Package(id_package),
+            samples: 4,
+        },
+        QuoteLine {
+            item: QuotedItem::Analysis(3),
+            samples: 2,
+        },
+    ];
+    let id = manager.quote(&quotation(), &lines).unwrap();
+    // Los cambios de precio y descuento posteriores no cambian la cotizacion
+    manager.set_price(1, Some(money("200"))).unwrap();
+    manager.set_discount(1, 0.0).unwrap();
+
+    let document = manager.document(id).unwrap();
+    assert_eq!(document.quotation.discount_percent, 10.0);
+    assert_eq!(document.quotation.tax_percent, 16);
+    assert_eq!(document.lines[0].amount, money("1082"));
+    assert_eq!(document.lines[1].amount, money("600"));
+    assert_eq!(
+        (
+            document.totals.subtotal,
+            document.totals.discount,
+            document.totals.tax,
+            document.totals.total
+        ),
+        (
+            money("1682"),
+            money("168.20"),
+            money("242.21"),
+            money("1756.01")
+        )
+    );
+
+    let text = document.render();
+    assert!(text.starts_with("QUOTATION 1\nIssued on 2024-03-01, valid until 2024-03-31\n"));
+    assert!(text.contains("Client: Agua Clara\nRFC: ACL850101AB1\nSite: P1 Pozo 1"));
+    assert!(text.contains(
+        "Basico                                    4        $270.50      $1,082.00\n  Nitratos, Dureza\n"
+    ));
+    assert!(text.contains("Discount 10%       -$168.20"));
+    assert!(text.contains("Total      $1,756.01"));
+}
+
+#[test]
+fn quotations_only_use_sites_of_their_client() {
+    let db = TestDb::new(10);
+    seed(&db);
+    ClientRepo::new(db.connector(), 10)
+        .insert(&Client {
+            client_active: true,
+            client_name: "Pozos del Norte".to_string(),
+            ..Client::default()
+        })
+        .unwrap();
+    let mut manager = manager(&db);
+    seed_prices(&mut manager);
+    let lines = [QuoteLine {
+        item: QuotedItem::Analysis(1),
+        samples: 1,
+    }];
+
+    let other = Quotation {
+        id_client: 2,
+        ..quotation()
+    };
+    assert!(manager
+        .quote(&other, &lines)
+        .unwrap_err()
+        .to_string()
+        .contains("The site P1 does not belong to the client 2"));
+    let without_site = Quotation {
+        id_site: None,
+        ..other
+    };
+    assert!(manager.quote(&without_site, &lines).is_ok());
+}
+
+#[test]
+fn accepted_quotations_become_samples_once() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    let id_package = seed_prices(&mut manager);
+    let lines = [
+        QuoteLine {
+            item: QuotedItem::Package(id_package),
+            samples: 2,
+        },
+        QuoteLine {
+            item: QuotedItem::Analysis(3),
+            samples: 1,
+        },
+    ];
+    assert!(matches!(
+        QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+            .with_session(session(Role::Reviewer))
+            .quote(&quotation(), &lines),
+        Err(Error::Forbidden { .. })
+    ));
+    let id = manager.quote(&quotation(), &lines).unwrap();
+
+    assert!(matches!(
+        QuotationManager::new(db.connector(), 10, ControlNumberPattern::default(), 16)
+            .with_session(session(Role::Analyst))
+            .convert(id, date(2024, 3, 5)),
+        Err(Error::Forbidden { .. })
+    ));
+    assert!(manager
+        .convert(id, date(2024, 4, 1))
+        .unwrap_err()
+        .to_string()
+        .contains("The quotation 1 expired on 2024-03-31"));
+
+    let samples = manager.convert(id, date(2024, 3, 5)).unwrap();
+    let numbers: Vec<&str> = samples
+        .iter()
+        .map(|sample| sample.control_number.as_str())
+        .collect();
+    assert_eq!(numbers, ["2024-1-00001", "2024-1-00002", "2024-1-00003"]);
+    assert!(samples.iter().all(|sample| sample.id_quotation == Some(id)
+        && sample.id_site == Some(1)
+        && sample.reception == date(2024, 3, 5)
+        && sample.project == "Monitoreo 2024"));
+
+    let expected = |index: usize| -> Vec<String> {
+        manager
+            .expected_analyses(samples[index].id_sample.unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|analysis| analysis.analysis_name)
+            .collect()
+    };
+    assert_eq!(expected(1), ["Nitratos", "Dureza"]);
+    assert_eq!(expected(2), ["Coliformes"]);
+
+    assert!(manager
+        .convert(id, date(2024, 3, 6))
+        .unwrap_err()
+        .to_string()
+        .contains("The quotation 1 was already converted into samples on 2024-03-05"));
+    assert!(manager
+        .document(id)
+        .unwrap()
+        .render()
+        .contains("Converted into samples on 2024-03-05"));
+}
+
+#[test]
+fn clients_with_quotations_cannot_be_deleted() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    seed_prices(&mut manager);
+    let line = QuoteLine {
+        item: QuotedItem::Analysis(1),
+        samples: 1,
+    };
+    manager.quote(&quotation(), &[line]).unwrap();
+
+    let mut clients = ClientManager::new(db.connector(), 10).with_session(Session::system());
+    let client = clients.search_by_id(1).unwrap().unwrap();
+    assert!(clients
+        .delete(&client)
+        .unwrap_err()
+        .to_string()
+        .contains("The client \"Agua Clara\" has quotations, it can only be deactivated"));
+    assert!(clients.search_by_id(1).unwrap().is_some());
+}
+
+#[test]
+fn console_quotes_and_converts() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cotizacion.txt");
+    let today = Local::now().date_naive();
+
+    // precios de Nitratos y Dureza, paquete sin precio propio, 10% al
+    // cliente 1, cotizar 4 Basico y 2 Nitratos en P1, guardarla, convertirla
+    let script = format!(
+        "2\nNitratos\n1\n150\n2\nDureza\n1\n$120.50\n3\nBasico\n2\nnitratos, Dureza\n4\n5\n1\n10\n\
+         6\n1\nP1\nMonitoreo\nbasico\n4\nFosforo\nNitratos\n2\n\n7\n1\n{}\n8\n1\n{}\n9\n",
+        path.display(),
+        today
+    );
+    let mut output = Vec::new();
+    QuotationConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Price of Dureza: $120.50"));
+    assert!(output.contains("  1) Basico: $270.50 (Nitratos, Dureza)"));
+    assert!(output.contains("Discount of Agua Clara: 10%"));
+    assert!(output.contains("There is no package or analysis named Fosforo"));
+    assert!(output.contains("Quotation 1 added"));
+    assert!(output.contains(&format!("Quotation saved to {}", path.display())));
+    assert!(output.contains("Quotation 1 converted into 6 samples"));
+    assert!(output.contains(&format!("  {}-1-00005: Nitratos", today.format("%Y"))));
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.starts_with("QUOTATION 1\n"));
+    // 1,082 + 300 = 1,382; -10% = 1,243.80; +16% IVA = 1,442.81
+    assert!(saved.contains("Total      $1,442.81"));
+}
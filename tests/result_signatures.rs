@@ -0,0 +1,240 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::TestDb;
+use rust_project::data::model::role::Role;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::signatary::Signatary;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::table::Patch;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::{Error, ErrorCode, Result};
+use rust_project::logic::result_manager::ResultManager;
+use rust_project::logic::session::Session;
+use rust_project::logic::signature::{self, SignatureStatus};
+use rust_project::logic::user_manager::UserManager;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::sample_console_view::SampleConsoleView;
+
+fn add_user(db: &TestDb, user: &str, role: Role) -> Session {
+    let mut users = UserManager::new(db.connector(), 10).with_session(Session::system());
+    users
+        .add_user(&Signatary {
+            first_name: "Ana".to_string(),
+            father_last_name: "Lopez".to_string(),
+            user: user.to_string(),
+            passwd: format!("{}-clave", user),
+            role,
+            ..Signatary::default()
+        })
+        .unwrap();
+    users.login(user, &format!("{}-clave", user)).unwrap()
+}
+
+fn record(db: &TestDb, value: &str) -> u32 {
+    ResultManager::new(db.connector(), 10)
+        .with_session(Session::system())
+        .record(&SampleResult {
+            result_active: true,
+            result: value.parse().unwrap(),
+            analysis_date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            ..SampleResult::default()
+        })
+        .unwrap()
+}
+
+#[test]
+fn payload_hash_covers_the_signed_data() {
+    let result = SampleResult {
+        id_sample_result: Some(7),
+        result: "0.25".parse().unwrap(),
+        result_unit: Some("mg/L".to_string()),
+        analysis_date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+        ..SampleResult::default()
+    };
+    let signed_at = Utc.with_ymd_and_hms(2024, 3, 5, 10, 30, 0).unwrap();
+    let hash = signature::payload_hash(&result, 2, signed_at).unwrap();
+    assert_eq!(hash.len(), 64);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+    // Los cambios de estado de la fila no alteran lo firmado
+    let dropped = SampleResult {
+        result_active: false,
+        row_version: 3,
+        ..result.clone()
+    };
+    assert_eq!(
+        signature::payload_hash(&dropped, 2, signed_at).unwrap(),
+        hash
+    );
+
+    let edited = SampleResult {
+        result: "0.2".parse().unwrap(),
+        ..result.clone()
+    };
+    assert_ne!(
+        signature::payload_hash(&edited, 2, signed_at).unwrap(),
+        hash
+    );
+    assert_ne!(
+        signature::payload_hash(&result, 3, signed_at).unwrap(),
+        hash
+    );
+}
+
+#[test]
+fn signing_locks_the_result() {
+    let db = TestDb::new(10);
+    let reviewer = add_user(&db, "beto", Role::Reviewer);
+    let id = record(&db, "0.25");
+    let mut manager = ResultManager::new(db.connector(), 10).with_session(reviewer.clone());
+
+    assert!(matches!(
+        manager.sign(id, "beto", "otra"),
+        Err(Error::InvalidField { .. })
+    ));
+    let signed = manager.sign(id, "beto", "beto-clave").unwrap();
+    assert_eq!(signed.id_signatary, reviewer.id_signatary.unwrap());
+    assert_eq!(manager.signature_of(id).unwrap(), Some(signed.clone()));
+
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    let mut stored = results.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.id_signatary, reviewer.id_signatary);
+
+    stored.result = "0.3".parse().unwrap();
+    let error = manager.modify(&stored).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::Locked);
+    assert_eq!(
+        error.to_string(),
+        "SampleResult record 1 is signed and can no longer be modified or deleted"
+    );
+
+    // Los triggers de la tabla protegen el resultado tambien fuera del manager
+    let locked = |result: Result<()>| {
+        matches!(
+            result,
+            Err(Error::Locked {
+                entity: "SampleResult",
+                id: None
+            })
+        )
+    };
+    assert!(locked(results.modify(&stored)));
+    assert!(locked(results.modify_many(
+        std::slice::from_ref(&stored),
+        &Patch::new().set("result_active", false).unwrap()
+    )));
+    assert!(locked(results.drop(&mut stored.clone())));
+    assert!(locked(results.delete(&stored)));
+    assert_eq!(
+        results
+            .search_by_id(id)
+            .unwrap()
+            .unwrap()
+            .result
+            .to_string(),
+        "0.25"
+    );
+    assert!(manager
+        .sign(id, "beto", "beto-clave")
+        .unwrap_err()
+        .to_string()
+        .contains("The result is already signed"));
+
+    let checks = manager.verify_all().unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, SignatureStatus::Valid);
+}
+
+#[test]
+fn only_the_signed_in_reviewer_signs() {
+    let db = TestDb::new(10);
+    let analyst = add_user(&db, "ana", Role::Analyst);
+    let reviewer = add_user(&db, "beto", Role::Reviewer);
+    add_user(&db, "carla", Role::Reviewer);
+    let id = record(&db, "12");
+
+    assert!(matches!(
+        ResultManager::new(db.connector(), 10)
+            .with_session(analyst)
+            .sign(id, "ana", "ana-clave"),
+        Err(Error::Forbidden { .. })
+    ));
+    assert!(ResultManager::new(db.connector(), 10)
+        .with_session(reviewer)
+        .sign(id, "carla", "carla-clave")
+        .unwrap_err()
+        .to_string()
+        .contains("The signature must be made with the credentials of the signed-in user"));
+    // Sin sesion de usuario (configuracion inicial) firma quien se autentique,
+    // si su rol lo permite
+    assert!(matches!(
+        ResultManager::new(db.connector(), 10)
+            .with_session(Session::system())
+            .sign(id, "ana", "ana-clave"),
+        Err(Error::Forbidden { .. })
+    ));
+    ResultManager::new(db.connector(), 10)
+        .with_session(Session::system())
+        .sign(id, "carla", "carla-clave")
+        .unwrap();
+}
+
+#[test]
+fn verification_detects_tampering() {
+    let db = TestDb::new(10);
+    add_user(&db, "beto", Role::Reviewer);
+    let tampered = record(&db, "0.25");
+    let deleted = record(&db, "7");
+    let untouched = record(&db, "ND");
+    let mut manager = ResultManager::new(db.connector(), 10).with_session(Session::system());
+    for id in [tampered, deleted, untouched] {
+        manager.sign(id, "beto", "beto-clave").unwrap();
+    }
+
+    // Cambios hechos directo en el archivo, quitando antes las protecciones
+    let conn = db.connector().get_connection().unwrap();
+    conn.execute_batch(&format!(
+        "PRAGMA foreign_keys = OFF;
+         DROP TRIGGER SignedResultNoUpdate;
+         DROP TRIGGER SignedResultNoDelete;
+         UPDATE SampleResult SET result = '0.025' WHERE id_sample_result = {tampered};
+         DELETE FROM SampleResult WHERE id_sample_result = {deleted};"
+    ))
+    .unwrap();
+
+    let statuses: Vec<(u32, SignatureStatus)> = manager
+        .verify_all()
+        .unwrap()
+        .into_iter()
+        .map(|check| (check.signature.id_sample_result, check.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (tampered, SignatureStatus::Tampered),
+            (deleted, SignatureStatus::Missing),
+            (untouched, SignatureStatus::Valid),
+        ]
+    );
+}
+
+#[test]
+fn console_signs_and_verifies() {
+    let db = TestDb::new(10);
+    let reviewer = add_user(&db, "beto", Role::Reviewer);
+    let id = record(&db, "0.25");
+
+    let script = format!("5\n{id}\nbeto\nbeto-clave\n6\n4\n");
+    let mut output = Vec::new();
+    SampleConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(reviewer)
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains(&format!("Result {} signed on", id)));
+    assert!(output.contains("1 signatures checked, 0 with problems"));
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::data_management::Repository;
+use rust_project::error::Error;
+use rust_project::logic::site_manager::SiteManager;
+use rust_project::logic::trend::{percentile, stats, OutlierFlag};
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::site_console_view::SiteConsoleView;
+
+// Sitio P1 con nitratos mensuales; el 100 es un error de captura (10.0)
+const NITRATES: [&str; 8] = ["10", "11", "9", "10", "12", "100", "ND", "10.5"];
+
+fn seed(db: &TestDb) {
+    let mut sites = SiteRepo::new(db.connector(), 10);
+    for key in ["P1", "P2"] {
+        sites
+            .insert(&Site {
+                site_active: true,
+                site_key: key.to_string(),
+                site_name: format!("Pozo {}", key),
+                latitude: "19.4".to_string(),
+                longitude: "-99.1".to_string(),
+                ..Site::default()
+            })
+            .unwrap();
+    }
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    for name in ["Nitratos", "Dureza"] {
+        analyses
+            .insert(&Analysis {
+                analysis_active: true,
+                analysis_name: name.to_string(),
+                ..Analysis::default()
+            })
+            .unwrap();
+    }
+
+    let mut samples = SampleRepo::new(db.connector(), 10);
+    let mut results = SampleResultRepo::new(db.connector(), 10);
+    let mut add = |month: u32, id_site: u32, id_analysis: u32, result: &str| {
+        let date = NaiveDate::from_ymd_opt(2024, month, 1).unwrap();
+        let id_sample = samples
+            .insert(&Sample {
+                sample_active: true,
+                control_number: format!("{}-{}-{:02}", id_site, id_analysis, month),
+                sampling_date: date,
+                reception: date,
+                id_site: Some(id_site),
+                ..Sample::default()
+            })
+            .unwrap();
+        results
+            .add(&SampleResult {
+                result_active: true,
+                result: result.parse().unwrap(),
+                analysis_date: date,
+                id_analysis: Some(id_analysis),
+                id_sample: Some(id_sample),
+                ..SampleResult::default()
+            })
+            .unwrap();
+    };
+    for (month, result) in (1..).zip(NITRATES) {
+        add(month, 1, 1, result);
+    }
+    // Otro sitio y otro analisis no entran en la serie
+    add(1, 2, 1, "500");
+    add(2, 1, 2, "300");
+}
+
+#[test]
+fn percentiles_and_statistics() {
+    assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0], 50.0), 2.5);
+    let tens: Vec<f64> = (1..=10).map(f64::from).collect();
+    assert!((percentile(&tens, 90.0) - 9.1).abs() < 1e-9);
+    assert_eq!(percentile(&[7.0], 25.0), 7.0);
+
+    let summary = stats(&[4.0, 2.0, 6.0, 8.0]).unwrap();
+    assert_eq!(summary.count, 4);
+    assert_eq!(summary.mean, 5.0);
+    assert_eq!(summary.median, 5.0);
+    assert_eq!((summary.min, summary.max), (2.0, 8.0));
+    assert!((summary.std_dev - 5.0_f64.sqrt()).abs() < 1e-9);
+    assert!(stats(&[]).is_none());
+}
+
+#[test]
+fn trend_flags_transcription_errors() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let manager = SiteManager::new(db.connector(), 10);
+
+    let trend = manager.result_trend(1, 1, 3.0).unwrap();
+    let results: Vec<String> = trend
+        .points
+        .iter()
+        .map(|point| point.point.result.to_string())
+        .collect();
+    assert_eq!(results, NITRATES);
+
+    let summary = trend.stats.as_ref().unwrap();
+    assert_eq!(summary.count, 7);
+    assert_eq!(summary.median, 10.5);
+    assert_eq!((summary.min, summary.max), (9.0, 100.0));
+    assert_eq!((summary.p25, summary.p75), (10.0, 11.5));
+
+    let flagged: Vec<(String, &[OutlierFlag])> = trend
+        .outliers()
+        .map(|point| (point.point.result.to_string(), point.flags.as_slice()))
+        .collect();
+    assert_eq!(
+        flagged,
+        vec![
+            ("12".to_string(), &[OutlierFlag::OutsideHistory][..]),
+            (
+                "100".to_string(),
+                &[OutlierFlag::Deviation, OutlierFlag::OutsideHistory][..]
+            ),
+        ]
+    );
+    let not_numeric = &trend.points[6];
+    assert_eq!((not_numeric.value, not_numeric.z_score), (None, None));
+
+    assert!(matches!(
+        manager.result_trend(1, 1, 0.0),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(manager.result_trend(2, 2, 3.0).unwrap().stats.is_none());
+}
+
+#[test]
+fn console_shows_the_series_and_outliers() {
+    let db = TestDb::new(10);
+    seed(&db);
+
+    let script = "4\np1\nnitratos\n3\n";
+    let mut output = Vec::new();
+    SiteConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("2024-03-01 1-1-03: 9\n"));
+    assert!(output
+        .contains("2024-06-01 1-1-06: 100 <- far from the mean, <- outside the previous range"));
+    assert!(output.contains("7 numeric results: mean 23.2143, median 10.5000"));
+    assert!(output.contains("2 results to check (more than 3 standard deviations"));
+}
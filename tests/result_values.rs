@@ -0,0 +1,203 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::result_point::ResultPoint;
+use rust_project::data::model::result_value::{BelowLimitPolicy, ResultValue};
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data_management::Finder;
+use rust_project::logic::compliance::{evaluate, ComplianceStatus};
+use rust_project::logic::result_manager::ResultManager;
+use rust_project::logic::session::Session;
+use rust_project::logic::trend::analyze;
+
+fn point(month: u32, result: &str, detection_limit: Option<f64>) -> ResultPoint {
+    let date = NaiveDate::from_ymd_opt(2024, month, 1).unwrap();
+    ResultPoint {
+        id_sample_result: month,
+        id_sample: month,
+        control_number: format!("2024-0-{:05}", month),
+        sampling_date: date,
+        analysis_date: date,
+        result: result.parse().unwrap(),
+        detection_limit,
+    }
+}
+
+#[test]
+fn results_are_parsed_and_formatted() {
+    let parsed = |text: &str| text.parse::<ResultValue>().unwrap();
+
+    assert_eq!(parsed(" 0.25 "), ResultValue::Numeric(0.25));
+    assert_eq!(parsed("<0.005"), ResultValue::BelowLimit(Some(0.005)));
+    assert_eq!(parsed("≤ 0.01"), ResultValue::BelowLimit(Some(0.01)));
+    assert_eq!(parsed("nd"), ResultValue::BelowLimit(None));
+    assert_eq!(parsed("No detectado"), ResultValue::BelowLimit(None));
+    assert_eq!(parsed("> 2400"), ResultValue::AboveRange(Some(2400.0)));
+    assert_eq!(parsed("TNTC"), ResultValue::AboveRange(None));
+    assert_eq!(
+        parsed("Ausente"),
+        ResultValue::Qualitative("Ausente".to_string())
+    );
+    assert_eq!(parsed("N/A"), ResultValue::NotDetermined);
+    assert_eq!(parsed(""), ResultValue::NotDetermined);
+
+    let formatted: Vec<String> = ["0.25", "< 0.005", "n.d.", ">=2400", "tntc", "Ausente", "-"]
+        .iter()
+        .map(|text| parsed(text).to_string())
+        .collect();
+    assert_eq!(
+        formatted,
+        ["0.25", "<0.005", "ND", ">2400", "TNTC", "Ausente", "NA"]
+    );
+    assert_eq!(
+        parsed("ND").with_detection_limit(Some(0.002)),
+        ResultValue::BelowLimit(Some(0.002))
+    );
+    assert!("median".parse::<BelowLimitPolicy>().is_err());
+    assert_eq!(
+        " Half ".parse::<BelowLimitPolicy>().unwrap(),
+        BelowLimitPolicy::Half
+    );
+}
+
+#[test]
+fn compliance_uses_what_is_known_of_the_value() {
+    let status = |text: &str, min: Option<f64>, max: Option<f64>, policy| {
+        evaluate(&text.parse().unwrap(), min, max, policy)
+    };
+    let exclude = BelowLimitPolicy::Exclude;
+
+    assert_eq!(
+        status("<0.005", None, Some(0.01), exclude),
+        ComplianceStatus::Compliant
+    );
+    assert_eq!(
+        status("<0.05", None, Some(0.01), exclude),
+        ComplianceStatus::Unknown
+    );
+    assert_eq!(
+        status("<0.05", None, Some(0.01), BelowLimitPolicy::Half),
+        ComplianceStatus::NonCompliant
+    );
+    assert_eq!(
+        status("<0.015", None, Some(0.01), BelowLimitPolicy::Half),
+        ComplianceStatus::Compliant
+    );
+    // El oxigeno disuelto debe estar arriba del minimo
+    assert_eq!(
+        status("<2", Some(4.0), None, exclude),
+        ComplianceStatus::NonCompliant
+    );
+    assert_eq!(
+        status(">2400", None, Some(1000.0), exclude),
+        ComplianceStatus::NonCompliant
+    );
+    assert_eq!(
+        status(">5", Some(4.0), None, exclude),
+        ComplianceStatus::Compliant
+    );
+    assert_eq!(
+        status(">5", None, Some(10.0), exclude),
+        ComplianceStatus::Unknown
+    );
+    assert_eq!(
+        status("TNTC", None, Some(1000.0), exclude),
+        ComplianceStatus::Unknown
+    );
+    assert_eq!(
+        status("ND", None, Some(0.01), exclude),
+        ComplianceStatus::Unknown
+    );
+    assert_eq!(
+        status("Ausente", None, Some(0.01), exclude),
+        ComplianceStatus::Unknown
+    );
+    assert_eq!(
+        status("0.2", None, None, exclude),
+        ComplianceStatus::Unknown
+    );
+}
+
+#[test]
+fn statistics_follow_the_below_limit_policy() {
+    let points = || {
+        vec![
+            point(1, "4", None),
+            point(2, "<2", None),
+            point(3, "6", None),
+            point(4, "ND", Some(1.0)),
+            point(5, "TNTC", None),
+        ]
+    };
+    let count_and_mean = |policy| {
+        let trend = analyze(points(), 3.0, policy);
+        trend.stats.map(|stats| (stats.count, stats.mean)).unwrap()
+    };
+
+    assert_eq!(count_and_mean(BelowLimitPolicy::Exclude), (2, 5.0));
+    assert_eq!(count_and_mean(BelowLimitPolicy::Zero), (4, 2.5));
+    assert_eq!(count_and_mean(BelowLimitPolicy::Half), (4, 2.875));
+    assert_eq!(count_and_mean(BelowLimitPolicy::Limit), (4, 3.25));
+
+    let trend = analyze(points(), 3.0, BelowLimitPolicy::Half);
+    assert_eq!(trend.points[3].value, Some(0.5));
+    assert_eq!(trend.points[4].value, None);
+}
+
+#[test]
+fn not_detected_takes_the_analysis_detection_limit() {
+    let db = TestDb::new(10);
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    let with_limit = analyses
+        .insert(&Analysis {
+            analysis_active: true,
+            analysis_name: "Arsenico".to_string(),
+            detection_limit: Some(0.001),
+            ..Analysis::default()
+        })
+        .unwrap();
+    let without_limit = analyses
+        .insert(&Analysis {
+            analysis_active: true,
+            analysis_name: "Coliformes".to_string(),
+            ..Analysis::default()
+        })
+        .unwrap();
+    assert_eq!(
+        analyses
+            .search_by_id(with_limit)
+            .unwrap()
+            .unwrap()
+            .detection_limit,
+        Some(0.001)
+    );
+
+    let mut manager = ResultManager::new(db.connector(), 10).with_session(Session::system());
+    let results = SampleResultRepo::new(db.connector(), 10);
+    let mut record = |id_analysis: u32, result: &str| {
+        let id = manager
+            .record(&SampleResult {
+                result_active: true,
+                result: result.parse().unwrap(),
+                analysis_date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+                id_analysis: Some(id_analysis),
+                ..SampleResult::default()
+            })
+            .unwrap();
+        results
+            .search_by_id(id)
+            .unwrap()
+            .unwrap()
+            .result
+            .to_string()
+    };
+
+    assert_eq!(record(with_limit, "ND"), "<0.001");
+    assert_eq!(record(with_limit, "<0.004"), "<0.004");
+    assert_eq!(record(without_limit, "ND"), "ND");
+    assert_eq!(record(without_limit, "TNTC"), "TNTC");
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::role::Role;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_status::SampleStatus;
+use rust_project::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use rust_project::data::table::Patch;
+use rust_project::data_management::{Finder, Repository};
+use rust_project::error::{Error, ErrorCode};
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::main_console_view::MainConsoleView;
+
+fn manager(db: &TestDb) -> SampleManager<SampleSearchCriteria> {
+    SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system())
+}
+
+// El historial guarda al usuario de la sesion que hizo el cambio
+fn signed_in(db: &TestDb, user: &str) -> SampleManager<SampleSearchCriteria> {
+    SampleManager::new(db.connector(), 10, ControlNumberPattern::default()).with_session(Session {
+        id_signatary: None,
+        user: user.to_string(),
+        role: Role::Admin,
+    })
+}
+
+fn sample(project: &str) -> Sample {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    Sample {
+        sample_active: true,
+        project: project.to_string(),
+        sampling_date: date,
+        reception: date,
+        ..Sample::default()
+    }
+}
+
+#[test]
+fn legal_transitions_follow_the_lifecycle() {
+    let db = TestDb::new(10);
+    let mut manager = manager(&db);
+    let id = manager
+        .register(&sample("Pozos"))
+        .unwrap()
+        .id_sample
+        .unwrap();
+
+    let mut ana = signed_in(&db, "Ana");
+    let mut beto = signed_in(&db, "Beto");
+    assert_eq!(
+        ana.start_analysis(id).unwrap().sample_status,
+        SampleStatus::InAnalysis
+    );
+    ana.complete_results(id).unwrap();
+    // La revision regresa la muestra a analisis
+    assert_eq!(
+        beto.reopen(id).unwrap().sample_status,
+        SampleStatus::InAnalysis
+    );
+    ana.complete_results(id).unwrap();
+    beto.review(id).unwrap();
+    beto.report(id).unwrap();
+    let archived = signed_in(&db, "Carla").archive(id).unwrap();
+    assert_eq!(archived.sample_status, SampleStatus::Archived);
+
+    let history = manager.history(id).unwrap();
+    let steps: Vec<(SampleStatus, SampleStatus, &str)> = history
+        .iter()
+        .map(|t| (t.from_status, t.to_status, t.actor.as_str()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            (SampleStatus::Received, SampleStatus::InAnalysis, "Ana"),
+            (
+                SampleStatus::InAnalysis,
+                SampleStatus::ResultsComplete,
+                "Ana"
+            ),
+            (
+                SampleStatus::ResultsComplete,
+                SampleStatus::InAnalysis,
+                "Beto"
+            ),
+            (
+                SampleStatus::InAnalysis,
+                SampleStatus::ResultsComplete,
+                "Ana"
+            ),
+            (
+                SampleStatus::ResultsComplete,
+                SampleStatus::Reviewed,
+                "Beto"
+            ),
+            (SampleStatus::Reviewed, SampleStatus::Reported, "Beto"),
+            (SampleStatus::Reported, SampleStatus::Archived, "Carla"),
+        ]
+    );
+}
+
+#[test]
+fn illegal_transitions_and_direct_edits_are_rejected() {
+    let db = TestDb::new(10);
+    let mut manager = manager(&db);
+    let id = manager
+        .register(&sample("Pozos"))
+        .unwrap()
+        .id_sample
+        .unwrap();
+
+    let error = manager.report(id).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::InvalidField);
+    assert!(error
+        .to_string()
+        .contains("The sample cannot go from received to reported"));
+    assert!(signed_in(&db, " ")
+        .start_analysis(id)
+        .unwrap_err()
+        .to_string()
+        .contains("The name of who makes the change must not be empty"));
+    assert!(matches!(
+        manager.start_analysis(99),
+        Err(Error::NotFound { id: Some(99), .. })
+    ));
+
+    let mut stored = manager.search_by_id(id).unwrap().unwrap();
+    stored.sample_status = SampleStatus::Reported;
+    assert!(manager
+        .modify(&stored)
+        .unwrap_err()
+        .to_string()
+        .contains("The status can only change through a lifecycle transition"));
+    let patch = Patch::new()
+        .set("sample_status", SampleStatus::Archived)
+        .unwrap();
+    assert!(manager.modify_many(&[stored.clone()], &patch).is_err());
+    assert!(manager
+        .add(&Sample {
+            sample_status: SampleStatus::Reviewed,
+            ..sample("Norias")
+        })
+        .is_err());
+
+    let stored = manager.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.sample_status, SampleStatus::Received);
+    assert!(manager.history(id).unwrap().is_empty());
+}
+
+#[test]
+fn transition_on_a_stale_sample_keeps_the_other_edit() {
+    let db = TestDb::new(10);
+    let mut manager = manager(&db);
+    let registered = manager.register(&sample("Pozos")).unwrap();
+    let id = registered.id_sample.unwrap();
+
+    manager.start_analysis(id).unwrap();
+    // Quien tenia la version anterior ya no puede sobrescribir el estado
+    let error = manager.modify(&registered).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidField { .. } | Error::Conflict { .. }
+    ));
+
+    let mut repo = SampleRepo::new(db.connector(), 10);
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.sample_status, SampleStatus::InAnalysis);
+    assert_eq!(stored.row_version, registered.row_version + 1);
+    let mut edited = stored.clone();
+    edited.project = "Pozos norte".to_string();
+    repo.modify(&edited).unwrap();
+}
+
+#[test]
+fn board_groups_active_samples_by_status() {
+    let db = TestDb::new(10);
+    let mut manager = manager(&db);
+    for project in ["Pozos", "Norias", "Presas"] {
+        manager.register(&sample(project)).unwrap();
+    }
+    manager.start_analysis(2).unwrap();
+    let mut dropped = manager.search_by_id(3).unwrap().unwrap();
+    manager.drop(&mut dropped).unwrap();
+
+    let board = manager.board().unwrap();
+    assert_eq!(board.len(), SampleStatus::ALL.len());
+    let projects = |status: SampleStatus| -> Vec<String> {
+        board
+            .iter()
+            .find(|(group, _)| *group == status)
+            .unwrap()
+            .1
+            .iter()
+            .map(|s| s.project.clone())
+            .collect()
+    };
+    assert_eq!(projects(SampleStatus::Received), vec!["Pozos"]);
+    assert_eq!(projects(SampleStatus::InAnalysis), vec!["Norias"]);
+    assert!(projects(SampleStatus::Archived).is_empty());
+}
+
+#[test]
+fn console_board_changes_status_and_shows_history() {
+    let db = TestDb::new(10);
+    let number = manager(&db)
+        .register(&sample("Pozos"))
+        .unwrap()
+        .control_number;
+
+    // muestras, cambiar estado a en analisis, tablero, historial, salir
+    let script = format!("5\n2\n{number}\n1\n1\n3\n{number}\n4\n4\n");
+    let mut output = Vec::new();
+    MainConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session {
+            id_signatary: None,
+            user: "Ana".to_string(),
+            role: Role::Analyst,
+        })
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Current status: Received"));
+    assert!(output.contains(&format!("Sample {} is now In analysis", number)));
+    assert!(output.contains("== Received (0) =="));
+    assert!(output.contains(&format!("== In analysis (1) ==\n  {} - Pozos", number)));
+    assert!(output.contains("Received -> In analysis by Ana"));
+}
@@ -0,0 +1,342 @@
+mod common;
+
+use std::io::Cursor;
+
+use chrono::{Local, NaiveDate};
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::frequency::Frequency;
+use rust_project::data::model::planned_visit::PlannedVisit;
+use rust_project::data::model::role::Role;
+use rust_project::data::model::sample_status::SampleStatus;
+use rust_project::data::model::sampler::Sampler;
+use rust_project::data::model::sampling_schedule::SamplingSchedule;
+use rust_project::data::model::signatary::Signatary;
+use rust_project::data::model::site::Site;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::sampler_repo::SamplerRepo;
+use rust_project::data::repo::signatary_repo::SignataryRepo;
+use rust_project::data::repo::site_repo::SiteRepo;
+use rust_project::error::Error;
+use rust_project::logic::control_number::ControlNumberPattern;
+use rust_project::logic::sample_manager::SampleManager;
+use rust_project::logic::schedule_manager::ScheduleManager;
+use rust_project::logic::session::Session;
+use rust_project::view::console_view::ConsoleView;
+use rust_project::view::schedule_console_view::ScheduleConsoleView;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn manager(db: &TestDb) -> ScheduleManager {
+    ScheduleManager::new(db.connector(), 10, ControlNumberPattern::default())
+        .with_session(Session::system())
+}
+
+// Sitio P1 del cliente 0, analisis Nitratos (1) y Dureza (2) y el muestreador
+// 1 con usuario "memo"
+fn seed(db: &TestDb) {
+    SiteRepo::new(db.connector(), 10)
+        .insert(&Site {
+            site_active: true,
+            site_key: "P1".to_string(),
+            site_name: "Pozo 1".to_string(),
+            latitude: "19.4".to_string(),
+            longitude: "-99.1".to_string(),
+            ..Site::default()
+        })
+        .unwrap();
+    let mut analyses = AnalysisRepo::new(db.connector(), 10);
+    for name in ["Nitratos", "Dureza"] {
+        analyses
+            .insert(&Analysis {
+                analysis_active: true,
+                analysis_name: name.to_string(),
+                ..Analysis::default()
+            })
+            .unwrap();
+    }
+    let id_signatary = SignataryRepo::new(db.connector(), 10)
+        .insert(&Signatary {
+            signatary_active: true,
+            first_name: "Memo".to_string(),
+            user: "memo".to_string(),
+            role: Role::Sampler,
+            ..Signatary::default()
+        })
+        .unwrap();
+    SamplerRepo::new(db.connector(), 10)
+        .insert(&Sampler {
+            sampler_active: true,
+            id_signatary,
+            ..Sampler::default()
+        })
+        .unwrap();
+}
+
+fn schedule(
+    frequency: Frequency,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+) -> SamplingSchedule {
+    SamplingSchedule {
+        schedule_active: true,
+        id_site: 1,
+        frequency,
+        starts_on,
+        ends_on,
+        project: "Monitoreo P1".to_string(),
+        ..SamplingSchedule::default()
+    }
+}
+
+#[test]
+fn frequencies_keep_the_day_of_the_month() {
+    let monthly: Vec<NaiveDate> = (0..4)
+        .map(|n| Frequency::Monthly.nth(date(2024, 1, 31), n).unwrap())
+        .collect();
+    assert_eq!(
+        monthly,
+        [
+            date(2024, 1, 31),
+            date(2024, 2, 29),
+            date(2024, 3, 31),
+            date(2024, 4, 30)
+        ]
+    );
+    assert_eq!(
+        Frequency::Quarterly.nth(date(2024, 11, 30), 1),
+        Some(date(2025, 2, 28))
+    );
+    assert_eq!(
+        Frequency::Biweekly.nth(date(2024, 12, 25), 2),
+        Some(date(2025, 1, 22))
+    );
+    assert_eq!(" Annual ".parse::<Frequency>().unwrap(), Frequency::Annual);
+    assert!("daily".parse::<Frequency>().is_err());
+}
+
+#[test]
+fn planning_creates_each_visit_once() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+
+    let errors = manager
+        .add_schedule(
+            &SamplingSchedule {
+                id_site: 9,
+                ..schedule(Frequency::Monthly, date(2024, 3, 1), Some(date(2024, 2, 1)))
+            },
+            &[],
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(errors.contains("The site 9 does not exist"));
+    assert!(errors.contains("the end date 2024-02-01 is before the start date 2024-03-01"));
+    assert!(errors.contains("the schedule needs at least one analysis"));
+
+    let monthly = manager
+        .add_schedule(
+            &schedule(
+                Frequency::Monthly,
+                date(2024, 1, 15),
+                Some(date(2024, 4, 30)),
+            ),
+            &[1, 2, 1],
+        )
+        .unwrap();
+    manager
+        .add_schedule(
+            &schedule(Frequency::Quarterly, date(2024, 2, 1), None),
+            &[2],
+        )
+        .unwrap();
+    let names: Vec<String> = manager
+        .analyses_of(monthly)
+        .unwrap()
+        .into_iter()
+        .map(|analysis| analysis.analysis_name)
+        .collect();
+    assert_eq!(names, ["Nitratos", "Dureza"]);
+
+    let planned: Vec<(u32, NaiveDate)> = manager
+        .plan(date(2024, 6, 30))
+        .unwrap()
+        .into_iter()
+        .map(|visit| (visit.id_schedule, visit.planned_date))
+        .collect();
+    assert_eq!(
+        planned,
+        [
+            (1, date(2024, 1, 15)),
+            (1, date(2024, 2, 15)),
+            (1, date(2024, 3, 15)),
+            (1, date(2024, 4, 15)),
+            (2, date(2024, 2, 1)),
+            (2, date(2024, 5, 1)),
+        ]
+    );
+    assert!(manager.plan(date(2024, 6, 30)).unwrap().is_empty());
+    assert_eq!(manager.plan(date(2024, 8, 1)).unwrap().len(), 1);
+}
+
+#[test]
+fn overdue_and_unassigned_visits() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    manager
+        .add_schedule(&schedule(Frequency::Monthly, date(2024, 1, 15), None), &[1])
+        .unwrap();
+    manager
+        .add_schedule(
+            &SamplingSchedule {
+                id_sampler: Some(1),
+                ..schedule(Frequency::Quarterly, date(2024, 3, 10), None)
+            },
+            &[2],
+        )
+        .unwrap();
+    manager.plan(date(2024, 6, 30)).unwrap();
+    let today = date(2024, 3, 1);
+
+    let dates = |visits: Vec<PlannedVisit>| -> Vec<NaiveDate> {
+        visits.into_iter().map(|visit| visit.planned_date).collect()
+    };
+    assert_eq!(
+        dates(manager.overdue(today).unwrap()),
+        [date(2024, 1, 15), date(2024, 2, 15)]
+    );
+    let upcoming = manager.upcoming(today, 10).unwrap();
+    assert_eq!(upcoming.len(), 1);
+    assert_eq!(upcoming[0].planned_date, date(2024, 3, 10));
+    assert_eq!(upcoming[0].site_key, "P1");
+    assert_eq!(upcoming[0].sampler_user.as_deref(), Some("memo"));
+    assert_eq!(
+        dates(manager.unassigned(today, 20).unwrap()),
+        [date(2024, 1, 15), date(2024, 2, 15), date(2024, 3, 15)]
+    );
+
+    let id_visit = manager.overdue(today).unwrap()[0].id_visit;
+    assert!(manager
+        .assign(id_visit, 5)
+        .unwrap_err()
+        .to_string()
+        .contains("The sampler 5 does not exist or is inactive"));
+    assert_eq!(manager.assign(id_visit, 1).unwrap().id_sampler, Some(1));
+    assert_eq!(
+        dates(manager.unassigned(today, 20).unwrap()),
+        [date(2024, 2, 15), date(2024, 3, 15)]
+    );
+}
+
+#[test]
+fn completing_a_visit_registers_its_sample() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let mut manager = manager(&db);
+    manager
+        .add_schedule(
+            &SamplingSchedule {
+                id_sampler: Some(1),
+                ..schedule(Frequency::Monthly, date(2024, 1, 15), None)
+            },
+            &[1],
+        )
+        .unwrap();
+    let id_visit = manager.plan(date(2024, 1, 31)).unwrap()[0]
+        .id_visit
+        .unwrap();
+
+    let mut sample = manager.prefill(id_visit).unwrap();
+    assert_eq!(
+        (
+            sample.id_site,
+            sample.id_sampler,
+            sample.sampling_date,
+            sample.project.as_str()
+        ),
+        (Some(1), Some(1), date(2024, 1, 15), "Monitoreo P1")
+    );
+    sample.sampling_date = date(2024, 1, 16);
+    sample.reception = date(2024, 1, 16);
+    sample.sampling_hour = "09:30".to_string();
+
+    let read_only = Session {
+        id_signatary: None,
+        user: "eva".to_string(),
+        role: Role::ReadOnly,
+    };
+    assert!(matches!(
+        ScheduleManager::new(db.connector(), 10, ControlNumberPattern::default())
+            .with_session(read_only)
+            .complete(id_visit, &sample),
+        Err(Error::Forbidden { .. })
+    ));
+
+    let sampler = Session {
+        id_signatary: Some(1),
+        user: "memo".to_string(),
+        role: Role::Sampler,
+    };
+    let mut manager = manager.with_session(sampler);
+    let registered = manager.complete(id_visit, &sample).unwrap();
+    assert_eq!(registered.control_number, "2024-0-00001");
+    assert_eq!(registered.sample_status, SampleStatus::Received);
+    assert_eq!(registered.sampling_date, date(2024, 1, 16));
+    assert_eq!(
+        manager.visit(id_visit).unwrap().id_sample,
+        registered.id_sample
+    );
+    let expected: Vec<String> =
+        SampleManager::new(db.connector(), 10, ControlNumberPattern::default())
+            .with_session(Session::system())
+            .expected_analyses(registered.id_sample.unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|analysis| analysis.analysis_name)
+            .collect();
+    assert_eq!(expected, ["Nitratos"]);
+
+    assert!(manager.overdue(date(2024, 3, 1)).unwrap().is_empty());
+    assert!(manager
+        .complete(id_visit, &sample)
+        .unwrap_err()
+        .to_string()
+        .contains(&format!("The visit {} already has its sample", id_visit)));
+}
+
+#[test]
+fn console_plans_and_completes_visits() {
+    let db = TestDb::new(10);
+    seed(&db);
+    let today = Local::now().date_naive();
+
+    // agregar programa mensual desde hoy sin fin ni muestreador, planear
+    // hasta hoy, proximas visitas, completar la visita 1, salir
+    let script = format!(
+        "1\nP1\n3\n{today}\n2\n2\nMonitoreo\nnitratos, Dureza\n2\n{today}\n3\n0\n6\n1\n\n10:00\n7\n"
+    );
+    let mut output = Vec::new();
+    ScheduleConsoleView::new(&db.config, Cursor::new(script), &mut output)
+        .with_session(Session::system())
+        .menu()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Schedule 1 added"));
+    assert!(output.contains("1 visits planned"));
+    assert!(output.contains("Visits in the next 0 days: 1"));
+    assert!(output.contains(&format!(
+        "  1) {} P1 Pozo 1 - no client - no sampler",
+        today
+    )));
+    assert!(output.contains(&format!(
+        "Site P1, project Monitoreo, planned for {}",
+        today
+    )));
+    assert!(output.contains("registered for visit 1"));
+    assert!(output.contains("Analyses to run: Nitratos, Dureza"));
+}
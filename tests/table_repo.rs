@@ -0,0 +1,138 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::standard_detail::StandardDetail;
+use rust_project::data::repo::sample_repo::{SampleRepo, SampleSearchCriteria};
+use rust_project::data::repo::standard_detail_repo::{
+    StandardDetailRepo, StandardDetailSearchCriteria,
+};
+use rust_project::data_management::{Finder, Repository};
+
+fn sample(control_number: &str, project: &str) -> Sample {
+    Sample {
+        id_sample: None,
+        sample_active: true,
+        control_number: control_number.to_string(),
+        project: project.to_string(),
+        sampling_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        sampling_hour: "09:30".to_string(),
+        reception: NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+        id_sampler: None,
+        id_site: None,
+        ..Sample::default()
+    }
+}
+
+#[test]
+fn insert_and_search_by_id_round_trip() {
+    let db = TestDb::new(10);
+    let mut repo = SampleRepo::new(db.connector(), 10);
+
+    let id = repo.insert(&sample("2024-0001", "Pozos")).unwrap();
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+
+    let mut expected = sample("2024-0001", "Pozos");
+    expected.id_sample = Some(id);
+    expected.created_at = stored.created_at;
+    expected.updated_at = stored.updated_at;
+    expected.row_version = 1;
+    assert_eq!(stored, expected);
+}
+
+#[test]
+fn modify_updates_changed_columns() {
+    let db = TestDb::new(10);
+    let mut repo = SampleRepo::new(db.connector(), 10);
+    let id = repo.insert(&sample("2024-0001", "Pozos")).unwrap();
+
+    let mut changed = repo.search_by_id(id).unwrap().unwrap();
+    changed.project = "Presas".to_string();
+    changed.reception = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+    repo.modify(&changed).unwrap();
+
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+    assert!(stored.updated_at > changed.updated_at);
+    changed.updated_at = stored.updated_at;
+    changed.row_version += 1;
+    assert_eq!(stored, changed);
+}
+
+#[test]
+fn drop_deactivates_and_delete_removes() {
+    let db = TestDb::new(10);
+    let mut repo = SampleRepo::new(db.connector(), 10);
+    let id = repo.insert(&sample("2024-0001", "Pozos")).unwrap();
+
+    let mut stored = repo.search_by_id(id).unwrap().unwrap();
+    repo.drop(&mut stored).unwrap();
+    assert!(!stored.sample_active);
+    assert!(!repo.search_by_id(id).unwrap().unwrap().sample_active);
+
+    repo.delete(&stored).unwrap();
+    assert!(repo.search_by_id(id).unwrap().is_none());
+}
+
+#[test]
+fn drop_without_active_column_deletes_the_row() {
+    let db = TestDb::new(10);
+    let mut repo = StandardDetailRepo::new(db.connector(), 10);
+    let detail = StandardDetail {
+        id_std_detail: None,
+        id_standard: None,
+        id_analysis: None,
+        min_value: None,
+        max_value: Some(0.5),
+        ..StandardDetail::default()
+    };
+    let id = repo.insert(&detail).unwrap();
+
+    let mut stored = repo.search_by_id(id).unwrap().unwrap();
+    repo.drop(&mut stored).unwrap();
+
+    let all = repo
+        .find_all(&StandardDetailSearchCriteria::default())
+        .unwrap();
+    assert!(all.is_empty());
+}
+
+#[test]
+fn search_by_combines_criteria_and_paginates() {
+    let db = TestDb::new(2);
+    let mut repo = SampleRepo::new(db.connector(), 2);
+    for (number, project) in [
+        ("A-1", "Pozos norte"),
+        ("A-2", "Pozos sur"),
+        ("A-3", "Pozos centro"),
+        ("B-1", "Presas"),
+    ] {
+        repo.insert(&sample(number, project)).unwrap();
+    }
+
+    let criteria = SampleSearchCriteria {
+        project: Some("Pozos".to_string()),
+        sample_active: Some(true),
+        ..Default::default()
+    };
+    let search = repo.search_by(&criteria, 2).unwrap();
+    let page: Vec<Sample> = serde_json::from_str(&search.result).unwrap();
+
+    assert_eq!(search.total_pages, 2);
+    assert_eq!(page.len(), 1);
+    assert!(page[0].project.starts_with("Pozos"));
+}
+
+#[test]
+fn criteria_values_are_bound_as_parameters() {
+    let db = TestDb::new(10);
+    let mut repo = SampleRepo::new(db.connector(), 10);
+    repo.insert(&sample("A-1", "Pozos")).unwrap();
+
+    let criteria = SampleSearchCriteria {
+        project: Some("' OR 1=1 --".to_string()),
+        ..Default::default()
+    };
+
+    assert!(repo.find_all(&criteria).unwrap().is_empty());
+}
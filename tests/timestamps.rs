@@ -0,0 +1,131 @@
+mod common;
+
+use chrono::{Duration, NaiveDate, Utc};
+use common::TestDb;
+use rust_project::data::model::client::Client;
+use rust_project::data::repo::client_repo::{ClientRepo, SearchCriteria};
+use rust_project::data::table::DateRange;
+use rust_project::data_management::{Finder, Repository};
+
+fn client(name: &str) -> Client {
+    Client {
+        client_active: true,
+        client_name: name.to_string(),
+        ..Client::default()
+    }
+}
+
+#[test]
+fn add_and_modify_fill_timestamps() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    let before = Utc::now();
+    let id = repo.insert(&client("Alfa")).unwrap();
+
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+    assert!(stored.created_at >= before && stored.created_at <= Utc::now());
+    assert_eq!(stored.created_at, stored.updated_at);
+
+    // Sin cambios reales no se toca updated_at, aunque se envien otras fechas
+    let mut unchanged = stored.clone();
+    unchanged.created_at = before - Duration::days(30);
+    unchanged.updated_at = before - Duration::days(30);
+    repo.modify(&unchanged).unwrap();
+    assert_eq!(repo.search_by_id(id).unwrap().unwrap(), stored);
+
+    let mut renamed = unchanged.clone();
+    renamed.client_name = "Beta".to_string();
+    repo.modify(&renamed).unwrap();
+    let modified = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(modified.created_at, stored.created_at);
+    assert!(modified.updated_at > stored.updated_at);
+
+    let mut dropped = modified.clone();
+    repo.drop(&mut dropped).unwrap();
+    let after_drop = repo.search_by_id(id).unwrap().unwrap();
+    assert!(after_drop.updated_at > modified.updated_at);
+    assert_eq!(after_drop, dropped);
+}
+
+#[test]
+fn rows_inserted_outside_the_repository_get_timestamps() {
+    let db = TestDb::new(10);
+    let conn = db.connector().get_connection().unwrap();
+    conn.execute(
+        "INSERT INTO Client (client_active, client_name) VALUES (1, 'Externo')",
+        [],
+    )
+    .unwrap();
+
+    let repo = ClientRepo::new(db.connector(), 10);
+    let stored = repo.search_by_id(1).unwrap().unwrap();
+    assert!(Utc::now() - stored.created_at < Duration::minutes(1));
+}
+
+#[test]
+fn date_range_filters() {
+    let db = TestDb::new(10);
+    let mut repo = ClientRepo::new(db.connector(), 10);
+    for name in ["Alfa", "Beta", "Gamma"] {
+        repo.add(&client(name)).unwrap();
+    }
+
+    // Alfa se dio de alta en marzo de 2024
+    let conn = db.connector().get_connection().unwrap();
+    conn.execute(
+        "UPDATE Client SET created_at = '2024-03-31 23:59:59.5+00:00' WHERE client_name = 'Alfa'",
+        [],
+    )
+    .unwrap();
+
+    let names = |criteria: &SearchCriteria| -> Vec<String> {
+        repo.find_all(criteria)
+            .unwrap()
+            .into_iter()
+            .map(|client| client.client_name)
+            .collect()
+    };
+
+    let march = SearchCriteria {
+        created_at: DateRange::month(2024, 3),
+        ..SearchCriteria::default()
+    };
+    assert_eq!(names(&march), ["Alfa"]);
+
+    let april = SearchCriteria {
+        created_at: DateRange::month(2024, 4),
+        ..SearchCriteria::default()
+    };
+    assert!(names(&april).is_empty());
+
+    let today = Utc::now().date_naive();
+    let recent = SearchCriteria {
+        created_at: Some(DateRange::days(today - Duration::days(1), today)),
+        client_name: Some("a".to_string()),
+        ..SearchCriteria::default()
+    };
+    assert_eq!(names(&recent), ["Beta", "Gamma"]);
+
+    let updated = SearchCriteria {
+        updated_at: Some(DateRange::new(Some(Utc::now() - Duration::hours(1)), None)),
+        ..SearchCriteria::default()
+    };
+    assert_eq!(names(&updated).len(), 3);
+}
+
+#[test]
+fn month_ranges() {
+    let february = DateRange::month(2024, 2).unwrap();
+    let day = |d: u32, m: u32| {
+        NaiveDate::from_ymd_opt(2024, m, d)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+    };
+    assert!(february.contains(&day(1, 2)));
+    assert!(february.contains(&(day(1, 3) - Duration::nanoseconds(1))));
+    assert!(!february.contains(&day(1, 3)));
+    assert!(!february.contains(&(day(1, 2) - Duration::seconds(1))));
+    assert_eq!(DateRange::month(2024, 13), None);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use rust_project::data::model::analysis::Analysis;
+use rust_project::data::model::result_limits::ResultLimits;
+use rust_project::data::model::result_value::BelowLimitPolicy;
+use rust_project::data::model::sample::Sample;
+use rust_project::data::model::sample_result::SampleResult;
+use rust_project::data::model::standard::Standard;
+use rust_project::data::repo::analysis_repo::AnalysisRepo;
+use rust_project::data::repo::sample_repo::SampleRepo;
+use rust_project::data::repo::sample_result_repo::SampleResultRepo;
+use rust_project::data::repo::standard_repo::StandardRepo;
+use rust_project::data_management::Finder;
+use rust_project::error::{Error, ErrorCode};
+use rust_project::i18n::{self, Locale};
+use rust_project::logic::compliance::{evaluate_in_units, sample_status, ComplianceStatus};
+use rust_project::logic::result_manager::ResultManager;
+use rust_project::logic::session::Session;
+use rust_project::logic::units::{self, Dimension};
+
+fn limits(result: &str, result_unit: Option<&str>, max_value: f64) -> ResultLimits {
+    ResultLimits {
+        id_sample: 1,
+        control_number: "2024-0-00001".to_string(),
+        sampling_date: NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+        id_analysis: Some(1),
+        result: result.parse().unwrap(),
+        result_unit: result_unit.map(str::to_string),
+        std_units: Some("mg/L".to_string()),
+        min_value: None,
+        max_value: Some(max_value),
+        detection_limit: None,
+    }
+}
+
+fn result(value: &str, unit: Option<&str>) -> SampleResult {
+    SampleResult {
+        result_active: true,
+        result: value.parse().unwrap(),
+        result_unit: unit.map(str::to_string),
+        analysis_date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+        id_analysis: Some(1),
+        id_standard: Some(1),
+        id_sample: Some(1),
+        ..SampleResult::default()
+    }
+}
+
+#[test]
+fn catalog_finds_units_and_converts_within_a_dimension() {
+    i18n::set_locale(Locale::En);
+    assert_eq!(units::find("ug/l").unwrap().symbol, "µg/L");
+    assert_eq!(units::find(" NMP/100mL ").unwrap().symbol, "NMP/100 mL");
+    assert_eq!(
+        units::find("ppm").unwrap().dimension,
+        Dimension::MassConcentration
+    );
+    assert!(units::find("furlong").is_none());
+
+    assert_eq!(units::convert_text(25.0, "µg/L", "mg/L").unwrap(), 0.025);
+    assert_eq!(units::convert_text(0.3, "mg/L", "µg/L").unwrap(), 300.0);
+    assert_eq!(units::convert_text(1.0, "S/m", "µS/cm").unwrap(), 10000.0);
+    assert_eq!(units::convert_text(100.0, "°C", "°F").unwrap(), 212.0);
+    assert_eq!(units::convert_text(0.0, "°C", "K").unwrap(), 273.15);
+    assert_eq!(units::convert_text(7.2, "pH", "upH").unwrap(), 7.2);
+
+    let error = units::convert_text(1.0, "mg/L", "µS/cm").unwrap_err();
+    assert_eq!(error.code(), ErrorCode::InvalidField);
+    assert!(error
+        .to_string()
+        .contains("A result in mg/L cannot be compared with a limit in µS/cm"));
+    assert!(units::convert_text(1.0, "NMP/100 mL", "UFC/100 mL").is_err());
+    assert!(units::convert_text(1.0, "mg/L", "furlong")
+        .unwrap_err()
+        .to_string()
+        .contains("The unit \"furlong\" is not in the units catalog"));
+}
+
+#[test]
+fn results_are_recorded_in_the_standard_unit() {
+    let db = TestDb::new(10);
+    StandardRepo::new(db.connector(), 10)
+        .insert(&Standard {
+            std_active: true,
+            std_name: "NOM-127".to_string(),
+            units: "mg/l".to_string(),
+            ..Standard::default()
+        })
+        .unwrap();
+    AnalysisRepo::new(db.connector(), 10)
+        .insert(&Analysis {
+            analysis_active: true,
+            analysis_name: "Nitratos".to_string(),
+            ..Analysis::default()
+        })
+        .unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+    SampleRepo::new(db.connector(), 10)
+        .insert(&Sample {
+            sample_active: true,
+            sampling_date: date,
+            reception: date,
+            ..Sample::default()
+        })
+        .unwrap();
+    let mut manager = ResultManager::new(db.connector(), 10).with_session(Session::system());
+    let repo = SampleResultRepo::new(db.connector(), 10);
+
+    let id = manager.record(&result("25", Some("ug/L"))).unwrap();
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(stored.result.to_string(), "0.025");
+    assert_eq!(stored.result_unit.as_deref(), Some("mg/L"));
+
+    let id = manager.record(&result("ND", Some("µg/L"))).unwrap();
+    let stored = repo.search_by_id(id).unwrap().unwrap();
+    assert_eq!(
+        (stored.result.to_string(), stored.result_unit.as_deref()),
+        ("ND".to_string(), Some("mg/L"))
+    );
+
+    let id = manager.record(&result("0.4", None)).unwrap();
+    assert_eq!(repo.search_by_id(id).unwrap().unwrap().result_unit, None);
+
+    assert!(matches!(
+        manager.record(&result("3", Some("NTU"))),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(manager.record(&result("ND", Some("NTU"))).is_err());
+    assert!(matches!(
+        manager.record(&SampleResult {
+            id_standard: Some(9),
+            ..result("1", Some("mg/L"))
+        }),
+        Err(Error::NotFound { id: Some(9), .. })
+    ));
+}
+
+#[test]
+fn compliance_converts_into_the_standard_unit() {
+    assert_eq!(
+        evaluate_in_units(&limits("30", Some("µg/L"), 0.05), BelowLimitPolicy::Exclude).unwrap(),
+        ComplianceStatus::Compliant
+    );
+    assert_eq!(
+        evaluate_in_units(&limits("80", Some("µg/L"), 0.05), BelowLimitPolicy::Exclude).unwrap(),
+        ComplianceStatus::NonCompliant
+    );
+    // Sin unidad el resultado ya esta en la de la norma
+    assert_eq!(
+        evaluate_in_units(&limits("30", None, 0.05), BelowLimitPolicy::Exclude).unwrap(),
+        ComplianceStatus::NonCompliant
+    );
+    assert_eq!(
+        evaluate_in_units(&limits("ND", Some("NTU"), 0.05), BelowLimitPolicy::Exclude).unwrap(),
+        ComplianceStatus::Unknown
+    );
+
+    let results = [
+        limits("30", Some("µg/L"), 0.05),
+        limits("5", Some("NTU"), 0.05),
+    ];
+    assert!(sample_status(&results, BelowLimitPolicy::Exclude).is_err());
+}